@@ -9,13 +9,24 @@ use clap::ArgAction;
 use clap::ArgMatches;
 use clap::Command;
 use clap::ValueHint;
+use delphinus_zkwasm::circuits::config::MAX_K;
+use delphinus_zkwasm::circuits::config::MIN_K;
+use delphinus_zkwasm::circuits::config::MIN_SMALL_K;
 
+use crate::args::FlamegraphFormat;
+use crate::args::FlamegraphWeight;
 use crate::args::HostMode;
+use crate::command::CheckContextArg;
+use crate::command::DebugArg;
 use crate::command::DryRunArg;
+use crate::command::EstimateArg;
+use crate::command::ListPhantomFunctionsArg;
+use crate::command::ProfileArg;
 use crate::command::ProveArg;
 use crate::command::RunningArg;
 use crate::command::SetupArg;
 use crate::command::Subcommands;
+use crate::command::TraceDiffArg;
 use crate::command::VerifyArg;
 use crate::ZkWasmCli;
 
@@ -87,7 +98,7 @@ impl ArgBuilder<Vec<String>> for PrivateInputsArg {
 struct ContextInputsArg;
 impl ArgBuilder<Vec<String>> for ContextInputsArg {
     fn builder() -> Arg<'static> {
-        arg!(--ctxin <CONTEXT_INPUT> ... "Context inputs with format value:type where type=i64|bytes|bytes-packed, values can be separated by `,` or multiple occurrence of `--ctxin`")
+        arg!(--ctxin <CONTEXT_INPUT> ... "Context inputs with format value:type where type=i64|bytes|bytes-packed|file|context, `context` takes a file written by `--ctxout`, values can be separated by `,` or multiple occurrence of `--ctxin`")
        .takes_value(true).value_delimiter(',').required(false)
     }
 
@@ -100,10 +111,53 @@ impl ArgBuilder<Vec<String>> for ContextInputsArg {
     }
 }
 
+struct IndexedInputsArg;
+impl ArgBuilder<Vec<String>> for IndexedInputsArg {
+    fn builder() -> Arg<'static> {
+        arg!(--indexed <INDEXED_INPUT> ... "Private inputs read by `wasm_indexed_input(index)` with format value:type where type=i64|bytes|bytes-packed|file")
+       .takes_value(true).value_delimiter(',').required(false)
+    }
+
+    fn parse(matches: &ArgMatches) -> Vec<String> {
+        matches
+            .get_many::<String>("indexed")
+            .unwrap_or_default()
+            .map(|s| s.to_string())
+            .collect()
+    }
+}
+
+struct IoSchemaArg;
+impl ArgBuilder<Option<PathBuf>> for IoSchemaArg {
+    fn builder() -> Arg<'static> {
+        arg!(--schema [SCHEMA] "Path to the JSON schema describing the fields of the input streams and of the outputs")
+            .value_parser(value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+    }
+
+    fn parse(matches: &ArgMatches) -> Option<PathBuf> {
+        matches.get_one::<PathBuf>("schema").cloned()
+    }
+}
+
+struct InputsArg;
+impl ArgBuilder<Option<PathBuf>> for InputsArg {
+    fn builder() -> Arg<'static> {
+        arg!(--inputs [INPUTS] "Path to the JSON values of the fields declared by `--schema`, they are encoded before the values of `--public`, `--private` and `--ctxin`")
+            .value_parser(value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+            .requires("schema")
+    }
+
+    fn parse(matches: &ArgMatches) -> Option<PathBuf> {
+        matches.get_one::<PathBuf>("inputs").cloned()
+    }
+}
+
 struct ContextOutputArg;
 impl ArgBuilder<Option<String>> for ContextOutputArg {
     fn builder() -> Arg<'static> {
-        arg!(--ctxout [CONTEXT_OUTPUT] "Path to context output")
+        arg!(--ctxout [CONTEXT_OUTPUT] "Path to write the context output along with the image checksum and the hash of the context inputs")
     }
 
     fn parse(matches: &ArgMatches) -> Option<String> {
@@ -111,6 +165,108 @@ impl ArgBuilder<Option<String>> for ContextOutputArg {
     }
 }
 
+struct GuestLogArg;
+impl ArgBuilder<Option<String>> for GuestLogArg {
+    fn builder() -> Arg<'static> {
+        arg!(--"guest-log" [GUEST_LOG] "Path to write the logs emitted by the guest through `wasm_log`")
+    }
+
+    fn parse(matches: &ArgMatches) -> Option<String> {
+        matches.get_one("guest-log").cloned()
+    }
+}
+
+struct HostModeArg;
+impl ArgBuilder<HostMode> for HostModeArg {
+    fn builder() -> Arg<'static> {
+        arg!(
+            --host <HOST_MODE> "Specify execution host environment for the runtime"
+        )
+        .default_value("default")
+        .value_parser(value_parser!(HostMode))
+        .required(false)
+    }
+
+    fn parse(matches: &ArgMatches) -> HostMode {
+        *matches.get_one::<HostMode>("host").unwrap()
+    }
+}
+
+struct PhantomFunctionsArg;
+impl ArgBuilder<Vec<String>> for PhantomFunctionsArg {
+    fn builder() -> Arg<'static> {
+        arg!(
+            --phantom <PHANTOM_FUNCTIONS> "Specify phantom functions whose body will be ignored in the circuit"
+        ).takes_value(true)
+        .value_delimiter(',')
+        .required(false)
+    }
+
+    fn parse(matches: &ArgMatches) -> Vec<String> {
+        matches
+            .get_many::<String>("phantom")
+            .unwrap_or_default()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+    }
+}
+
+struct JsonOutputArg;
+impl ArgBuilder<bool> for JsonOutputArg {
+    fn builder() -> Arg<'static> {
+        arg!(--json "Print the report in JSON").action(ArgAction::SetTrue)
+    }
+
+    fn parse(matches: &ArgMatches) -> bool {
+        matches.get_flag("json")
+    }
+}
+
+struct FlamegraphArg;
+impl ArgBuilder<Option<PathBuf>> for FlamegraphArg {
+    fn builder() -> Arg<'static> {
+        arg!(--flamegraph [FLAMEGRAPH] "Path to write the flamegraph of the execution")
+            .value_parser(value_parser!(PathBuf))
+            .value_hint(ValueHint::FilePath)
+    }
+
+    fn parse(matches: &ArgMatches) -> Option<PathBuf> {
+        matches.get_one::<PathBuf>("flamegraph").cloned()
+    }
+}
+
+struct FlamegraphFormatArg;
+impl ArgBuilder<FlamegraphFormat> for FlamegraphFormatArg {
+    fn builder() -> Arg<'static> {
+        arg!(--"flamegraph-format" <FORMAT> "Format of the flamegraph")
+            .default_value("folded")
+            .value_parser(value_parser!(FlamegraphFormat))
+            .required(false)
+    }
+
+    fn parse(matches: &ArgMatches) -> FlamegraphFormat {
+        *matches
+            .get_one::<FlamegraphFormat>("flamegraph-format")
+            .unwrap()
+    }
+}
+
+struct FlamegraphWeightArg;
+impl ArgBuilder<FlamegraphWeight> for FlamegraphWeightArg {
+    fn builder() -> Arg<'static> {
+        arg!(--"flamegraph-weight" <WEIGHT> "Cost each instruction contributes to its frame in the flamegraph")
+            .default_value("etable")
+            .value_parser(value_parser!(FlamegraphWeight))
+            .required(false)
+    }
+
+    fn parse(matches: &ArgMatches) -> FlamegraphWeight {
+        *matches
+            .get_one::<FlamegraphWeight>("flamegraph-weight")
+            .unwrap()
+    }
+}
+
 struct FileBackendArg;
 impl ArgBuilder<bool> for FileBackendArg {
     fn builder() -> Arg<'static> {
@@ -140,24 +296,11 @@ fn setup_command() -> Command<'static> {
         .arg(
             arg!(-k <K> "Size of the circuit.")
                 .default_value("22")
-                .value_parser(value_parser!(u32).range(18..23))
+                .value_parser(value_parser!(u32).range(MIN_SMALL_K as i64..=MAX_K as i64))
                 .required(false),
         )
-        .arg(
-            arg!(
-                --host <HOST_MODE> "Specify execution host environment for the runtime"
-            )
-            .default_value("default")
-            .value_parser(value_parser!(HostMode))
-            .required(false),
-        )
-        .arg(
-            arg!(
-                --phantom <PHANTOM_FUNCTIONS> "Specify phantom functions whose body will be ignored in the circuit"
-            ).takes_value(true)
-            .value_delimiter(',')
-            .required(false)
-        );
+        .arg(HostModeArg::builder())
+        .arg(PhantomFunctionsArg::builder());
 
     let command = if cfg!(not(feature = "uniform-circuit")) {
         command.arg(WasmImageArg::builder())
@@ -175,10 +318,38 @@ fn dry_run_command() -> Command<'static> {
         .arg(PublicInputsArg::builder())
         .arg(PrivateInputsArg::builder())
         .arg(ContextInputsArg::builder())
+        .arg(IndexedInputsArg::builder())
+        .arg(IoSchemaArg::builder())
+        .arg(InputsArg::builder())
         .arg(ContextOutputArg::builder())
+        .arg(GuestLogArg::builder())
         .arg(OutputDirArg::builder())
 }
 
+fn profile_command() -> Command<'static> {
+    Command::new("profile")
+        .about("Execute the Wasm image and report table costs per opcode class and per function")
+        .arg(WasmImageArg::builder().required(true))
+        .arg(PublicInputsArg::builder())
+        .arg(PrivateInputsArg::builder())
+        .arg(ContextInputsArg::builder())
+        .arg(IndexedInputsArg::builder())
+        .arg(JsonOutputArg::builder())
+        .arg(FlamegraphArg::builder())
+        .arg(FlamegraphFormatArg::builder())
+        .arg(FlamegraphWeightArg::builder())
+}
+
+fn debug_command() -> Command<'static> {
+    Command::new("debug")
+        .about("Execute the Wasm image and step through its trace interactively")
+        .arg(WasmImageArg::builder().required(true))
+        .arg(PublicInputsArg::builder())
+        .arg(PrivateInputsArg::builder())
+        .arg(ContextInputsArg::builder())
+        .arg(IndexedInputsArg::builder())
+}
+
 fn prove_command() -> Command<'static> {
     Command::new("prove")
         .about("Execute the Wasm image and generate a proof")
@@ -186,12 +357,72 @@ fn prove_command() -> Command<'static> {
         .arg(PublicInputsArg::builder())
         .arg(PrivateInputsArg::builder())
         .arg(ContextInputsArg::builder())
+        .arg(IndexedInputsArg::builder())
+        .arg(IoSchemaArg::builder())
+        .arg(InputsArg::builder())
         .arg(ContextOutputArg::builder())
+        .arg(GuestLogArg::builder())
         .arg(OutputDirArg::builder())
         .arg(MockTestArg::builder())
         .arg(FileBackendArg::builder())
 }
 
+fn estimate_command() -> Command<'static> {
+    Command::new("estimate")
+        .about("Execute the Wasm image on sample inputs and recommend a circuit size K")
+        .arg(WasmImageArg::builder().required(true))
+        .arg(HostModeArg::builder())
+        .arg(PhantomFunctionsArg::builder())
+        .arg(PublicInputsArg::builder())
+        .arg(PrivateInputsArg::builder())
+        .arg(ContextInputsArg::builder())
+        .arg(IndexedInputsArg::builder())
+        .arg(
+            arg!(--"min-k" <MIN_K> "The smallest candidate K, default to the smallest K of the default layout.")
+                .value_parser(value_parser!(u32).range(MIN_SMALL_K as i64..=MAX_K as i64))
+                .required(false),
+        )
+        .arg(
+            arg!(--"max-k" <MAX_K> "The largest candidate K, default to the maximal supported K.")
+                .value_parser(value_parser!(u32).range(MIN_SMALL_K as i64..=MAX_K as i64))
+                .required(false),
+        )
+        .arg(JsonOutputArg::builder())
+}
+
+fn phantom_functions_command() -> Command<'static> {
+    Command::new("phantom-functions")
+        .about("List the functions the phantom function patterns of the setup resolve to in the Wasm image")
+        .arg(WasmImageArg::builder().required(true))
+        .arg(JsonOutputArg::builder())
+}
+
+fn trace_diff_command() -> Command<'static> {
+    Command::new("trace-diff")
+        .about("Compare two executions saved in trace directories and report where they diverge")
+        .arg(
+            arg!(<TRACE_A> "Trace directory of the first execution, e.g. <OUTPUT>/traces")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::DirPath),
+        )
+        .arg(
+            arg!(<TRACE_B> "Trace directory of the second execution")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::DirPath),
+        )
+        .arg(JsonOutputArg::builder())
+}
+
+fn check_context_command() -> Command<'static> {
+    Command::new("check-context")
+        .about("Check that context files written by `--ctxout` chain runs of the image, each one continuing from the previous one")
+        .arg(
+            arg!(<CONTEXT> ... "Context files, in the order of the runs")
+                .value_parser(value_parser!(PathBuf))
+                .value_hint(ValueHint::FilePath),
+        )
+}
+
 fn verify_command() -> Command<'static> {
     Command::new("verify")
         .about("Verify the proof")
@@ -210,8 +441,14 @@ pub(crate) fn app() -> App<'static> {
         )
         .subcommand(setup_command())
         .subcommand(dry_run_command())
+        .subcommand(profile_command())
+        .subcommand(debug_command())
         .subcommand(prove_command())
         .subcommand(verify_command())
+        .subcommand(estimate_command())
+        .subcommand(phantom_functions_command())
+        .subcommand(trace_diff_command())
+        .subcommand(check_context_command())
         .subcommand_required(true)
 }
 
@@ -219,12 +456,8 @@ impl Into<SetupArg> for &ArgMatches {
     fn into(self) -> SetupArg {
         SetupArg {
             k: *self.get_one::<u32>("K").unwrap(),
-            host_mode: *self.get_one::<HostMode>("host").unwrap(),
-            phantom_functions: self
-                .get_many::<String>("phantom")
-                .unwrap_or_default()
-                .map(|v| v.to_string())
-                .collect::<Vec<_>>(),
+            host_mode: HostModeArg::parse(self),
+            phantom_functions: PhantomFunctionsArg::parse(self),
             wasm_image: WasmImageArg::parse(self),
         }
     }
@@ -237,7 +470,11 @@ impl Into<RunningArg> for &ArgMatches {
             public_inputs: PublicInputsArg::parse(self),
             private_inputs: PrivateInputsArg::parse(self),
             context_inputs: ContextInputsArg::parse(self),
+            indexed_inputs: IndexedInputsArg::parse(self),
+            schema: IoSchemaArg::parse(self),
+            inputs: InputsArg::parse(self),
             context_output: ContextOutputArg::parse(self),
+            guest_log: GuestLogArg::parse(self),
         }
     }
 }
@@ -251,6 +488,34 @@ impl Into<DryRunArg> for &ArgMatches {
     }
 }
 
+impl Into<ProfileArg> for &ArgMatches {
+    fn into(self) -> ProfileArg {
+        ProfileArg {
+            wasm_image: WasmImageArg::parse(self).unwrap(),
+            public_inputs: PublicInputsArg::parse(self),
+            private_inputs: PrivateInputsArg::parse(self),
+            context_inputs: ContextInputsArg::parse(self),
+            indexed_inputs: IndexedInputsArg::parse(self),
+            json: JsonOutputArg::parse(self),
+            flamegraph: FlamegraphArg::parse(self),
+            flamegraph_format: FlamegraphFormatArg::parse(self),
+            flamegraph_weight: FlamegraphWeightArg::parse(self),
+        }
+    }
+}
+
+impl Into<DebugArg> for &ArgMatches {
+    fn into(self) -> DebugArg {
+        DebugArg {
+            wasm_image: WasmImageArg::parse(self).unwrap(),
+            public_inputs: PublicInputsArg::parse(self),
+            private_inputs: PrivateInputsArg::parse(self),
+            context_inputs: ContextInputsArg::parse(self),
+            indexed_inputs: IndexedInputsArg::parse(self),
+        }
+    }
+}
+
 impl Into<ProveArg> for &ArgMatches {
     fn into(self) -> ProveArg {
         ProveArg {
@@ -271,13 +536,69 @@ impl Into<VerifyArg> for &ArgMatches {
     }
 }
 
+impl Into<EstimateArg> for &ArgMatches {
+    fn into(self) -> EstimateArg {
+        EstimateArg {
+            wasm_image: WasmImageArg::parse(self).unwrap(),
+            host_mode: HostModeArg::parse(self),
+            phantom_functions: PhantomFunctionsArg::parse(self),
+            public_inputs: PublicInputsArg::parse(self),
+            private_inputs: PrivateInputsArg::parse(self),
+            context_inputs: ContextInputsArg::parse(self),
+            indexed_inputs: IndexedInputsArg::parse(self),
+            min_k: self.get_one::<u32>("min-k").cloned().unwrap_or(MIN_K),
+            max_k: self.get_one::<u32>("max-k").cloned().unwrap_or(MAX_K),
+            json: JsonOutputArg::parse(self),
+        }
+    }
+}
+
+impl Into<ListPhantomFunctionsArg> for &ArgMatches {
+    fn into(self) -> ListPhantomFunctionsArg {
+        ListPhantomFunctionsArg {
+            wasm_image: WasmImageArg::parse(self).unwrap(),
+            json: JsonOutputArg::parse(self),
+        }
+    }
+}
+
+impl Into<TraceDiffArg> for &ArgMatches {
+    fn into(self) -> TraceDiffArg {
+        TraceDiffArg {
+            trace_a: self.get_one::<PathBuf>("TRACE_A").cloned().unwrap(),
+            trace_b: self.get_one::<PathBuf>("TRACE_B").cloned().unwrap(),
+            json: JsonOutputArg::parse(self),
+        }
+    }
+}
+
+impl Into<CheckContextArg> for &ArgMatches {
+    fn into(self) -> CheckContextArg {
+        CheckContextArg {
+            contexts: self
+                .get_many::<PathBuf>("CONTEXT")
+                .unwrap()
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
 impl Into<ZkWasmCli> for ArgMatches {
     fn into(self) -> ZkWasmCli {
         let subcommand = match self.subcommand() {
             Some(("setup", sub_matches)) => Subcommands::Setup(sub_matches.into()),
             Some(("dry-run", sub_matches)) => Subcommands::DryRun(sub_matches.into()),
+            Some(("profile", sub_matches)) => Subcommands::Profile(sub_matches.into()),
+            Some(("debug", sub_matches)) => Subcommands::Debug(sub_matches.into()),
             Some(("prove", sub_matches)) => Subcommands::Prove(sub_matches.into()),
             Some(("verify", sub_matches)) => Subcommands::Verify(sub_matches.into()),
+            Some(("estimate", sub_matches)) => Subcommands::Estimate(sub_matches.into()),
+            Some(("phantom-functions", sub_matches)) => {
+                Subcommands::PhantomFunctions(sub_matches.into())
+            }
+            Some(("trace-diff", sub_matches)) => Subcommands::TraceDiff(sub_matches.into()),
+            Some(("check-context", sub_matches)) => Subcommands::CheckContext(sub_matches.into()),
             _ => unreachable!("unknown subcommand"),
         };
 
@@ -1,3 +1,4 @@
+use delphinus_zkwasm::profile::flamegraph::FrameWeight;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -10,3 +11,32 @@ pub enum HostMode {
     /// Wasm Host Environment with more Zk plugins
     STANDARD,
 }
+
+#[derive(clap::ArgEnum, Copy, Clone, Debug, Default)]
+pub enum FlamegraphFormat {
+    /// Folded stacks for flamegraph.pl and inferno
+    #[default]
+    FOLDED,
+
+    /// Chrome trace-event JSON
+    CHROME,
+}
+
+#[derive(clap::ArgEnum, Copy, Clone, Debug, Default)]
+pub enum FlamegraphWeight {
+    /// Rows in the event table
+    #[default]
+    ETABLE,
+
+    /// Entries in the memory table
+    MEMORY,
+}
+
+impl From<FlamegraphWeight> for FrameWeight {
+    fn from(weight: FlamegraphWeight) -> Self {
+        match weight {
+            FlamegraphWeight::ETABLE => FrameWeight::EtableRows,
+            FlamegraphWeight::MEMORY => FrameWeight::MemoryOps,
+        }
+    }
+}
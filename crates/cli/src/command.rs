@@ -2,8 +2,11 @@ use std::fs::File;
 use std::fs::OpenOptions;
 use std::fs::{self};
 use std::io::Read;
+use std::path::Path;
 use std::path::PathBuf;
 
+use crate::args::FlamegraphFormat;
+use crate::args::FlamegraphWeight;
 use crate::config::CircuitDataConfig;
 use crate::config::CircuitDataMd5;
 use crate::TRIVIAL_WASM;
@@ -11,9 +14,13 @@ use clap::Args;
 use console::style;
 use delphinus_zkwasm::checksum::ImageCheckSum;
 use delphinus_zkwasm::circuits::ZkWasmCircuit;
+use delphinus_zkwasm::loader::estimate::estimate_k;
+use delphinus_zkwasm::loader::estimate::ProvingCostModel;
 use delphinus_zkwasm::loader::ZkWasmLoader;
+use delphinus_zkwasm::runtime::host::default_env::ExecutionArg;
 use delphinus_zkwasm::runtime::host::HostEnvBuilder;
 use delphinus_zkwasm::runtime::monitor::table_monitor::TableMonitor;
+use delphinus_zkwasm::runtime::trace_diff::diff_trace_dirs;
 use halo2_proofs::arithmetic::CurveAffine;
 use halo2_proofs::pairing::bn256::Bn256;
 use halo2_proofs::pairing::bn256::G1Affine;
@@ -21,6 +28,12 @@ use halo2_proofs::plonk::keygen_vk;
 use halo2_proofs::plonk::Circuit;
 use halo2_proofs::plonk::CircuitData;
 use halo2_proofs::poly::commitment::Params;
+use specs::args::parse_args;
+use specs::context::check_context_chain;
+use specs::context::ContextFile;
+use specs::io_schema::EncodedInputs;
+use specs::io_schema::IoSchema;
+use specs::io_schema::Stream;
 use specs::slice::Slice;
 use specs::CompilationTable;
 use specs::TraceBackend;
@@ -30,6 +43,7 @@ use crate::config::Config;
 use crate::names::name_of_circuit_data;
 use crate::names::name_of_config;
 use crate::names::name_of_params;
+use crate::names::name_of_phantom_functions;
 
 #[derive(Debug)]
 pub(crate) struct SetupArg {
@@ -102,6 +116,16 @@ impl SetupArg {
                     &circuit,
                     params_dir.join(name_of_circuit_data(name, is_last_slice)),
                 ),
+                ZkWasmCircuit::SmallOngoing(circuit) => SetupArg::_setup_circuit_data(
+                    params,
+                    &circuit,
+                    params_dir.join(name_of_circuit_data(name, is_last_slice)),
+                ),
+                ZkWasmCircuit::SmallLastSliceCircuit(circuit) => SetupArg::_setup_circuit_data(
+                    params,
+                    &circuit,
+                    params_dir.join(name_of_circuit_data(name, is_last_slice)),
+                ),
             }
         };
 
@@ -165,6 +189,13 @@ impl SetupArg {
         loader.compile(&module, &mut monitor)?;
         let compilation_table = monitor.into_compilation_table();
 
+        for phantom_function in compilation_table.phantom_functions.entries() {
+            println!(
+                "Phantom function {} resolves to function {}",
+                phantom_function.name, phantom_function.fid
+            );
+        }
+
         println!("{} Building circuit data...", style("[3/5]").bold().dim(),);
         let circuit_datas =
             self.setup_circuit_data(name, params_dir, &params, self.k, &compilation_table)?;
@@ -208,10 +239,22 @@ impl SetupArg {
 
                 checksum,
                 phantom_functions: self.phantom_functions.clone(),
+                resolved_phantom_functions: if cfg!(feature = "uniform-circuit") {
+                    None
+                } else {
+                    Some(compilation_table.phantom_functions.resolve())
+                },
                 host_mode: self.host_mode,
             };
             config.write(&mut File::create(&config_path)?)?;
 
+            if let Some(resolved_phantom_functions) = &config.resolved_phantom_functions {
+                serde_json::to_writer_pretty(
+                    File::create(params_dir.join(name_of_phantom_functions(name)))?,
+                    resolved_phantom_functions,
+                )?;
+            }
+
             println!(
                 "{} {:?}",
                 style("The configuration is saved at").green().bold().dim(),
@@ -237,13 +280,84 @@ pub(crate) struct RunningArg {
     #[clap(long = "private")]
     pub(crate) private_inputs: Vec<String>,
 
-    /// Context inputs with format 'value:type' where type=i64|bytes|bytes-packed|file.
+    /// Context inputs with format 'value:type' where type=i64|bytes|bytes-packed|file|context.
     #[clap(long = "context-in")]
     pub(crate) context_inputs: Vec<String>,
 
+    /// Private inputs for `wasm_indexed_input(index)` with format 'value:type' where
+    /// type=i64|bytes|bytes-packed|file.
+    #[clap(long = "indexed")]
+    pub(crate) indexed_inputs: Vec<String>,
+
+    /// Path to the JSON schema of the input streams and of the outputs.
+    #[clap(long = "schema")]
+    pub(crate) schema: Option<PathBuf>,
+
+    /// Path to the JSON values of the fields declared by the schema.
+    #[clap(long = "inputs")]
+    pub(crate) inputs: Option<PathBuf>,
+
     /// Filename to the file to write the context output.
     #[clap(long = "context-out")]
     pub(crate) context_output: Option<String>,
+
+    /// Filename to the file to write the logs emitted by the guest.
+    #[clap(long = "guest-log")]
+    pub(crate) guest_log: Option<String>,
+}
+
+impl RunningArg {
+    /// Encodes the inputs, the values of `--inputs` come first in each stream and are followed by
+    /// the values of `--public`, `--private` and `--ctxin`. The schema has no indexed stream, so
+    /// the indexed inputs are the values of `--indexed` only. Context files given to `--ctxin`
+    /// must have been written by the image of `image_checksum`.
+    pub(crate) fn execution_arg(
+        &self,
+        image_checksum: &(String, String),
+    ) -> anyhow::Result<(ExecutionArg, Option<IoSchema>)> {
+        for context_input in &self.context_inputs {
+            if let Some(path) = context_input.strip_suffix(":context") {
+                ContextFile::read(Path::new(path))?.check_image(image_checksum)?;
+            }
+        }
+
+        let schema = self
+            .schema
+            .as_ref()
+            .map(|path| IoSchema::read(path))
+            .transpose()?;
+
+        let inputs = match (&schema, &self.inputs) {
+            (Some(schema), Some(inputs)) => schema.encode_file(inputs)?,
+            // A schema may only describe the outputs.
+            (Some(schema), None)
+                if [Stream::Public, Stream::Private, Stream::Context]
+                    .into_iter()
+                    .all(|stream| schema.fields(stream).is_empty()) =>
+            {
+                EncodedInputs::default()
+            }
+            (None, None) => EncodedInputs::default(),
+            (Some(_), None) => {
+                anyhow::bail!("--schema declares input fields but no --inputs are given")
+            }
+            (None, Some(_)) => anyhow::bail!("--inputs can't be encoded without --schema"),
+        };
+
+        let mut arg = ExecutionArg {
+            public_inputs: inputs.public_inputs,
+            private_inputs: inputs.private_inputs,
+            context_inputs: inputs.context_inputs,
+            indexed_inputs: vec![],
+        };
+
+        arg.public_inputs.extend(parse_args(&self.public_inputs)?);
+        arg.private_inputs.extend(parse_args(&self.private_inputs)?);
+        arg.context_inputs.extend(parse_args(&self.context_inputs)?);
+        arg.indexed_inputs.extend(parse_args(&self.indexed_inputs)?);
+
+        Ok((arg, schema))
+    }
 }
 
 #[derive(Debug)]
@@ -252,6 +366,30 @@ pub(crate) struct DryRunArg {
     pub(crate) running_arg: RunningArg,
 }
 
+/// Execute the Wasm image and report the cost of each table.
+#[derive(Debug)]
+pub(crate) struct ProfileArg {
+    pub(crate) wasm_image: PathBuf,
+    pub(crate) public_inputs: Vec<String>,
+    pub(crate) private_inputs: Vec<String>,
+    pub(crate) context_inputs: Vec<String>,
+    pub(crate) indexed_inputs: Vec<String>,
+    pub(crate) json: bool,
+    pub(crate) flamegraph: Option<PathBuf>,
+    pub(crate) flamegraph_format: FlamegraphFormat,
+    pub(crate) flamegraph_weight: FlamegraphWeight,
+}
+
+/// Execute the Wasm image and explore its trace interactively.
+#[derive(Debug)]
+pub(crate) struct DebugArg {
+    pub(crate) wasm_image: PathBuf,
+    pub(crate) public_inputs: Vec<String>,
+    pub(crate) private_inputs: Vec<String>,
+    pub(crate) context_inputs: Vec<String>,
+    pub(crate) indexed_inputs: Vec<String>,
+}
+
 /// Execute the Wasm image and generate a proof.
 #[derive(Debug)]
 pub(crate) struct ProveArg {
@@ -262,6 +400,240 @@ pub(crate) struct ProveArg {
     pub(crate) file_backend: bool,
 }
 
+/// Estimate the cost of proving the Wasm image for each candidate K.
+#[derive(Debug)]
+pub(crate) struct EstimateArg {
+    pub(crate) wasm_image: PathBuf,
+    pub(crate) host_mode: HostMode,
+    pub(crate) phantom_functions: Vec<String>,
+    pub(crate) public_inputs: Vec<String>,
+    pub(crate) private_inputs: Vec<String>,
+    pub(crate) context_inputs: Vec<String>,
+    pub(crate) indexed_inputs: Vec<String>,
+    pub(crate) min_k: u32,
+    pub(crate) max_k: u32,
+    pub(crate) json: bool,
+}
+
+impl EstimateArg {
+    pub(crate) fn estimate(&self, env_builder: &Box<dyn HostEnvBuilder>) -> anyhow::Result<()> {
+        let wasm_image = fs::read(&self.wasm_image)?;
+        let module = ZkWasmLoader::parse_module(&wasm_image)?;
+
+        let estimation = estimate_k(
+            env_builder.as_ref(),
+            &module,
+            &self.phantom_functions,
+            ExecutionArg {
+                public_inputs: parse_args(&self.public_inputs)?,
+                private_inputs: parse_args(&self.private_inputs)?,
+                context_inputs: parse_args(&self.context_inputs)?,
+                indexed_inputs: parse_args(&self.indexed_inputs)?,
+            },
+            self.min_k..=self.max_k,
+            &ProvingCostModel::default(),
+        )?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&estimation)?);
+
+            return Ok(());
+        }
+
+        println!(
+            "guest instructions: {}, maximal memory pages: {}",
+            estimation.summary.instructions, estimation.summary.maximal_memory_pages
+        );
+        println!(
+            "{:>3} {:>8} {:>14} {:>14} {:>12} {:>10}  host rounds (used/max)",
+            "K", "slices", "page headroom", "prove time(s)", "memory(GiB)", "feasible"
+        );
+        for candidate in &estimation.candidates {
+            let host_ops = candidate
+                .host_ops
+                .iter()
+                .map(|(name, usage)| format!("{}: {}/{}", name, usage.used_round, usage.max_round))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            println!(
+                "{:>3} {:>8} {:>14} {:>14.1} {:>12.1} {:>10}  {}",
+                candidate.k,
+                candidate.slices,
+                candidate.page_headroom,
+                candidate.estimated_prove_seconds,
+                candidate.estimated_memory_bytes as f64 / (1u64 << 30) as f64,
+                candidate.is_feasible(),
+                host_ops
+            );
+        }
+
+        match estimation.recommended {
+            Some(k) => println!("{} K = {}", style("Recommended").green().bold(), k),
+            None => println!(
+                "{}",
+                style("No candidate K is able to prove the execution.")
+                    .red()
+                    .bold()
+            ),
+        }
+
+        Ok(())
+    }
+}
+
+/// List the phantom functions of the Wasm image.
+#[derive(Debug)]
+pub(crate) struct ListPhantomFunctionsArg {
+    pub(crate) wasm_image: PathBuf,
+    pub(crate) json: bool,
+}
+
+/// Check that the context files written by `--ctxout` form a chain of runs of the image.
+#[derive(Debug)]
+pub(crate) struct CheckContextArg {
+    pub(crate) contexts: Vec<PathBuf>,
+}
+
+impl CheckContextArg {
+    pub(crate) fn check(&self, image_checksum: &(String, String)) -> anyhow::Result<()> {
+        let chain = self
+            .contexts
+            .iter()
+            .map(|path| ContextFile::read(path))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (path, context) in self.contexts.iter().zip(chain.iter()) {
+            context
+                .check_image(image_checksum)
+                .map_err(|err| anyhow::anyhow!("{:?}: {}", path, err))?;
+        }
+
+        check_context_chain(&chain)
+            .map_err(|(index, err)| anyhow::anyhow!("{:?}: {}", self.contexts[index], err))?;
+
+        println!(
+            "{}",
+            style(format!("{} contexts form a chain.", chain.len()))
+                .green()
+                .bold()
+        );
+
+        Ok(())
+    }
+}
+
+/// Compare two executions saved by `prove` in their trace directories.
+#[derive(Debug)]
+pub(crate) struct TraceDiffArg {
+    pub(crate) trace_a: PathBuf,
+    pub(crate) trace_b: PathBuf,
+    pub(crate) json: bool,
+}
+
+impl TraceDiffArg {
+    pub(crate) fn diff(&self) -> anyhow::Result<()> {
+        let diff = diff_trace_dirs(&self.trace_a, &self.trace_b)?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+
+            return Ok(());
+        }
+
+        println!("steps: {} vs {}", diff.steps_a, diff.steps_b);
+
+        match &diff.first_divergence {
+            None => println!("{}", style("The traces are identical.").green().bold()),
+            Some(divergence) => {
+                println!(
+                    "{} at step {}{}",
+                    style("First divergence").red().bold(),
+                    divergence.step,
+                    if divergence.control_flow {
+                        " (control flow)"
+                    } else {
+                        ""
+                    }
+                );
+
+                for (side, step) in [("a", &divergence.a), ("b", &divergence.b)] {
+                    match step {
+                        None => println!("  {}: <end of trace>", side),
+                        Some(step) => {
+                            println!(
+                                "  {}: eid {} fid {} iid {} depth {} {}",
+                                side,
+                                step.eid,
+                                step.location.fid,
+                                step.location.iid,
+                                step.location.depth,
+                                step.opcode
+                            );
+                            for write in &step.memory_writes {
+                                println!(
+                                    "     write {:?}[{}] = {}",
+                                    write.ltype, write.offset, write.value
+                                );
+                            }
+                            if let Some(host_call) = &step.host_call {
+                                println!(
+                                    "     host call {} args {:?} returns {:?}",
+                                    host_call.name, host_call.args, host_call.rets
+                                );
+                            }
+                        }
+                    }
+                }
+
+                if let Some(realigned) = &divergence.realigned {
+                    println!(
+                        "  realigned at fid {} iid {} depth {} after {} steps of a and {} steps of b",
+                        realigned.location.fid,
+                        realigned.location.iid,
+                        realigned.location.depth,
+                        realigned.skipped_a,
+                        realigned.skipped_b
+                    );
+                }
+
+                for field in &divergence.fields {
+                    let show = |value: &Option<serde_json::Value>| {
+                        value
+                            .as_ref()
+                            .map_or("-".to_string(), |value| value.to_string())
+                    };
+
+                    println!(
+                        "  {:<24} {} vs {}",
+                        field.field,
+                        show(&field.a),
+                        show(&field.b)
+                    );
+                }
+            }
+        }
+
+        if !diff.opcode_class_deltas.is_empty() {
+            println!(
+                "{:<20} {:>12} {:>12} {:>12}",
+                "opcode class", "a", "b", "delta"
+            );
+            for delta in &diff.opcode_class_deltas {
+                println!(
+                    "{:<20} {:>12} {:>12} {:>+12}",
+                    delta.class,
+                    delta.a,
+                    delta.b,
+                    delta.b as i64 - delta.a as i64
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Verify the proof.
 #[derive(Debug, Args)]
 pub(crate) struct VerifyArg {
@@ -274,6 +646,12 @@ pub(crate) struct VerifyArg {
 pub(crate) enum Subcommands {
     Setup(SetupArg),
     DryRun(DryRunArg),
+    Profile(ProfileArg),
+    Debug(DebugArg),
     Prove(ProveArg),
     Verify(VerifyArg),
+    Estimate(EstimateArg),
+    PhantomFunctions(ListPhantomFunctionsArg),
+    TraceDiff(TraceDiffArg),
+    CheckContext(CheckContextArg),
 }
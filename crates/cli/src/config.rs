@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Cursor;
 use std::io::Read;
@@ -13,13 +14,21 @@ use circuits_batcher::proof::ProofPieceInfo;
 use circuits_batcher::proof::Prover;
 use console::style;
 use delphinus_zkwasm::circuits::ZkWasmCircuit;
+use delphinus_zkwasm::foreign::log_helper::GuestLog;
 use delphinus_zkwasm::loader::slice::Slices;
 use delphinus_zkwasm::loader::Module;
 use delphinus_zkwasm::loader::ZkWasmLoader;
+use delphinus_zkwasm::profile::flamegraph::FlameGraph;
+use delphinus_zkwasm::profile::flamegraph::FrameWeight;
+use delphinus_zkwasm::profile::instruction_statistic::CostReport;
+use delphinus_zkwasm::profile::instruction_statistic::InstructionStatistic;
+use delphinus_zkwasm::profile::instruction_statistic::TableCost;
 use delphinus_zkwasm::runtime::host::default_env::ExecutionArg;
 use delphinus_zkwasm::runtime::host::HostEnvBuilder;
 use delphinus_zkwasm::runtime::monitor::statistic_monitor::StatisticMonitor;
 use delphinus_zkwasm::runtime::monitor::table_monitor::TableMonitor;
+use delphinus_zkwasm::runtime::monitor::WasmiMonitor;
+use delphinus_zkwasm::runtime::trace_explorer::TraceExplorer;
 use halo2_proofs::pairing::bn256::Bn256;
 use halo2_proofs::pairing::bn256::G1Affine;
 use halo2_proofs::plonk::CircuitData;
@@ -27,15 +36,21 @@ use halo2_proofs::poly::commitment::Params;
 use indicatif::ProgressBar;
 use serde::Deserialize;
 use serde::Serialize;
+use specs::context::ContextFile;
+use specs::io_schema::IoSchema;
+use specs::phantom::ResolvedPhantomFunction;
 use specs::TraceBackend;
 
+use crate::args::FlamegraphFormat;
 use crate::args::HostMode;
+use crate::debugger;
 use crate::names::name_of_circuit_data;
 use crate::names::name_of_etable_slice;
 use crate::names::name_of_frame_table_slice;
 use crate::names::name_of_instance;
 use crate::names::name_of_loadinfo;
 use crate::names::name_of_params;
+use crate::names::name_of_phantom_functions;
 use crate::names::name_of_transcript;
 use crate::names::name_of_witness;
 
@@ -71,9 +86,108 @@ pub(crate) struct Config {
 
     pub(crate) checksum: (String, String),
     pub(crate) phantom_functions: Vec<String>,
+    /// Functions the phantom function patterns resolved to at setup, `None` if the circuit is set
+    /// up without an image.
+    pub(crate) resolved_phantom_functions: Option<Vec<ResolvedPhantomFunction>>,
     pub(crate) host_mode: HostMode,
 }
 
+/// `Config` as written before setup recorded the resolved phantom functions. bincode is not
+/// self-describing, so the missing field can't be defaulted and such configs are read with this
+/// layout instead.
+#[derive(Serialize, Deserialize)]
+struct ConfigWithoutResolvedPhantomFunctions {
+    name: String,
+
+    is_uniform_circuit: bool,
+    k: u32,
+    params: PathBuf,
+    params_md5: String,
+    wasm_image_md5: Option<String>,
+    circuit_datas: CircuitDataConfig,
+
+    checksum: (String, String),
+    phantom_functions: Vec<String>,
+    host_mode: HostMode,
+}
+
+impl From<ConfigWithoutResolvedPhantomFunctions> for Config {
+    fn from(config: ConfigWithoutResolvedPhantomFunctions) -> Self {
+        Config {
+            name: config.name,
+            is_uniform_circuit: config.is_uniform_circuit,
+            k: config.k,
+            params: config.params,
+            params_md5: config.params_md5,
+            wasm_image_md5: config.wasm_image_md5,
+            circuit_datas: config.circuit_datas,
+            checksum: config.checksum,
+            phantom_functions: config.phantom_functions,
+            resolved_phantom_functions: None,
+            host_mode: config.host_mode,
+        }
+    }
+}
+
+/// Outputs that don't match the schema are only reported, the execution and its proof don't depend
+/// on the schema.
+fn print_outputs(schema: &IoSchema, outputs: &Vec<u64>) -> Result<()> {
+    match schema.decode_outputs(outputs) {
+        Ok(outputs) => println!("outputs {}", serde_json::to_string_pretty(&outputs)?),
+        Err(err) => eprintln!(
+            "{} outputs don't match the schema: {}",
+            style("Warning:").yellow().bold(),
+            err
+        ),
+    }
+
+    Ok(())
+}
+
+fn write_guest_logs(path: &PathBuf, logs: &Vec<GuestLog>) -> Result<()> {
+    let mut fd = File::create(path)?;
+
+    for log in logs {
+        writeln!(
+            fd,
+            "{} [{}] {}",
+            log.level,
+            log.function.as_deref().unwrap_or("unknown"),
+            log.message
+        )?;
+    }
+
+    Ok(())
+}
+
+fn print_cost_report(report: &CostReport) {
+    fn print_costs(title: &str, costs: &BTreeMap<String, TableCost>, total: &TableCost) {
+        let mut costs = costs.iter().collect::<Vec<_>>();
+        costs.sort_by(|(_, a), (_, b)| b.etable_rows.cmp(&a.etable_rows));
+
+        println!(
+            "{:<32} {:>12} {:>8} {:>12} {:>10} {:>10} {:>12}",
+            title, "etable rows", "%", "mtable", "bit ops", "frames", "host calls"
+        );
+        for (name, cost) in costs.into_iter().chain([(&"total".to_string(), total)]) {
+            println!(
+                "{:<32} {:>12} {:>7.2}% {:>12} {:>10} {:>10} {:>12}",
+                name,
+                cost.etable_rows,
+                cost.etable_rows as f64 / total.etable_rows.max(1) as f64 * 100.0,
+                cost.mtable_entries,
+                cost.bit_table_ops,
+                cost.frame_table_entries,
+                cost.external_host_call_rows
+            );
+        }
+    }
+
+    print_costs("opcode class", &report.by_opcode_class, &report.total);
+    println!();
+    print_costs("function", &report.by_function, &report.total);
+}
+
 impl Config {
     fn image_consistent_check(&self, wasm_image: &[u8]) -> anyhow::Result<()> {
         if let Some(expected_wasm_image_md5) = &self.wasm_image_md5 {
@@ -90,6 +204,39 @@ impl Config {
         Ok(())
     }
 
+    fn phantom_functions_consistent_check(
+        &self,
+        resolved_phantom_functions: &Vec<ResolvedPhantomFunction>,
+    ) -> anyhow::Result<()> {
+        fn describe(phantom_functions: &Vec<ResolvedPhantomFunction>) -> String {
+            phantom_functions
+                .iter()
+                .map(|function| {
+                    format!(
+                        "{} (fid {}, body {})",
+                        function.name,
+                        function.fid,
+                        &function.body_hash[..8]
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        if let Some(expected) = &self.resolved_phantom_functions {
+            if expected != resolved_phantom_functions {
+                anyhow::bail!(
+                    "Phantom functions [{}] are inconsistent with [{}] resolved at setup. \
+                        Maybe you have changed the Wasm image or its name section after setup the circuit?",
+                    describe(resolved_phantom_functions),
+                    describe(expected),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     fn params_consistent_check(&self, params: &[u8]) -> anyhow::Result<()> {
         let params_md5 = format!("{:x}", md5::compute(params));
 
@@ -131,7 +278,9 @@ impl Config {
     pub(crate) fn read(fd: &mut File) -> anyhow::Result<Self> {
         let mut buf = Vec::new();
         fd.read_to_end(&mut buf)?;
-        let config = bincode::deserialize(&mut buf)?;
+        let config = bincode::deserialize(&buf).or_else(|_| {
+            bincode::deserialize::<ConfigWithoutResolvedPhantomFunctions>(&buf).map(Config::from)
+        })?;
 
         Ok(config)
     }
@@ -188,10 +337,14 @@ impl Config {
         wasm_image: &PathBuf,
         output_dir: &PathBuf,
         arg: ExecutionArg,
+        schema: Option<IoSchema>,
         context_output_filename: Option<String>,
+        guest_log_filename: Option<String>,
     ) -> Result<()> {
         let module = self.read_wasm_image(wasm_image)?;
 
+        let context_inputs = arg.context_inputs.clone();
+
         let env = env_builder.create_env(self.k, arg);
 
         let mut monitor = StatisticMonitor::new(&self.phantom_functions, &env);
@@ -207,6 +360,14 @@ impl Config {
             println!("total guest instructions used {:?}", result.guest_statics);
             println!("total host api used {:?}", result.host_statics);
 
+            if let Some(schema) = &schema {
+                print_outputs(schema, &result.outputs)?;
+            }
+
+            if let Some(guest_log_filename) = guest_log_filename {
+                write_guest_logs(&output_dir.join(guest_log_filename), &result.guest_logs)?;
+            }
+
             result
         };
 
@@ -220,9 +381,12 @@ impl Config {
                     context_output_path
                 );
 
-                result
-                    .context_outputs
-                    .write(&mut File::create(&context_output_path)?)?;
+                ContextFile::new(
+                    self.checksum.clone(),
+                    &context_inputs,
+                    result.context_outputs.0.clone(),
+                )
+                .write(&context_output_path)?;
             } else {
                 println!(
                     "{} Context output is not specified. Skip writing context output...",
@@ -234,6 +398,144 @@ impl Config {
         Ok(())
     }
 
+    pub(crate) fn profile(
+        self,
+        env_builder: &Box<dyn HostEnvBuilder>,
+        wasm_image: &PathBuf,
+        arg: ExecutionArg,
+        json: bool,
+        flamegraph: Option<(&PathBuf, FlamegraphFormat, FrameWeight)>,
+    ) -> Result<()> {
+        let module = self.read_wasm_image(wasm_image)?;
+
+        let env = env_builder.create_env(self.k, arg);
+
+        let mut monitor =
+            TableMonitor::new(self.k, &self.phantom_functions, TraceBackend::Memory, &env);
+
+        let loader = ZkWasmLoader::new(self.k, env)?;
+        let runner = loader.compile(&module, &mut monitor)?;
+        loader.run(runner, &mut monitor)?;
+
+        let function_names = monitor.expose_observer().borrow().function_names.clone();
+        let tables = monitor.into_tables();
+        let report = tables.profile_instruction(&function_names)?;
+
+        if let Some((path, format, weight)) = flamegraph {
+            let mut fd = File::create(path)?;
+
+            match format {
+                FlamegraphFormat::FOLDED => {
+                    tables.write_folded_stacks(&function_names, weight, &mut fd)?
+                }
+                FlamegraphFormat::CHROME => {
+                    tables.write_chrome_trace(&function_names, weight, &mut fd)?
+                }
+            }
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            print_cost_report(&report);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn debug(
+        self,
+        env_builder: &Box<dyn HostEnvBuilder>,
+        wasm_image: &PathBuf,
+        arg: ExecutionArg,
+    ) -> Result<()> {
+        let module = self.read_wasm_image(wasm_image)?;
+
+        let env = env_builder.create_env(self.k, arg);
+
+        let mut monitor =
+            TableMonitor::new(self.k, &self.phantom_functions, TraceBackend::Memory, &env);
+
+        let loader = ZkWasmLoader::new(self.k, env)?;
+        let runner = loader.compile(&module, &mut monitor)?;
+        if let Err(err) = loader.run(runner, &mut monitor) {
+            // Explore the trace up to the failure.
+            println!("Execution failed: {}", err);
+        }
+
+        let function_names = monitor.expose_observer().borrow().function_names.clone();
+        let explorer = TraceExplorer::new(&monitor.into_tables(), function_names)?;
+
+        debugger::repl(explorer)
+    }
+
+    pub(crate) fn list_phantom_functions(
+        self,
+        env_builder: &Box<dyn HostEnvBuilder>,
+        wasm_image: &PathBuf,
+        json: bool,
+    ) -> Result<()> {
+        // Any image is accepted, to inspect how the patterns resolve in an image changed after setup.
+        let mut buf = Vec::new();
+        File::open(&wasm_image)?.read_to_end(&mut buf)?;
+        let module = ZkWasmLoader::parse_module(&buf)?;
+
+        let env = env_builder.create_env_without_value(self.k);
+        let mut monitor =
+            TableMonitor::new(self.k, &self.phantom_functions, TraceBackend::Memory, &env);
+        ZkWasmLoader::new(self.k, env)?.compile(&module, &mut monitor)?;
+
+        let phantom_functions = monitor.phantom_functions();
+
+        if json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(phantom_functions.entries())?
+            );
+
+            return Ok(());
+        }
+
+        println!(
+            "{:>5} {:<32} {:<24} {}",
+            "fid", "name", "signature", "body sha256"
+        );
+        for entry in phantom_functions.entries() {
+            let signature = format!(
+                "({}) -> {}",
+                entry
+                    .signature
+                    .params
+                    .iter()
+                    .map(|ty| format!("{:?}", ty).to_lowercase())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                entry
+                    .signature
+                    .return_type
+                    .map_or("()".to_string(), |ty| format!("{:?}", ty).to_lowercase())
+            );
+
+            println!(
+                "{:>5} {:<32} {:<24} {}",
+                entry.fid,
+                entry.name,
+                signature,
+                entry.body_hash()
+            );
+        }
+
+        match self.phantom_functions_consistent_check(&phantom_functions.resolve()) {
+            Ok(()) => println!(
+                "{}",
+                style("Consistent with the phantom functions resolved at setup.").green()
+            ),
+            Err(err) => println!("{}", style(err).red()),
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn prove(
         self,
         env_builder: &Box<dyn HostEnvBuilder>,
@@ -241,7 +543,9 @@ impl Config {
         params_dir: &PathBuf,
         output_dir: &PathBuf,
         arg: ExecutionArg,
+        schema: Option<IoSchema>,
         context_output_filename: Option<String>,
+        guest_log_filename: Option<String>,
         mock_test: bool,
         table_backend: TraceBackend,
     ) -> anyhow::Result<()> {
@@ -253,6 +557,8 @@ impl Config {
         println!("{} Load params...", style("[2/8]").bold().dim(),);
         let params = self.read_params(params_dir)?;
 
+        let context_inputs = arg.context_inputs.clone();
+
         let env = env_builder.create_env(self.k, arg);
 
         let mut monitor = TableMonitor::new(self.k, &self.phantom_functions, table_backend, &env);
@@ -262,11 +568,22 @@ impl Config {
 
             let loader = ZkWasmLoader::new(self.k, env)?;
             let runner = loader.compile(&module, &mut monitor)?;
+
+            self.phantom_functions_consistent_check(&monitor.phantom_functions().resolve())?;
+
             let result = loader.run(runner, &mut monitor)?;
 
             println!("total guest instructions used {:?}", result.guest_statics);
             println!("total host api used {:?}", result.host_statics);
 
+            if let Some(schema) = &schema {
+                print_outputs(schema, &result.outputs)?;
+            }
+
+            if let Some(guest_log_filename) = guest_log_filename {
+                write_guest_logs(&output_dir.join(guest_log_filename), &result.guest_logs)?;
+            }
+
             (result, monitor.into_tables())
         };
 
@@ -280,9 +597,12 @@ impl Config {
                     context_output_path
                 );
 
-                result
-                    .context_outputs
-                    .write(&mut File::create(&context_output_path)?)?;
+                ContextFile::new(
+                    self.checksum.clone(),
+                    &context_inputs,
+                    result.context_outputs.0.clone(),
+                )
+                .write(&context_output_path)?;
             } else {
                 println!(
                     "{} Context output is not specified. Skip writing context output...",
@@ -303,7 +623,7 @@ impl Config {
                 &dir,
                 |slice| name_of_etable_slice(&self.name, slice),
                 |slice| name_of_frame_table_slice(&self.name, slice),
-            );
+            )?;
         }
 
         println!("{} Build circuit(s)...", style("[6/8]").bold().dim(),);
@@ -405,6 +725,23 @@ impl Config {
                         proof_load_info.hashtype,
                         OpenSchema::Shplonk,
                     ),
+                ZkWasmCircuit::SmallOngoing(circuit) => proof_piece_info.create_proof::<Bn256, _>(
+                    &circuit,
+                    &vec![instances.clone()],
+                    &params,
+                    &cached_proving_key.as_ref().unwrap().1,
+                    proof_load_info.hashtype,
+                    OpenSchema::Shplonk,
+                ),
+                ZkWasmCircuit::SmallLastSliceCircuit(circuit) => proof_piece_info
+                    .create_proof::<Bn256, _>(
+                        &circuit,
+                        &vec![instances.clone()],
+                        &params,
+                        &cached_proving_key.as_ref().unwrap().1,
+                        proof_load_info.hashtype,
+                        OpenSchema::Shplonk,
+                    ),
             };
 
             proof_piece_info.save_proof_data(&vec![instances.clone()], &proof, &output_dir);
@@ -429,6 +766,17 @@ impl Config {
     }
 
     pub(crate) fn verify(self, params_dir: &PathBuf, output_dir: &PathBuf) -> anyhow::Result<()> {
+        // The output directory is written by the prover, so the resolution is read from the setup
+        // artifacts. The proof itself is bound to it by the verifying key check below, the
+        // instruction table of the circuit holds the replaced bodies.
+        if self.resolved_phantom_functions.is_some() {
+            let resolved_phantom_functions: Vec<ResolvedPhantomFunction> = serde_json::from_reader(
+                File::open(params_dir.join(name_of_phantom_functions(&self.name)))?,
+            )?;
+
+            self.phantom_functions_consistent_check(&resolved_phantom_functions)?;
+        }
+
         let mut proofs = {
             println!(
                 "{} Reading proofs from {:?}",
@@ -538,3 +886,105 @@ impl Config {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::path::PathBuf;
+
+    use specs::phantom::ResolvedPhantomFunction;
+
+    use super::CircuitDataConfig;
+    use super::CircuitDataMd5;
+    use super::Config;
+    use super::ConfigWithoutResolvedPhantomFunctions;
+    use crate::args::HostMode;
+
+    fn circuit_datas() -> CircuitDataConfig {
+        let md5 = || CircuitDataMd5 {
+            circuit_data_md5: "circuit".to_string(),
+            verifying_key_md5: "vkey".to_string(),
+        };
+
+        #[cfg(feature = "continuation")]
+        let circuit_datas = CircuitDataConfig {
+            on_going_circuit: md5(),
+            finalized_circuit: md5(),
+        };
+
+        #[cfg(not(feature = "continuation"))]
+        let circuit_datas = CircuitDataConfig {
+            finalized_circuit: md5(),
+        };
+
+        circuit_datas
+    }
+
+    fn read(name: &str, bytes: Vec<u8>) -> Config {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, bytes).unwrap();
+
+        Config::read(&mut File::open(&path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_read_config_without_resolved_phantom_functions() {
+        for host_mode in [HostMode::DEFAULT, HostMode::STANDARD] {
+            let legacy = ConfigWithoutResolvedPhantomFunctions {
+                name: "test".to_string(),
+                is_uniform_circuit: false,
+                k: 18,
+                params: PathBuf::from("K18.params"),
+                params_md5: "params".to_string(),
+                wasm_image_md5: Some("image".to_string()),
+                circuit_datas: circuit_datas(),
+                checksum: ("x".to_string(), "y".to_string()),
+                phantom_functions: vec!["^phantom_".to_string()],
+                host_mode,
+            };
+
+            let config = read(
+                "zkwasm-legacy.zkwasm.config",
+                bincode::serialize(&legacy).unwrap(),
+            );
+
+            assert_eq!(config.name, "test");
+            assert_eq!(config.k, 18);
+            assert_eq!(config.wasm_image_md5, Some("image".to_string()));
+            assert_eq!(config.phantom_functions, vec!["^phantom_".to_string()]);
+            assert_eq!(config.resolved_phantom_functions, None);
+            assert_eq!(config.host_mode as u32, host_mode as u32);
+        }
+    }
+
+    #[test]
+    fn test_read_config_with_resolved_phantom_functions() {
+        let resolved_phantom_functions = vec![ResolvedPhantomFunction {
+            name: "phantom_sum".to_string(),
+            fid: 3,
+            body_hash: "00".repeat(32),
+        }];
+
+        let config = Config {
+            name: "test".to_string(),
+            is_uniform_circuit: false,
+            k: 18,
+            params: PathBuf::from("K18.params"),
+            params_md5: "params".to_string(),
+            wasm_image_md5: Some("image".to_string()),
+            circuit_datas: circuit_datas(),
+            checksum: ("x".to_string(), "y".to_string()),
+            phantom_functions: vec!["^phantom_".to_string()],
+            resolved_phantom_functions: Some(resolved_phantom_functions.clone()),
+            host_mode: HostMode::STANDARD,
+        };
+
+        let config = read("zkwasm.zkwasm.config", bincode::serialize(&config).unwrap());
+
+        assert_eq!(
+            config.resolved_phantom_functions,
+            Some(resolved_phantom_functions)
+        );
+        assert_eq!(config.host_mode as u32, HostMode::STANDARD as u32);
+    }
+}
@@ -0,0 +1,232 @@
+use std::io::BufRead;
+use std::io::Write;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use console::style;
+use delphinus_zkwasm::runtime::trace_explorer::Breakpoint;
+use delphinus_zkwasm::runtime::trace_explorer::TraceExplorer;
+use specs::mtable::LocationType;
+
+const HELP: &str = r#"Commands:
+    s, step [N]                 step forward N entries (default 1)
+    b, back [N]                 step backward N entries (default 1)
+    c, continue                 run forward to the next breakpoint
+    rc, reverse-continue        run backward to the previous breakpoint
+    goto <EID>                  jump to the entry with EID
+    break func <NAME|FID>       break when the function is entered
+    break iid <FID> <IID>       break before the instruction
+    break mem <ADDRESS>         break on any access to the heap block containing ADDRESS
+    breakpoints                 list breakpoints
+    clear                       delete all breakpoints
+    i, info                     show the current entry
+    stack [N]                   show the top N stack values (default 8)
+    frame                       show locals and operands of the current frame
+    mem <ADDRESS> [N]           show N heap blocks from ADDRESS (default 1)
+    global <INDEX>              show a global
+    last-write <stack|heap|global> <OFFSET>
+                                jump to the entry that last wrote the location, the offset of
+                                heap is a byte address
+    h, help                     show this message
+    q, quit                     exit"#;
+
+fn show_value(value: Option<u64>) -> String {
+    match value {
+        Some(value) => format!("{:#018x} ({})", value, value as i64),
+        None => "<uninitialized>".to_string(),
+    }
+}
+
+fn show_entry(explorer: &TraceExplorer) {
+    let entry = match explorer.current() {
+        Some(entry) => entry,
+        None => {
+            println!("The trace is empty.");
+            return;
+        }
+    };
+
+    println!(
+        "{} eid {} in {} (fid {}) iid {} sp {} pages {}",
+        style("=>").green().bold(),
+        entry.eid,
+        explorer.function_name(entry.fid),
+        entry.fid,
+        entry.iid,
+        entry.sp,
+        entry.allocated_memory_pages
+    );
+    if let Some(instruction) = explorer.instruction() {
+        println!("   {:?}", instruction.opcode);
+    }
+    println!("   {:?}", entry.step_info);
+    for event in explorer.memory_events() {
+        println!(
+            "   {:?} {:?}[{}] {:?} = {}",
+            event.atype,
+            event.ltype,
+            event.offset,
+            event.vtype,
+            show_value(Some(event.value))
+        );
+    }
+}
+
+fn show_stack(values: Vec<(u32, Option<u64>)>) {
+    for (offset, value) in values {
+        println!("   [{}] {}", offset, show_value(value));
+    }
+}
+
+fn parse<T: std::str::FromStr>(arg: Option<&str>) -> Result<T> {
+    arg.ok_or(anyhow!("missing argument"))?
+        .parse::<T>()
+        .map_err(|_| anyhow!("invalid argument {:?}", arg.unwrap()))
+}
+
+fn parse_location_type(arg: Option<&str>) -> Result<LocationType> {
+    match arg {
+        Some("stack") => Ok(LocationType::Stack),
+        Some("heap") => Ok(LocationType::Heap),
+        Some("global") => Ok(LocationType::Global),
+        _ => Err(anyhow!("expected stack, heap or global")),
+    }
+}
+
+fn execute(explorer: &mut TraceExplorer, command: &str, args: &[&str]) -> Result<bool> {
+    let mut args = args.iter().cloned();
+
+    match command {
+        "s" | "step" | "b" | "back" => {
+            let steps = args.next().map_or(Ok(1), |n| parse::<isize>(Some(n)))?;
+            let steps = if command.starts_with('b') {
+                -steps
+            } else {
+                steps
+            };
+
+            if explorer.step(steps) == 0 {
+                println!("Reached the end of the trace.");
+            }
+            show_entry(explorer);
+        }
+        "c" | "continue" | "rc" | "reverse-continue" => {
+            match explorer.resume(!command.starts_with('r')) {
+                Some(breakpoint) => println!("Hit {:?}.", breakpoint),
+                None => println!("Reached the end of the trace."),
+            }
+            show_entry(explorer);
+        }
+        "goto" => {
+            if explorer.goto(parse(args.next())?) {
+                show_entry(explorer);
+            } else {
+                println!("No such eid in the trace.");
+            }
+        }
+        "break" => {
+            let breakpoint = match args.next() {
+                Some("func") => {
+                    let function = args.next().ok_or(anyhow!("missing function"))?;
+                    let fid = match explorer.find_function(function) {
+                        Some(fid) => fid,
+                        None => parse(Some(function))?,
+                    };
+
+                    Breakpoint::Function(fid)
+                }
+                Some("iid") => Breakpoint::Instruction {
+                    fid: parse(args.next())?,
+                    iid: parse(args.next())?,
+                },
+                Some("mem") => Breakpoint::Memory(parse(args.next())?),
+                _ => return Err(anyhow!("expected func, iid or mem")),
+            };
+
+            explorer.add_breakpoint(breakpoint);
+        }
+        "breakpoints" => {
+            for breakpoint in explorer.breakpoints() {
+                println!("   {:?}", breakpoint);
+            }
+        }
+        "clear" => explorer.clear_breakpoints(),
+        "i" | "info" => show_entry(explorer),
+        "stack" => {
+            let depth = args.next().map_or(Ok(8), |n| parse(Some(n)))?;
+
+            show_stack(explorer.stack(depth));
+        }
+        "frame" => show_stack(explorer.frame()),
+        "mem" => {
+            let address: u32 = parse(args.next())?;
+            let blocks: u32 = args.next().map_or(Ok(1), |n| parse(Some(n)))?;
+
+            for block in 0..blocks {
+                let address = (address / 8 + block) * 8;
+
+                println!(
+                    "   {:#010x} {}",
+                    address,
+                    show_value(Some(explorer.memory(address)))
+                );
+            }
+        }
+        "global" => {
+            println!("   {}", show_value(explorer.global(parse(args.next())?)));
+        }
+        "last-write" => {
+            let ltype = parse_location_type(args.next())?;
+            let offset: u32 = parse(args.next())?;
+            let offset = if ltype == LocationType::Heap {
+                offset / 8
+            } else {
+                offset
+            };
+
+            match explorer.last_write(ltype, offset) {
+                Some(eid) => {
+                    explorer.goto(eid);
+                    show_entry(explorer);
+                }
+                None => println!("The location is not written before the current entry."),
+            }
+        }
+        "h" | "help" => println!("{}", HELP),
+        "q" | "quit" => return Ok(false),
+        _ => println!("Unknown command {:?}, type `help` for commands.", command),
+    }
+
+    Ok(true)
+}
+
+/// Read commands from stdin until `quit` or EOF.
+pub(crate) fn repl(mut explorer: TraceExplorer) -> Result<()> {
+    println!(
+        "Loaded {} entries, type `help` for commands.",
+        explorer.len()
+    );
+    show_entry(&explorer);
+
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("(zkwasm) ");
+        std::io::stdout().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => return Ok(()),
+        };
+        let words = line.split_whitespace().collect::<Vec<_>>();
+
+        if let Some((command, args)) = words.split_first() {
+            match execute(&mut explorer, command, args) {
+                Ok(true) => (),
+                Ok(false) => return Ok(()),
+                Err(err) => println!("{}", err),
+            }
+        }
+    }
+}
@@ -17,7 +17,6 @@ use names::name_of_config;
 use names::name_of_etable_slice;
 use names::name_of_frame_table_slice;
 use specs::args::parse_args;
-use specs::etable::EventTable;
 use specs::jtable::FrameTable;
 use specs::TraceBackend;
 
@@ -25,6 +24,7 @@ mod app_builder;
 mod args;
 mod command;
 mod config;
+mod debugger;
 mod names;
 
 const TRIVIAL_WASM: &'static str = r#"
@@ -64,9 +64,7 @@ fn main() -> Result<()> {
                 cli.params_dir.join(&name_of_config(&cli.name)),
             )?)?;
 
-            let public_inputs = parse_args(&arg.running_arg.public_inputs);
-            let private_inputs = parse_args(&arg.running_arg.private_inputs);
-            let context_inputs = parse_args(&arg.running_arg.context_inputs);
+            let (execution_arg, schema) = arg.running_arg.execution_arg(&config.checksum)?;
 
             let env_builder: Box<dyn HostEnvBuilder> = match config.host_mode {
                 HostMode::DEFAULT => Box::new(DefaultHostEnvBuilder),
@@ -77,12 +75,56 @@ fn main() -> Result<()> {
                 &env_builder,
                 &arg.wasm_image,
                 &arg.running_arg.output_dir,
+                execution_arg,
+                schema,
+                arg.running_arg.context_output,
+                arg.running_arg.guest_log,
+            )?;
+        }
+        Subcommands::Profile(arg) => {
+            let config = Config::read(&mut fs::File::open(
+                cli.params_dir.join(&name_of_config(&cli.name)),
+            )?)?;
+
+            let env_builder: Box<dyn HostEnvBuilder> = match config.host_mode {
+                HostMode::DEFAULT => Box::new(DefaultHostEnvBuilder),
+                HostMode::STANDARD => Box::new(StandardHostEnvBuilder::default()),
+            };
+
+            config.profile(
+                &env_builder,
+                &arg.wasm_image,
                 ExecutionArg {
-                    public_inputs,
-                    private_inputs,
-                    context_inputs,
+                    public_inputs: parse_args(&arg.public_inputs)?,
+                    private_inputs: parse_args(&arg.private_inputs)?,
+                    context_inputs: parse_args(&arg.context_inputs)?,
+                    indexed_inputs: parse_args(&arg.indexed_inputs)?,
+                },
+                arg.json,
+                arg.flamegraph
+                    .as_ref()
+                    .map(|path| (path, arg.flamegraph_format, arg.flamegraph_weight.into())),
+            )?;
+        }
+        Subcommands::Debug(arg) => {
+            let config = Config::read(&mut fs::File::open(
+                cli.params_dir.join(&name_of_config(&cli.name)),
+            )?)?;
+
+            let env_builder: Box<dyn HostEnvBuilder> = match config.host_mode {
+                HostMode::DEFAULT => Box::new(DefaultHostEnvBuilder),
+                HostMode::STANDARD => Box::new(StandardHostEnvBuilder::default()),
+            };
+
+            config.debug(
+                &env_builder,
+                &arg.wasm_image,
+                ExecutionArg {
+                    public_inputs: parse_args(&arg.public_inputs)?,
+                    private_inputs: parse_args(&arg.private_inputs)?,
+                    context_inputs: parse_args(&arg.context_inputs)?,
+                    indexed_inputs: parse_args(&arg.indexed_inputs)?,
                 },
-                arg.running_arg.context_output,
             )?;
         }
         Subcommands::Prove(arg) => {
@@ -93,23 +135,18 @@ fn main() -> Result<()> {
                 cli.params_dir.join(&name_of_config(&cli.name)),
             )?)?;
 
-            let public_inputs = parse_args(&arg.running_arg.public_inputs);
-            let private_inputs = parse_args(&arg.running_arg.private_inputs);
-            let context_inputs = parse_args(&arg.running_arg.context_inputs);
+            let (execution_arg, schema) = arg.running_arg.execution_arg(&config.checksum)?;
 
             let trace_backend: TraceBackend = if arg.file_backend {
-                let event_table_writer = {
+                let event_table_path = {
                     let name = cli.name.clone();
                     let trace_dir = trace_dir.clone();
 
-                    Box::new(move |slice, etable: &EventTable| {
+                    Box::new(move |slice| {
                         let filename_of_etable_slice =
                             PathBuf::from(name_of_etable_slice(&name, slice));
-                        let path = trace_dir.join(&filename_of_etable_slice);
 
-                        etable.write(&path).unwrap();
-
-                        path
+                        trace_dir.join(&filename_of_etable_slice)
                     })
                 };
 
@@ -129,7 +166,7 @@ fn main() -> Result<()> {
                 };
 
                 TraceBackend::File {
-                    event_table_writer,
+                    event_table_path,
                     frame_table_writer,
                 }
             } else {
@@ -146,12 +183,10 @@ fn main() -> Result<()> {
                 &arg.wasm_image,
                 &cli.params_dir,
                 &arg.output_dir,
-                ExecutionArg {
-                    public_inputs,
-                    private_inputs,
-                    context_inputs,
-                },
+                execution_arg,
+                schema,
                 arg.running_arg.context_output,
+                arg.running_arg.guest_log,
                 arg.mock_test,
                 trace_backend,
             )?;
@@ -163,6 +198,34 @@ fn main() -> Result<()> {
 
             config.verify(&cli.params_dir, &arg.output_dir)?;
         }
+        Subcommands::Estimate(arg) => {
+            let env_builder: Box<dyn HostEnvBuilder> = match arg.host_mode {
+                HostMode::DEFAULT => Box::new(DefaultHostEnvBuilder),
+                HostMode::STANDARD => Box::new(StandardHostEnvBuilder::default()),
+            };
+
+            arg.estimate(&env_builder)?;
+        }
+        Subcommands::PhantomFunctions(arg) => {
+            let config = Config::read(&mut fs::File::open(
+                cli.params_dir.join(&name_of_config(&cli.name)),
+            )?)?;
+
+            let env_builder: Box<dyn HostEnvBuilder> = match config.host_mode {
+                HostMode::DEFAULT => Box::new(DefaultHostEnvBuilder),
+                HostMode::STANDARD => Box::new(StandardHostEnvBuilder::default()),
+            };
+
+            config.list_phantom_functions(&env_builder, &arg.wasm_image, arg.json)?;
+        }
+        Subcommands::TraceDiff(arg) => arg.diff()?,
+        Subcommands::CheckContext(arg) => {
+            let config = Config::read(&mut fs::File::open(
+                cli.params_dir.join(&name_of_config(&cli.name)),
+            )?)?;
+
+            arg.check(&config.checksum)?;
+        }
     }
 
     Ok(())
@@ -47,3 +47,8 @@ pub(crate) fn name_of_etable_slice(name: &str, index: usize) -> String {
 pub(crate) fn name_of_frame_table_slice(name: &str, index: usize) -> String {
     format!("{}.frame_table.{}.data", name, index)
 }
+
+#[inline(always)]
+pub(crate) fn name_of_phantom_functions(name: &str) -> String {
+    format!("{}.phantom_functions.json", name)
+}
@@ -0,0 +1,76 @@
+pub mod sum;
+use ark_std::One;
+use ark_std::Zero;
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use std::ops::Shl;
+
+use super::fetch_u256;
+use super::inv_mod;
+
+lazy_static! {
+    /// Base field modulus of edwards25519: 2^255 - 19
+    pub static ref MODULUS: BigUint = BigUint::one().shl(255) - BigUint::from(19u64);
+    /// Curve parameter d = -121665 / 121666
+    pub static ref D: BigUint = {
+        let p = &*MODULUS;
+        (p - BigUint::from(121665u64)) * inv(&BigUint::from(121666u64)) % p
+    };
+}
+
+fn inv(v: &BigUint) -> BigUint {
+    inv_mod(v, &*MODULUS)
+}
+
+/// Affine point on the twisted Edwards curve -x^2 + y^2 = 1 + d * x^2 * y^2
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl Point {
+    pub fn identity() -> Self {
+        Point {
+            x: BigUint::zero(),
+            y: BigUint::one(),
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let p = &*MODULUS;
+        let x2 = &self.x * &self.x % p;
+        let y2 = &self.y * &self.y % p;
+        self.x < *p && self.y < *p && (&y2 + p - &x2) % p == (BigUint::one() + &*D * x2 * y2) % p
+    }
+
+    /// Complete addition law, valid for all points since d is not a square mod p.
+    pub fn add(&self, other: &Point) -> Point {
+        let p = &*MODULUS;
+        let t = &*D * &self.x * &other.x % p * &self.y * &other.y % p;
+        let x =
+            (&self.x * &other.y + &self.y * &other.x) % p * inv(&((BigUint::one() + &t) % p)) % p;
+        let y = (&self.y * &other.y + &self.x * &other.x) % p
+            * inv(&((BigUint::one() + p - &t) % p))
+            % p;
+        Point { x, y }
+    }
+
+    pub fn mul_scalar(&self, scalar: &BigUint) -> Point {
+        let mut acc = Point::identity();
+        for i in (0..scalar.bits()).rev() {
+            acc = acc.add(&acc);
+            if scalar.bit(i) {
+                acc = acc.add(self);
+            }
+        }
+        acc
+    }
+}
+
+fn fetch_point(limbs: &[u64]) -> Point {
+    Point {
+        x: fetch_u256(limbs, 0),
+        y: fetch_u256(limbs, 1),
+    }
+}
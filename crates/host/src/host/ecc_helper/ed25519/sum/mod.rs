@@ -0,0 +1,188 @@
+use delphinus_zkwasm::runtime::host::host_env::HostEnv;
+use delphinus_zkwasm::runtime::host::host_trap;
+use delphinus_zkwasm::runtime::host::ForeignContext;
+use delphinus_zkwasm::runtime::host::ForeignStatics;
+use delphinus_zkwasm::runtime::host::HostFunctionResult;
+use std::rc::Rc;
+use wasmi::Trap;
+use zkwasm_host_circuits::circuits::babyjub::AltJubChip;
+use zkwasm_host_circuits::circuits::host::HostOpSelector;
+
+use super::super::fetch_u256;
+use super::super::u256_to_limbs;
+use super::super::LIMBNB;
+use super::fetch_point;
+use super::Point;
+
+/// zkwasm-host-circuits neither allocates ed25519 entries in `ForeignInst` nor provides a circuit
+/// for them, so the op indices are taken from a range it does not use. The calls are recorded in
+/// the external host call table but no circuit checks their results: they are unproven, which is
+/// why the op is left out of the default `StandardHostEnvBuilder`.
+pub const ED25519_SUM_NEW: usize = 0x1000;
+pub const ED25519_SUM_PUSH: usize = 0x1001;
+pub const ED25519_SUM_RESULT: usize = 0x1002;
+
+/// Limbs of a point followed by the limbs of its scalar.
+const INPUT_SIZE: usize = LIMBNB * 2 + 4;
+
+pub struct Ed25519SumContext {
+    pub k: u32,
+    pub acc: Point,
+    pub inputs: Vec<u64>,
+    pub result_limbs: Option<Vec<u64>>,
+    pub result_cursor: usize,
+    pub used_round: usize,
+}
+
+impl Ed25519SumContext {
+    pub fn default(k: u32) -> Self {
+        Ed25519SumContext {
+            k,
+            acc: Point::identity(),
+            inputs: vec![],
+            result_limbs: None,
+            result_cursor: 0,
+            used_round: 0,
+        }
+    }
+
+    pub fn ed25519_sum_new(&mut self, new: usize) {
+        self.result_limbs = None;
+        self.result_cursor = 0;
+        self.inputs = vec![];
+        if new != 0 {
+            self.acc = Point::identity();
+        }
+    }
+
+    /// Pushes the limbs of a point then the limbs of its scalar, the product is added to the
+    /// accumulator once the scalar is complete so that any number of points can be summed.
+    pub fn ed25519_sum_push(&mut self, v: u64) -> Result<(), Trap> {
+        if self.result_limbs.is_some() {
+            return Err(host_trap(
+                "ed25519_sum_push after ed25519_sum_finalize without ed25519_sum_new",
+            ));
+        }
+
+        self.inputs.push(v);
+
+        if self.inputs.len() == INPUT_SIZE {
+            let point = fetch_point(&self.inputs);
+            if !point.is_on_curve() {
+                return Err(host_trap("ed25519 point is not on curve"));
+            }
+            let coeff = fetch_u256(&self.inputs, 2);
+            log::debug!("point is {:?}", point);
+            log::debug!("coeff is {:?}", coeff);
+
+            self.acc = self.acc.add(&point.mul_scalar(&coeff));
+            log::debug!("msm result: {:?}", self.acc);
+            self.inputs = vec![];
+        }
+
+        Ok(())
+    }
+
+    pub fn ed25519_sum_finalize(&mut self) -> Result<u64, Trap> {
+        if self.result_limbs.is_none() {
+            if !self.inputs.is_empty() {
+                return Err(host_trap(format!(
+                    "ed25519_sum_finalize with an incomplete point, {} of {} limbs pushed",
+                    self.inputs.len(),
+                    INPUT_SIZE
+                )));
+            }
+
+            self.ed25519_result_to_limbs(self.acc.clone());
+            self.used_round += 1;
+        }
+
+        let ret = self
+            .result_limbs
+            .as_ref()
+            .unwrap()
+            .get(self.result_cursor)
+            .copied()
+            .ok_or_else(|| host_trap("ed25519_sum_finalize reads past the result"))?;
+        self.result_cursor += 1;
+
+        Ok(ret)
+    }
+}
+
+impl Ed25519SumContext {
+    fn ed25519_result_to_limbs(&mut self, g: Point) {
+        let mut limbs = vec![];
+        u256_to_limbs(&mut limbs, &g.x);
+        u256_to_limbs(&mut limbs, &g.y);
+        self.result_limbs = Some(limbs);
+    }
+}
+
+impl ForeignContext for Ed25519SumContext {
+    /// Each finalized sum is a round. The op has no circuit yet, so its rounds are bounded like
+    /// those of the jubjub sum, the twisted Edwards curve the host circuits do check.
+    fn get_statics(&self) -> Option<ForeignStatics> {
+        Some(ForeignStatics {
+            used_round: self.used_round,
+            max_round: AltJubChip::max_rounds(self.k as usize),
+        })
+    }
+}
+
+use specs::external_host_call_table::ExternalHostCallSignature;
+pub fn register_ed25519sum_foreign(env: &mut HostEnv) {
+    let foreign_ed25519sum_plugin = env.external_env.register_plugin(
+        "foreign_ed25519sum",
+        Box::new(Ed25519SumContext::default(env.k)),
+    );
+
+    env.external_env.register_function(
+        "ed25519_sum_new",
+        ED25519_SUM_NEW,
+        ExternalHostCallSignature::Argument,
+        foreign_ed25519sum_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Ed25519SumContext>().unwrap();
+                context.ed25519_sum_new(args.nth::<u64>(0) as usize);
+                None
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "ed25519_sum_push",
+        ED25519_SUM_PUSH,
+        ExternalHostCallSignature::Argument,
+        foreign_ed25519sum_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Ed25519SumContext>().unwrap();
+                context.ed25519_sum_push(args.nth(0))?;
+                Ok(None)
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "ed25519_sum_finalize",
+        ED25519_SUM_RESULT,
+        ExternalHostCallSignature::Return,
+        foreign_ed25519sum_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             _args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Ed25519SumContext>().unwrap();
+                Ok(Some(wasmi::RuntimeValue::I64(
+                    context.ed25519_sum_finalize()? as i64,
+                )))
+            },
+        ),
+    );
+}
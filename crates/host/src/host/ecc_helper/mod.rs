@@ -1,11 +1,17 @@
 pub mod bls381;
 pub mod bn254;
+pub mod ed25519;
 pub mod jubjub;
+pub mod secp256k1;
 pub mod test;
 
 use halo2_proofs::arithmetic::BaseExt;
 use num_bigint::BigUint;
 
+/// Number of 64-bit limbs, least significant first, of the 256-bit integers exchanged by the
+/// ed25519 and secp256k1 ops.
+pub const LIMBNB: usize = 4;
+
 pub fn bn_to_field<F: BaseExt>(bn: &BigUint) -> F {
     let mut bytes = bn.to_bytes_le();
     bytes.resize(48, 0);
@@ -18,3 +24,24 @@ pub fn field_to_bn<F: BaseExt>(f: &F) -> BigUint {
     f.write(&mut bytes).unwrap();
     BigUint::from_bytes_le(&bytes[..])
 }
+
+/// Reads the `index`-th 256-bit integer of `limbs`.
+pub fn fetch_u256(limbs: &[u64], index: usize) -> BigUint {
+    BigUint::from_bytes_le(
+        &limbs[index * LIMBNB..(index + 1) * LIMBNB]
+            .iter()
+            .flat_map(|limb| limb.to_le_bytes())
+            .collect::<Vec<_>>(),
+    )
+}
+
+pub fn u256_to_limbs(result_limbs: &mut Vec<u64>, f: &BigUint) {
+    let mut digits = f.to_u64_digits();
+    digits.resize(LIMBNB, 0);
+    result_limbs.append(&mut digits);
+}
+
+/// Inverse of `v` in the prime field of `modulus`.
+pub fn inv_mod(v: &BigUint, modulus: &BigUint) -> BigUint {
+    v.modpow(&(modulus - BigUint::from(2u64)), modulus)
+}
@@ -0,0 +1,138 @@
+pub mod recover;
+use ark_std::One;
+use ark_std::Zero;
+use lazy_static::lazy_static;
+use num_bigint::BigUint;
+use num_traits::Num;
+use std::ops::Shl;
+
+use super::inv_mod;
+
+lazy_static! {
+    /// Base field modulus: 2^256 - 2^32 - 977
+    pub static ref MODULUS: BigUint =
+        BigUint::one().shl(256) - BigUint::one().shl(32) - BigUint::from(977u64);
+    /// Order of the generator
+    pub static ref ORDER: BigUint = BigUint::from_str_radix(
+        "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+        16
+    )
+    .unwrap();
+    pub static ref GENERATOR: Point = Point::from_xy(
+        BigUint::from_str_radix(
+            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+            16
+        )
+        .unwrap(),
+        BigUint::from_str_radix(
+            "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+            16
+        )
+        .unwrap(),
+    );
+}
+
+/// Affine point on y^2 = x^3 + 7
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub is_identity: bool,
+}
+
+impl Point {
+    pub fn identity() -> Self {
+        Point {
+            x: BigUint::zero(),
+            y: BigUint::zero(),
+            is_identity: true,
+        }
+    }
+
+    pub fn from_xy(x: BigUint, y: BigUint) -> Self {
+        Point {
+            x,
+            y,
+            is_identity: false,
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let p = &*MODULUS;
+        self.is_identity
+            || (self.x < *p
+                && self.y < *p
+                && &self.y * &self.y % p == (&self.x * &self.x * &self.x + 7u64) % p)
+    }
+
+    pub fn add(&self, other: &Point) -> Point {
+        let p = &*MODULUS;
+        if self.is_identity {
+            return other.clone();
+        }
+        if other.is_identity {
+            return self.clone();
+        }
+        let lambda = if self.x == other.x {
+            if (&self.y + &other.y) % p == BigUint::zero() {
+                return Point::identity();
+            }
+            BigUint::from(3u64) * &self.x * &self.x % p * inv_mod(&(&self.y * 2u64 % p), p) % p
+        } else {
+            (&other.y + p - &self.y) % p * inv_mod(&((&other.x + p - &self.x) % p), p) % p
+        };
+        let x = (&lambda * &lambda + p * 2u64 - &self.x - &other.x) % p;
+        let y = (&lambda * ((&self.x + p - &x) % p) + p - &self.y) % p;
+        Point::from_xy(x, y)
+    }
+
+    pub fn mul_scalar(&self, scalar: &BigUint) -> Point {
+        let mut acc = Point::identity();
+        for i in (0..scalar.bits()).rev() {
+            acc = acc.add(&acc);
+            if scalar.bit(i) {
+                acc = acc.add(self);
+            }
+        }
+        acc
+    }
+}
+
+/// Recovers the signer's public key from a message hash `z` and an ECDSA
+/// signature `(r, s, v)`, where `v` is the recovery id (0..=3).
+/// Returns None if the signature is malformed.
+pub fn recover(z: &BigUint, r: &BigUint, s: &BigUint, v: u64) -> Option<Point> {
+    let p = &*MODULUS;
+    let n = &*ORDER;
+
+    if r.is_zero() || r >= n || s.is_zero() || s >= n || v > 3 {
+        return None;
+    }
+
+    let x = if v & 2 != 0 { r + n } else { r.clone() };
+    if x >= *p {
+        return None;
+    }
+
+    let y2 = (&x * &x * &x + 7u64) % p;
+    let mut y = y2.modpow(&((p + 1u64) >> 2), p);
+    if &y * &y % p != y2 {
+        return None;
+    }
+    if y.bit(0) != (v & 1 != 0) {
+        y = p - y;
+    }
+
+    let r_inv = inv_mod(r, n);
+    let u1 = (n - z % n) * &r_inv % n;
+    let u2 = s * &r_inv % n;
+    let q = GENERATOR
+        .mul_scalar(&u1)
+        .add(&Point::from_xy(x, y).mul_scalar(&u2));
+
+    if q.is_identity {
+        None
+    } else {
+        Some(q)
+    }
+}
@@ -0,0 +1,179 @@
+use delphinus_zkwasm::runtime::host::host_env::HostEnv;
+use delphinus_zkwasm::runtime::host::host_trap;
+use delphinus_zkwasm::runtime::host::ForeignContext;
+use delphinus_zkwasm::runtime::host::ForeignStatics;
+use delphinus_zkwasm::runtime::host::HostFunctionResult;
+use std::rc::Rc;
+use wasmi::Trap;
+use zkwasm_host_circuits::circuits::bn256::Bn256SumChip;
+use zkwasm_host_circuits::circuits::host::HostOpSelector;
+
+use super::super::fetch_u256;
+use super::super::u256_to_limbs;
+use super::super::LIMBNB;
+use super::recover;
+
+/// Allocated next to the ed25519 ops, outside the `ForeignInst` range of zkwasm-host-circuits.
+/// No host circuit checks the recovered key, so the op is unproven and left out of the default
+/// `StandardHostEnvBuilder`.
+pub const SECP256K1_RECOVER_NEW: usize = 0x1010;
+pub const SECP256K1_RECOVER_PUSH: usize = 0x1011;
+pub const SECP256K1_RECOVER_RESULT: usize = 0x1012;
+
+/// message hash, r and s in limbs followed by the recovery id
+const INPUT_SIZE: usize = LIMBNB * 3 + 1;
+
+pub struct Secp256k1RecoverContext {
+    pub k: u32,
+    pub inputs: Vec<u64>,
+    pub result_limbs: Option<Vec<u64>>,
+    pub result_cursor: usize,
+    pub used_round: usize,
+}
+
+impl Secp256k1RecoverContext {
+    pub fn default(k: u32) -> Self {
+        Secp256k1RecoverContext {
+            k,
+            inputs: vec![],
+            result_limbs: None,
+            result_cursor: 0,
+            used_round: 0,
+        }
+    }
+
+    pub fn secp256k1_recover_new(&mut self, _new: usize) {
+        log::debug!("new secp256k1 recover context");
+        self.result_limbs = None;
+        self.result_cursor = 0;
+        self.inputs = vec![];
+        self.used_round += 1;
+    }
+
+    pub fn secp256k1_recover_push(&mut self, v: u64) -> Result<(), Trap> {
+        if self.inputs.len() == INPUT_SIZE {
+            return Err(host_trap(format!(
+                "secp256k1_recover_push takes {} limbs",
+                INPUT_SIZE
+            )));
+        }
+
+        self.inputs.push(v);
+
+        Ok(())
+    }
+
+    /// Returns the limbs of x and y of the public key, followed by 1 if the
+    /// recovery failed and 0 otherwise.
+    pub fn secp256k1_recover_finalize(&mut self) -> Result<u64, Trap> {
+        if self.result_limbs.is_none() {
+            if self.inputs.len() != INPUT_SIZE {
+                return Err(host_trap(format!(
+                    "secp256k1_recover_finalize with {} of {} limbs pushed",
+                    self.inputs.len(),
+                    INPUT_SIZE
+                )));
+            }
+
+            let z = fetch_u256(&self.inputs, 0);
+            let r = fetch_u256(&self.inputs, 1);
+            let s = fetch_u256(&self.inputs, 2);
+            let v = self.inputs[LIMBNB * 3];
+            let pubkey = recover(&z, &r, &s, v);
+            log::debug!("recovered public key: {:?}", pubkey);
+
+            let mut limbs = vec![];
+            match pubkey {
+                Some(q) => {
+                    u256_to_limbs(&mut limbs, &q.x);
+                    u256_to_limbs(&mut limbs, &q.y);
+                    limbs.push(0);
+                }
+                None => {
+                    limbs.resize(LIMBNB * 2, 0);
+                    limbs.push(1);
+                }
+            }
+            self.result_limbs = Some(limbs);
+        }
+
+        let ret = self
+            .result_limbs
+            .as_ref()
+            .unwrap()
+            .get(self.result_cursor)
+            .copied()
+            .ok_or_else(|| host_trap("secp256k1_recover_finalize reads past the result"))?;
+        self.result_cursor += 1;
+
+        Ok(ret)
+    }
+}
+
+impl ForeignContext for Secp256k1RecoverContext {
+    /// The op has no circuit yet, so its rounds are bounded like those of the bn254 sum, the
+    /// non-native multi-scalar multiplication the host circuits do check.
+    fn get_statics(&self) -> Option<ForeignStatics> {
+        Some(ForeignStatics {
+            used_round: self.used_round,
+            max_round: Bn256SumChip::max_rounds(self.k as usize),
+        })
+    }
+}
+
+use specs::external_host_call_table::ExternalHostCallSignature;
+pub fn register_secp256k1recover_foreign(env: &mut HostEnv) {
+    let foreign_secp256k1recover_plugin = env.external_env.register_plugin(
+        "foreign_secp256k1recover",
+        Box::new(Secp256k1RecoverContext::default(env.k)),
+    );
+
+    env.external_env.register_function(
+        "secp256k1_recover_new",
+        SECP256K1_RECOVER_NEW,
+        ExternalHostCallSignature::Argument,
+        foreign_secp256k1recover_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Secp256k1RecoverContext>().unwrap();
+                context.secp256k1_recover_new(args.nth::<u64>(0) as usize);
+                None
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "secp256k1_recover_push",
+        SECP256K1_RECOVER_PUSH,
+        ExternalHostCallSignature::Argument,
+        foreign_secp256k1recover_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Secp256k1RecoverContext>().unwrap();
+                context.secp256k1_recover_push(args.nth::<u64>(0))?;
+                Ok(None)
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "secp256k1_recover_finalize",
+        SECP256K1_RECOVER_RESULT,
+        ExternalHostCallSignature::Return,
+        foreign_secp256k1recover_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             _args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Secp256k1RecoverContext>().unwrap();
+                Ok(Some(wasmi::RuntimeValue::I64(
+                    context.secp256k1_recover_finalize()? as i64,
+                )))
+            },
+        ),
+    );
+}
@@ -106,4 +106,157 @@ pub(crate) mod tests {
         //println!("\npair sum {:?}", pair_ab +  pair_gamma + pair_delta - pair_alphabeta);
         println!("\npair alpha beta {:?}", pair_alphabeta);
     }
+
+    #[test]
+    fn test_ed25519_sum() {
+        use super::super::ed25519::sum::Ed25519SumContext;
+        use super::super::ed25519::Point;
+        use super::super::u256_to_limbs;
+        use delphinus_zkwasm::circuits::config::MIN_K;
+        use delphinus_zkwasm::runtime::host::ForeignContext;
+
+        let base = Point {
+            x: BigUint::from_str(
+                "15112221349535400772501151409588531511454012693041857206046113283949847762202",
+            )
+            .unwrap(),
+            y: BigUint::from_str(
+                "46316835694926478169428394003475163141307993866256225615783033603165251855960",
+            )
+            .unwrap(),
+        };
+        assert!(base.is_on_curve());
+
+        let order = BigUint::from_str(
+            "7237005577332262213973186563042994240857116359379907606001950938285454250989",
+        )
+        .unwrap();
+        assert_eq!(base.mul_scalar(&order), Point::identity());
+        assert_eq!(base.add(&base), base.mul_scalar(&BigUint::from(2u64)));
+
+        let limbs_of = |point: &Point| {
+            let mut limbs = vec![];
+            u256_to_limbs(&mut limbs, &point.x);
+            u256_to_limbs(&mut limbs, &point.y);
+            limbs
+        };
+        let push = |context: &mut Ed25519SumContext, point: &Point, scalar: u64| {
+            for limb in limbs_of(point).into_iter().chain([scalar, 0, 0, 0]) {
+                context.ed25519_sum_push(limb).unwrap();
+            }
+        };
+        let finalize = |context: &mut Ed25519SumContext| {
+            (0..8)
+                .map(|_| context.ed25519_sum_finalize().unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // acc = base * 3 through the host op interface
+        let mut context = Ed25519SumContext::default(MIN_K);
+        context.ed25519_sum_new(1);
+        push(&mut context, &base, 3);
+        assert_eq!(
+            finalize(&mut context),
+            limbs_of(&base.mul_scalar(&BigUint::from(3u64)))
+        );
+
+        // acc = base * 3 + (base * 2) * 2 + base * 5, the points are summed in one round
+        let double = base.add(&base);
+        context.ed25519_sum_new(1);
+        push(&mut context, &base, 3);
+        push(&mut context, &double, 2);
+        push(&mut context, &base, 5);
+        assert_eq!(
+            finalize(&mut context),
+            limbs_of(&base.mul_scalar(&BigUint::from(12u64)))
+        );
+
+        // the accumulator is kept by `ed25519_sum_new(0)`
+        context.ed25519_sum_new(0);
+        push(&mut context, &base, 1);
+        assert_eq!(
+            finalize(&mut context),
+            limbs_of(&base.mul_scalar(&BigUint::from(13u64)))
+        );
+        assert_eq!(context.get_statics().unwrap().used_round, 3);
+
+        // malformed inputs trap instead of aborting the host
+        context.ed25519_sum_new(1);
+        let mut off_curve = limbs_of(&base);
+        off_curve[0] += 1;
+        for limb in off_curve.into_iter().chain([1, 0, 0]) {
+            context.ed25519_sum_push(limb).unwrap();
+        }
+        assert!(context.ed25519_sum_push(0).is_err());
+
+        context.ed25519_sum_new(1);
+        context.ed25519_sum_push(1).unwrap();
+        assert!(context.ed25519_sum_finalize().is_err());
+
+        context.ed25519_sum_new(1);
+        finalize(&mut context);
+        assert!(context.ed25519_sum_finalize().is_err());
+    }
+
+    #[test]
+    fn test_secp256k1_recover() {
+        use super::super::secp256k1::recover;
+        use super::super::secp256k1::recover::Secp256k1RecoverContext;
+        use super::super::secp256k1::GENERATOR;
+        use super::super::u256_to_limbs;
+        use delphinus_zkwasm::circuits::config::MIN_K;
+        use delphinus_zkwasm::runtime::host::ForeignContext;
+        use num_traits::Num;
+
+        // signed by the private key 1, whose public key is the generator
+        let z = BigUint::from_str_radix(
+            "eeb2beb02299bf18c36691d965c1e881ebe63ad673d175829620ed6ab51f6bd7",
+            16,
+        )
+        .unwrap();
+        let r = BigUint::from_str_radix(
+            "f973a0b87062c389d125d8199e803b832b6ac6bf7867a4f6cd87506060fc4c58",
+            16,
+        )
+        .unwrap();
+        let s = BigUint::from_str_radix(
+            "1f1523952fa17974e2b985023d10feff3378d76ac2452576cd3521b804e52ac4",
+            16,
+        )
+        .unwrap();
+
+        assert!(GENERATOR.is_on_curve());
+        assert_eq!(recover(&z, &r, &s, 0), Some(GENERATOR.clone()));
+        assert_ne!(recover(&z, &r, &s, 1), Some(GENERATOR.clone()));
+        assert_eq!(recover(&z, &BigUint::from(0u64), &s, 0), None);
+
+        let mut context = Secp256k1RecoverContext::default(MIN_K);
+        let mut limbs = vec![];
+        u256_to_limbs(&mut limbs, &z);
+        u256_to_limbs(&mut limbs, &r);
+        u256_to_limbs(&mut limbs, &s);
+        limbs.push(0);
+
+        context.secp256k1_recover_new(0);
+        for limb in &limbs[..limbs.len() - 1] {
+            context.secp256k1_recover_push(*limb).unwrap();
+        }
+        assert!(context.secp256k1_recover_finalize().is_err());
+
+        context.secp256k1_recover_new(0);
+        for limb in &limbs {
+            context.secp256k1_recover_push(*limb).unwrap();
+        }
+        assert!(context.secp256k1_recover_push(0).is_err());
+
+        let mut expected = vec![];
+        u256_to_limbs(&mut expected, &GENERATOR.x);
+        u256_to_limbs(&mut expected, &GENERATOR.y);
+        expected.push(0);
+        for limb in expected {
+            assert_eq!(context.secp256k1_recover_finalize().unwrap(), limb);
+        }
+        assert!(context.secp256k1_recover_finalize().is_err());
+        assert_eq!(context.get_statics().unwrap().used_round, 2);
+    }
 }
@@ -0,0 +1,247 @@
+use blake2::digest::Update;
+use blake2::digest::VariableOutput;
+use blake2::Blake2bVar;
+use delphinus_zkwasm::runtime::host::host_env::HostEnv;
+use delphinus_zkwasm::runtime::host::host_trap;
+use delphinus_zkwasm::runtime::host::ForeignContext;
+use delphinus_zkwasm::runtime::host::ForeignStatics;
+use delphinus_zkwasm::runtime::host::HostFunctionResult;
+use std::rc::Rc;
+use wasmi::Trap;
+use zkwasm_host_circuits::circuits::host::HostOpSelector;
+use zkwasm_host_circuits::circuits::keccak256::KeccakChip;
+
+use super::Generator;
+
+/// Taken outside the `ForeignInst` range of zkwasm-host-circuits, next to the local ecc ops.
+///
+/// The digest is not proven: the external host call table only records the limbs exchanged with
+/// the host, and no host circuit checks that they are the BLAKE2b digest of the pushed message.
+/// The op is therefore left out of the default `StandardHostEnvBuilder`.
+pub const BLAKE2B_NEW: usize = 0x1020;
+pub const BLAKE2B_PUSH: usize = 0x1021;
+pub const BLAKE2B_FINALIZE: usize = 0x1022;
+
+const MAX_DIGEST_SIZE: usize = 64;
+
+/// Foreign functions that supports the following C code library
+///
+/// void blake2b(uint8_t* data, uint32_t size, uint32_t digest_size, uint64_t* r)
+/// {
+///     int i;
+///     blake2b_new(((uint64_t)digest_size << 32) | size);
+///     for(i=0; i<size; i=i+8) {
+///         blake2b_push(*((uint64_t *)(&data[i])));
+///     }
+///     for(i=0; i<(digest_size+7)/8; i++) {
+///         r[i] = blake2b_finalize();
+///     }
+/// }
+///
+/// The lower 32 bits of the argument of `blake2b_new` are the message size in
+/// bytes and the higher 32 bits the digest size in bytes (0 stands for 64).
+
+struct Blake2bContext {
+    pub k: u32,
+    pub hasher: Option<Blake2bVar>,
+    pub generator: Generator,
+    pub size: usize,
+    pub used_round: usize,
+}
+
+impl Blake2bContext {
+    fn default(k: u32) -> Self {
+        Blake2bContext {
+            k,
+            hasher: None,
+            generator: Generator {
+                cursor: 0,
+                values: vec![],
+            },
+            size: 0,
+            used_round: 0,
+        }
+    }
+
+    pub fn blake2b_new(&mut self, arg: u64) -> Result<(), Trap> {
+        let size = (arg & 0xffff_ffff) as usize;
+        let digest_size = match (arg >> 32) as usize {
+            0 => MAX_DIGEST_SIZE,
+            s => s,
+        };
+        if digest_size > MAX_DIGEST_SIZE {
+            return Err(host_trap(format!(
+                "blake2b digest size {} exceeds {} bytes",
+                digest_size, MAX_DIGEST_SIZE
+            )));
+        }
+
+        self.hasher = Some(Blake2bVar::new(digest_size).unwrap());
+        self.generator.cursor = 0;
+        self.generator.values = vec![];
+        self.size = size;
+        self.used_round += 1;
+
+        Ok(())
+    }
+
+    pub fn blake2b_push(&mut self, v: u64) {
+        let sz = std::cmp::min(self.size, 8);
+        self.size -= sz;
+        self.hasher
+            .as_mut()
+            .map(|s| s.update(&v.to_le_bytes()[..sz]));
+    }
+
+    pub fn blake2b_finalize(&mut self) -> Result<u64, Trap> {
+        self.hasher.take().map(|s| {
+            let mut digest = vec![0u8; s.output_size()];
+            s.finalize_variable(&mut digest).unwrap();
+            digest.resize((digest.len() + 7) / 8 * 8, 0);
+            self.generator.values = digest
+                .chunks(8)
+                .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<u64>>();
+        });
+        self.generator
+            .try_gen()
+            .ok_or_else(|| host_trap("blake2b_finalize reads past the digest"))
+    }
+}
+
+impl ForeignContext for Blake2bContext {
+    /// Each hashed message is a round. The op has no circuit yet, so its rounds are bounded like
+    /// those of keccak256.
+    fn get_statics(&self) -> Option<ForeignStatics> {
+        Some(ForeignStatics {
+            used_round: self.used_round,
+            max_round: KeccakChip::max_rounds(self.k as usize),
+        })
+    }
+}
+
+use specs::external_host_call_table::ExternalHostCallSignature;
+pub fn register_blake2b_foreign(env: &mut HostEnv) {
+    let foreign_blake2b_plugin = env
+        .external_env
+        .register_plugin("foreign_blake2b", Box::new(Blake2bContext::default(env.k)));
+
+    env.external_env.register_fallible_function(
+        "blake2b_new",
+        BLAKE2B_NEW,
+        ExternalHostCallSignature::Argument,
+        foreign_blake2b_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Blake2bContext>().unwrap();
+                context.blake2b_new(args.nth::<u64>(0))?;
+                Ok(None)
+            },
+        ),
+    );
+
+    env.external_env.register_function(
+        "blake2b_push",
+        BLAKE2B_PUSH,
+        ExternalHostCallSignature::Argument,
+        foreign_blake2b_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Blake2bContext>().unwrap();
+                context.blake2b_push(args.nth::<u64>(0));
+                None
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "blake2b_finalize",
+        BLAKE2B_FINALIZE,
+        ExternalHostCallSignature::Return,
+        foreign_blake2b_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             _args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Blake2bContext>().unwrap();
+                Ok(Some(wasmi::RuntimeValue::I64(
+                    context.blake2b_finalize()? as i64
+                )))
+            },
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Blake2bContext;
+    use blake2::digest::Update;
+    use blake2::digest::VariableOutput;
+    use blake2::Blake2bVar;
+    use delphinus_zkwasm::circuits::config::MIN_K;
+    use delphinus_zkwasm::runtime::host::ForeignContext;
+
+    fn blake2b(message: &[u8], digest_size: usize) -> Vec<u8> {
+        let mut context = Blake2bContext::default(MIN_K);
+        context
+            .blake2b_new(((digest_size as u64) << 32) | message.len() as u64)
+            .unwrap();
+        for chunk in message.chunks(8) {
+            let mut limb = [0u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            context.blake2b_push(u64::from_le_bytes(limb));
+        }
+        let mut digest = (0..(digest_size + 7) / 8)
+            .map(|_| context.blake2b_finalize().unwrap().to_le_bytes())
+            .flatten()
+            .collect::<Vec<_>>();
+        assert!(context.blake2b_finalize().is_err());
+        assert_eq!(context.get_statics().unwrap().used_round, 1);
+
+        digest.truncate(digest_size);
+        digest
+    }
+
+    #[test]
+    fn test_blake2b_known_answers() {
+        // RFC 7693, Appendix A
+        assert_eq!(
+            hex::encode(blake2b(b"abc", 64)),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d1\
+             7d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        // the empty message still compresses one block
+        assert_eq!(
+            hex::encode(blake2b(b"", 64)),
+            "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419\
+             d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+        );
+    }
+
+    #[test]
+    fn test_blake2b_blocks() {
+        // spans two blocks and ends in a partial limb
+        let message = (0..201).map(|i| i as u8).collect::<Vec<_>>();
+
+        for digest_size in [20, 32, 64] {
+            let mut hasher = Blake2bVar::new(digest_size).unwrap();
+            hasher.update(&message);
+            let mut expected = vec![0u8; digest_size];
+            hasher.finalize_variable(&mut expected).unwrap();
+
+            assert_eq!(blake2b(&message, digest_size), expected);
+        }
+    }
+
+    #[test]
+    fn test_blake2b_traps() {
+        let mut context = Blake2bContext::default(MIN_K);
+        assert!(context.blake2b_new((65 << 32) | 3).is_err());
+        assert!(context.blake2b_finalize().is_err());
+        assert_eq!(context.get_statics().unwrap().used_round, 0);
+    }
+}
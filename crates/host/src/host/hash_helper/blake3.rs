@@ -0,0 +1,206 @@
+use delphinus_zkwasm::runtime::host::host_env::HostEnv;
+use delphinus_zkwasm::runtime::host::host_trap;
+use delphinus_zkwasm::runtime::host::ForeignContext;
+use delphinus_zkwasm::runtime::host::ForeignStatics;
+use delphinus_zkwasm::runtime::host::HostFunctionResult;
+use std::rc::Rc;
+use wasmi::Trap;
+use zkwasm_host_circuits::circuits::host::HostOpSelector;
+use zkwasm_host_circuits::circuits::keccak256::KeccakChip;
+
+use super::Generator;
+
+/// Taken outside the `ForeignInst` range of zkwasm-host-circuits, next to the blake2b ops.
+///
+/// Like blake2b, the digest is not proven by any host circuit, so the op is left out of the
+/// default `StandardHostEnvBuilder`.
+pub const BLAKE3_NEW: usize = 0x1030;
+pub const BLAKE3_PUSH: usize = 0x1031;
+pub const BLAKE3_FINALIZE: usize = 0x1032;
+
+/// Foreign functions that supports the following C code library
+///
+/// void blake3(uint8_t* data, uint32_t size, uint64_t* r)
+/// {
+///     int i;
+///     blake3_new(size);
+///     for(i=0; i<size; i=i+8) {
+///         blake3_push(*((uint64_t *)(&data[i])));
+///     }
+///     r[0] = blake3_finalize();
+///     r[1] = blake3_finalize();
+///     r[2] = blake3_finalize();
+///     r[3] = blake3_finalize();
+/// }
+
+struct Blake3Context {
+    pub k: u32,
+    pub hasher: Option<::blake3::Hasher>,
+    pub generator: Generator,
+    pub size: usize,
+    pub used_round: usize,
+}
+
+impl Blake3Context {
+    fn default(k: u32) -> Self {
+        Blake3Context {
+            k,
+            hasher: None,
+            generator: Generator {
+                cursor: 0,
+                values: vec![],
+            },
+            size: 0,
+            used_round: 0,
+        }
+    }
+
+    pub fn blake3_new(&mut self, size: usize) {
+        self.hasher = Some(::blake3::Hasher::new());
+        self.generator.cursor = 0;
+        self.generator.values = vec![];
+        self.size = size;
+        self.used_round += 1;
+    }
+
+    pub fn blake3_push(&mut self, v: u64) {
+        let sz = std::cmp::min(self.size, 8);
+        self.size -= sz;
+        self.hasher
+            .as_mut()
+            .map(|s| s.update(&v.to_le_bytes()[..sz]));
+    }
+
+    pub fn blake3_finalize(&mut self) -> Result<u64, Trap> {
+        self.hasher.take().map(|s| {
+            self.generator.values = s
+                .finalize()
+                .as_bytes()
+                .chunks(8)
+                .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+                .collect::<Vec<u64>>();
+        });
+        self.generator
+            .try_gen()
+            .ok_or_else(|| host_trap("blake3_finalize reads past the digest"))
+    }
+}
+
+impl ForeignContext for Blake3Context {
+    /// Rounds are counted and bounded like those of blake2b.
+    fn get_statics(&self) -> Option<ForeignStatics> {
+        Some(ForeignStatics {
+            used_round: self.used_round,
+            max_round: KeccakChip::max_rounds(self.k as usize),
+        })
+    }
+}
+
+use specs::external_host_call_table::ExternalHostCallSignature;
+pub fn register_blake3_foreign(env: &mut HostEnv) {
+    let foreign_blake3_plugin = env
+        .external_env
+        .register_plugin("foreign_blake3", Box::new(Blake3Context::default(env.k)));
+
+    env.external_env.register_function(
+        "blake3_new",
+        BLAKE3_NEW,
+        ExternalHostCallSignature::Argument,
+        foreign_blake3_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Blake3Context>().unwrap();
+                context.blake3_new(args.nth::<u64>(0) as usize);
+                None
+            },
+        ),
+    );
+
+    env.external_env.register_function(
+        "blake3_push",
+        BLAKE3_PUSH,
+        ExternalHostCallSignature::Argument,
+        foreign_blake3_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Blake3Context>().unwrap();
+                context.blake3_push(args.nth::<u64>(0));
+                None
+            },
+        ),
+    );
+
+    env.external_env.register_fallible_function(
+        "blake3_finalize",
+        BLAKE3_FINALIZE,
+        ExternalHostCallSignature::Return,
+        foreign_blake3_plugin.clone(),
+        Rc::new(
+            |_obs,
+             context: &mut dyn ForeignContext,
+             _args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<Blake3Context>().unwrap();
+                Ok(Some(wasmi::RuntimeValue::I64(
+                    context.blake3_finalize()? as i64
+                )))
+            },
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Blake3Context;
+    use delphinus_zkwasm::circuits::config::MIN_K;
+    use delphinus_zkwasm::runtime::host::ForeignContext;
+
+    fn blake3(message: &[u8]) -> Vec<u8> {
+        let mut context = Blake3Context::default(MIN_K);
+        context.blake3_new(message.len());
+        for chunk in message.chunks(8) {
+            let mut limb = [0u8; 8];
+            limb[..chunk.len()].copy_from_slice(chunk);
+            context.blake3_push(u64::from_le_bytes(limb));
+        }
+        let digest = (0..4)
+            .map(|_| context.blake3_finalize().unwrap().to_le_bytes())
+            .flatten()
+            .collect::<Vec<_>>();
+        assert!(context.blake3_finalize().is_err());
+        assert_eq!(context.get_statics().unwrap().used_round, 1);
+
+        digest
+    }
+
+    #[test]
+    fn test_blake3_known_answers() {
+        // test_vectors.json of the BLAKE3 reference implementation, whose inputs repeat the
+        // bytes 0..251
+        assert_eq!(
+            hex::encode(blake3(b"")),
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+        );
+        assert_eq!(
+            hex::encode(blake3(&[0])),
+            "2d3adedff11b61f14c886e35afa036736dcd87a74d27b5c1510225d0f592e213"
+        );
+    }
+
+    #[test]
+    fn test_blake3_chunks() {
+        // spans two chunks and ends in a partial limb
+        let message = (0..1025).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+
+        assert_eq!(
+            blake3(&message),
+            ::blake3::hash(&message).as_bytes().to_vec()
+        );
+    }
+
+    #[test]
+    fn test_blake3_traps() {
+        let mut context = Blake3Context::default(MIN_K);
+        assert!(context.blake3_finalize().is_err());
+    }
+}
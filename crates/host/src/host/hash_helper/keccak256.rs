@@ -9,20 +9,9 @@ use zkwasm_host_circuits::host::ForeignInst::Keccak256Finalize;
 use zkwasm_host_circuits::host::ForeignInst::Keccak256New;
 use zkwasm_host_circuits::host::ForeignInst::Keccak256Push;
 
-pub use zkwasm_host_circuits::host::keccak256::KECCAK_HASHER;
-
-struct Generator {
-    pub cursor: usize,
-    pub values: Vec<u64>,
-}
+use super::Generator;
 
-impl Generator {
-    fn gen(&mut self) -> u64 {
-        let r = self.values[self.cursor];
-        self.cursor += 1;
-        r
-    }
-}
+pub use zkwasm_host_circuits::host::keccak256::KECCAK_HASHER;
 
 struct Keccak256Context {
     pub k: u32,
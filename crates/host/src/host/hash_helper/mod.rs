@@ -1,3 +1,27 @@
+pub mod blake2b;
+#[cfg(feature = "blake3")]
+pub mod blake3;
 pub mod keccak256;
 pub mod poseidon;
 pub mod sha256;
+
+/// Hands out the limbs of a digest one `finalize` call at a time.
+pub(crate) struct Generator {
+    pub cursor: usize,
+    pub values: Vec<u64>,
+}
+
+impl Generator {
+    pub(crate) fn gen(&mut self) -> u64 {
+        let r = self.values[self.cursor];
+        self.cursor += 1;
+        r
+    }
+
+    /// Like `gen`, but returns None once every limb has been read.
+    pub(crate) fn try_gen(&mut self) -> Option<u64> {
+        let r = self.values.get(self.cursor).copied()?;
+        self.cursor += 1;
+        Some(r)
+    }
+}
@@ -7,6 +7,8 @@ use zkwasm_host_circuits::host::ForeignInst::SHA256Finalize;
 use zkwasm_host_circuits::host::ForeignInst::SHA256New;
 use zkwasm_host_circuits::host::ForeignInst::SHA256Push;
 
+use super::Generator;
+
 use sha2::Sha256;
 
 /// Foreign functions that supports the following C code library
@@ -35,19 +37,6 @@ use sha2::Sha256;
 ///     wasm_dbg(r[3]);
 /// }
 
-struct Generator {
-    pub cursor: usize,
-    pub values: Vec<u64>,
-}
-
-impl Generator {
-    fn gen(&mut self) -> u64 {
-        let r = self.values[self.cursor];
-        self.cursor += 1;
-        r
-    }
-}
-
 struct Sha256Context {
     pub hasher: Option<Sha256>,
     pub generator: Generator,
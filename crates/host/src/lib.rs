@@ -25,15 +25,32 @@ pub struct StandardExecutionArg {
     pub private_inputs: Vec<u64>,
     /// Context inputs for `wasm_read_context()`
     pub context_inputs: Vec<u64>,
+    /// Private inputs for `wasm_indexed_input(index)`
+    pub indexed_inputs: Vec<u64>,
     /// indexed witness context
     pub indexed_witness: Rc<RefCell<HashMap<u64, Vec<u64>>>>,
     /// db src
     pub tree_db: Option<Rc<RefCell<dyn TreeDB>>>,
 }
 
+/// Host ops provided by this crate without an `OpType` in zkwasm-host-circuits.
+///
+/// No host circuit checks their results, so they are unproven and only registered when enabled by
+/// `StandardHostEnvBuilder::with_local_ops`.
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub enum LocalOpType {
+    ED25519SUM,
+    SECP256K1RECOVER,
+    BLAKE2BHASH,
+    #[cfg(feature = "blake3")]
+    BLAKE3HASH,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct HostEnvConfig {
     pub ops: Vec<OpType>,
+    #[serde(default)]
+    pub local_ops: Vec<LocalOpType>,
 }
 
 impl HostEnvConfig {
@@ -53,15 +70,33 @@ impl HostEnvConfig {
         }
     }
 
+    fn register_local_op(op: &LocalOpType, env: &mut HostEnv) {
+        match op {
+            LocalOpType::ED25519SUM => {
+                host::ecc_helper::ed25519::sum::register_ed25519sum_foreign(env)
+            }
+            LocalOpType::SECP256K1RECOVER => {
+                host::ecc_helper::secp256k1::recover::register_secp256k1recover_foreign(env)
+            }
+            LocalOpType::BLAKE2BHASH => host::hash_helper::blake2b::register_blake2b_foreign(env),
+            #[cfg(feature = "blake3")]
+            LocalOpType::BLAKE3HASH => host::hash_helper::blake3::register_blake3_foreign(env),
+        }
+    }
+
     fn register_ops(&self, env: &mut HostEnv) {
         for op in &self.ops {
             Self::register_op(op, env);
         }
+        for op in &self.local_ops {
+            Self::register_local_op(op, env);
+        }
     }
 }
 
 pub struct StandardHostEnvBuilder {
     ops: Vec<OpType>,
+    local_ops: Vec<LocalOpType>,
 }
 
 impl Default for StandardHostEnvBuilder {
@@ -74,17 +109,32 @@ impl Default for StandardHostEnvBuilder {
                 OpType::KECCAKHASH,
                 OpType::BN256SUM,
             ],
+            local_ops: vec![],
         }
     }
 }
 
+impl StandardHostEnvBuilder {
+    /// Registers the local ops in addition to the default ones. Their results are not proven.
+    pub fn with_local_ops(mut self, local_ops: Vec<LocalOpType>) -> Self {
+        for op in local_ops {
+            if !self.local_ops.contains(&op) {
+                self.local_ops.push(op);
+            }
+        }
+
+        self
+    }
+}
+
 impl HostEnvBuilder for StandardHostEnvBuilder {
     fn create_env_without_value(&self, k: u32) -> HostEnv {
         let mut env = HostEnv::new(k);
         let host_env_config = HostEnvConfig {
             ops: self.ops.clone(),
+            local_ops: self.local_ops.clone(),
         };
-        register_wasm_input_foreign(&mut env, vec![], vec![]);
+        register_wasm_input_foreign(&mut env, vec![], vec![], vec![]);
         register_require_foreign(&mut env);
         register_log_foreign(&mut env);
         register_context_foreign(&mut env, vec![]);
@@ -103,16 +153,23 @@ impl HostEnvBuilder for StandardHostEnvBuilder {
         let mut env = HostEnv::new(k);
         let host_env_config = HostEnvConfig {
             ops: self.ops.clone(),
+            local_ops: self.local_ops.clone(),
         };
         let arg = StandardExecutionArg {
             public_inputs: arg.public_inputs,
             private_inputs: arg.private_inputs,
             context_inputs: arg.context_inputs,
+            indexed_inputs: arg.indexed_inputs,
             indexed_witness: Rc::new(RefCell::new(HashMap::new())),
             tree_db: None,
         };
 
-        register_wasm_input_foreign(&mut env, arg.public_inputs, arg.private_inputs);
+        register_wasm_input_foreign(
+            &mut env,
+            arg.public_inputs,
+            arg.private_inputs,
+            arg.indexed_inputs,
+        );
         register_require_foreign(&mut env);
         register_log_foreign(&mut env);
         register_context_foreign(&mut env, arg.context_inputs);
@@ -0,0 +1,54 @@
+#include <stdint.h>
+#include "foreign.h"
+#include "secp256k1.h"
+
+/* Keccak256 Plugin */
+extern void keccak_new(uint64_t);
+extern void keccak_push(uint64_t);
+extern uint64_t keccak_finalize();
+
+/*
+ * Private inputs: message hash, r, s (4 limbs each) and the recovery id.
+ * Public inputs: the expected signer address in 3 words.
+ */
+__attribute__((visibility("default")))
+void
+zkmain()
+{
+    uint64_t hash[4], r[4], s[4], pubkey[8], digest[4];
+
+    for (int i = 0; i < 4; i++) {
+        hash[i] = read_private_input();
+    }
+    for (int i = 0; i < 4; i++) {
+        r[i] = read_private_input();
+    }
+    for (int i = 0; i < 4; i++) {
+        s[i] = read_private_input();
+    }
+    uint64_t v = read_private_input();
+
+    require(secp256k1_recover(hash, r, s, v, pubkey) == 0);
+
+    /* keccak256 over the big-endian encoding of x || y, padded to one block */
+    keccak_new(1);
+    for (int i = 3; i >= 0; i--) {
+        keccak_push(__builtin_bswap64(pubkey[i]));
+    }
+    for (int i = 7; i >= 4; i--) {
+        keccak_push(__builtin_bswap64(pubkey[i]));
+    }
+    keccak_push(1);
+    for (int i = 0; i < 7; i++) {
+        keccak_push(0);
+    }
+    keccak_push(0x8000000000000000);
+    for (int i = 0; i < 4; i++) {
+        digest[i] = keccak_finalize();
+    }
+
+    /* the address is the last 20 bytes of the digest */
+    require((digest[1] >> 32) == read_public_input());
+    require(digest[2] == read_public_input());
+    require(digest[3] == read_public_input());
+}
@@ -19,6 +19,9 @@ static inline uint64_t read_private_input()
 /* Require Plugin */
 extern void require(uint32_t);
 
+/* Guest Log Plugin, only callable within phantom functions */
+extern void wasm_log(uint32_t level, const char* message, uint32_t len);
+
 /* Context Cont Plugin */
 extern uint64_t wasm_read_context();
 extern void wasm_write_context(uint64_t);
@@ -0,0 +1,42 @@
+#ifndef SECP256K1_H
+#define SECP256K1_H
+
+#include <stdint.h>
+
+/* Secp256k1 Recover Plugin */
+extern void secp256k1_recover_new(uint64_t);
+extern void secp256k1_recover_push(uint64_t);
+extern uint64_t secp256k1_recover_finalize();
+
+/*
+ * Recovers the public key from a signature over a 32-byte message hash.
+ * hash, r and s are little-endian u64 limbs, v is the recovery id (0..=3).
+ * On success, pubkey receives the limbs of x followed by the limbs of y
+ * and 0 is returned; otherwise 1 is returned.
+ */
+static inline uint64_t secp256k1_recover(
+	const uint64_t hash[4],
+	const uint64_t r[4],
+	const uint64_t s[4],
+	uint64_t v,
+	uint64_t pubkey[8]
+)
+{
+	secp256k1_recover_new(0);
+	for (int i = 0; i < 4; i++) {
+		secp256k1_recover_push(hash[i]);
+	}
+	for (int i = 0; i < 4; i++) {
+		secp256k1_recover_push(r[i]);
+	}
+	for (int i = 0; i < 4; i++) {
+		secp256k1_recover_push(s[i]);
+	}
+	secp256k1_recover_push(v);
+	for (int i = 0; i < 8; i++) {
+		pubkey[i] = secp256k1_recover_finalize();
+	}
+	return secp256k1_recover_finalize();
+}
+
+#endif
@@ -22,6 +22,7 @@ fn main() -> Result<()> {
                 public_inputs: vec![],
                 private_inputs: vec![],
                 context_inputs: vec![2, 1],
+                indexed_inputs: vec![],
             },
         );
 
@@ -44,6 +45,7 @@ fn main() -> Result<()> {
                 public_inputs: vec![],
                 private_inputs: vec![],
                 context_inputs: context_output.0,
+                indexed_inputs: vec![],
             },
         );
 
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use delphinus_host::LocalOpType;
+use delphinus_host::StandardHostEnvBuilder;
+use delphinus_zkwasm::circuits::config::MIN_K;
+use delphinus_zkwasm::loader::slice::Slices;
+use delphinus_zkwasm::loader::TraceBackend;
+use delphinus_zkwasm::loader::ZkWasmLoader;
+use delphinus_zkwasm::runtime::host::default_env::ExecutionArg;
+use delphinus_zkwasm::runtime::host::HostEnvBuilder;
+use delphinus_zkwasm::runtime::monitor::table_monitor::TableMonitor;
+use pairing_bn256::bn256::Fr;
+
+const K: u32 = MIN_K;
+
+/// sha256("hello zkwasm") in little-endian limbs
+const HASH: [u64; 4] = [
+    0x9620ed6ab51f6bd7,
+    0xebe63ad673d17582,
+    0xc36691d965c1e881,
+    0xeeb2beb02299bf18,
+];
+const R: [u64; 4] = [
+    0xcd87506060fc4c58,
+    0x2b6ac6bf7867a4f6,
+    0xd125d8199e803b83,
+    0xf973a0b87062c389,
+];
+const S: [u64; 4] = [
+    0xcd3521b804e52ac4,
+    0x3378d76ac2452576,
+    0xe2b985023d10feff,
+    0x1f1523952fa17974,
+];
+const V: u64 = 0;
+
+/// Address of the private key 1, which signed the message above.
+const ADDRESS: [u8; 20] = [
+    0x7e, 0x5f, 0x45, 0x52, 0x09, 0x1a, 0x69, 0x12, 0x5d, 0x5d, 0xfc, 0xb7, 0xb8, 0xc2, 0x65, 0x90,
+    0x29, 0x39, 0x5b, 0xdf,
+];
+
+fn main() -> Result<()> {
+    let wasm = std::fs::read("wasm/ecrecover.wasm")?;
+    let module = ZkWasmLoader::parse_module(&wasm)?;
+
+    let private_inputs = [&HASH[..], &R[..], &S[..], &[V]].concat();
+    let public_inputs = vec![
+        u32::from_le_bytes(ADDRESS[0..4].try_into().unwrap()) as u64,
+        u64::from_le_bytes(ADDRESS[4..12].try_into().unwrap()),
+        u64::from_le_bytes(ADDRESS[12..20].try_into().unwrap()),
+    ];
+
+    let env = StandardHostEnvBuilder::default()
+        .with_local_ops(vec![LocalOpType::SECP256K1RECOVER])
+        .create_env(
+            K,
+            ExecutionArg {
+                public_inputs,
+                private_inputs,
+                context_inputs: vec![],
+                indexed_inputs: vec![],
+            },
+        );
+    let mut monitor = TableMonitor::new(K, &vec![], TraceBackend::Memory, &env);
+    let loader = ZkWasmLoader::new(K, env)?;
+
+    let runner = loader.compile(&module, &mut monitor)?;
+    let result = loader.run(runner, &mut monitor)?;
+    let instances = result.public_inputs_and_outputs::<Fr>();
+
+    let slices = Slices::new(K, monitor.into_tables())?;
+    slices.mock_test_all(instances)?;
+
+    Ok(())
+}
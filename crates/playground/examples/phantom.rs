@@ -21,6 +21,7 @@ fn main() -> Result<()> {
             public_inputs: vec![2],
             private_inputs: vec![],
             context_inputs: vec![],
+            indexed_inputs: vec![],
         },
     );
     let mut monitor = TableMonitor::new(K, &vec!["search".to_string()], TraceBackend::Memory, &env);
@@ -1,63 +1,99 @@
-pub fn parse_args<T: AsRef<str>>(values: &[T]) -> Vec<u64> {
-    values
-        .into_iter()
-        .map(|v| {
-            let [v, t] = v.as_ref().split(":").collect::<Vec<&str>>()[..] else { todo!() };
-            match t {
-                "i64" => {
-                    if v.starts_with("0x") {
-                        vec![
-                            u64::from_str_radix(String::from(v).trim_start_matches("0x"), 16)
-                                .unwrap(),
-                        ]
-                    } else {
-                        vec![v.parse::<u64>().unwrap()]
-                    }
-                }
-                "bytes" => {
-                    if !v.starts_with("0x") {
-                        panic!("bytes input need start with 0x");
-                    }
-                    let bytes = hex::decode(String::from(v).trim_start_matches("0x")).unwrap();
-                    bytes
-                        .into_iter()
-                        .map(|x| u64::from(x))
-                        .collect::<Vec<u64>>()
-                }
-                "bytes-packed" => {
-                    if !v.starts_with("0x") {
-                        panic!("bytes input need start with 0x");
-                    }
-                    let bytes = hex::decode(String::from(v).trim_start_matches("0x")).unwrap();
-                    let bytes = bytes.chunks(8);
-                    bytes
-                        .into_iter()
-                        .map(|x| {
-                            let mut data = [0u8; 8];
-                            data[..x.len()].copy_from_slice(x);
-
-                            u64::from_le_bytes(data)
-                        })
-                        .collect::<Vec<u64>>()
-                }
-                "file" => {
-                    let bytes = std::fs::read(v).unwrap();
-                    let bytes = bytes.chunks(8);
-                    bytes
-                        .into_iter()
-                        .map(|x| {
-                            let mut data = [0u8; 8];
-                            data[..x.len()].copy_from_slice(x);
-
-                            u64::from_be_bytes(data)
-                        })
-                        .collect()
-                }
-                _ => {
-                    panic!("Unsupported input data type: {}", t)
+use std::path::Path;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::context::ContextFile;
+use crate::context::ContextFileError;
+
+#[derive(Debug, Error)]
+pub enum ArgsError {
+    #[error("Input {0:?} is not in the format 'value:type'.")]
+    Format(String),
+    #[error(
+        "Input {arg:?} has unsupported type {ty}, expected i64|bytes|bytes-packed|file|context."
+    )]
+    UnsupportedType { arg: String, ty: String },
+    #[error("Input {arg:?} is not a valid {ty}: {reason}")]
+    InvalidValue {
+        arg: String,
+        ty: String,
+        reason: String,
+    },
+    #[error("Failed to read input file {path:?}: {source}")]
+    File {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Context(#[from] ContextFileError),
+}
+
+/// Parses inputs with format 'value:type'. `bytes` takes one word per byte, `bytes-packed` packs
+/// 8 bytes per word in little-endian order, while `file` packs the content of the file 8 bytes per
+/// word in big-endian order. `context` takes the values of a context file written by `--ctxout`.
+pub fn parse_args<T: AsRef<str>>(values: &[T]) -> Result<Vec<u64>, ArgsError> {
+    let mut words = vec![];
+
+    for arg in values {
+        let arg = arg.as_ref();
+        let [v, t] = arg.split(":").collect::<Vec<&str>>()[..] else {
+            return Err(ArgsError::Format(arg.to_string()));
+        };
+        let invalid = |reason: String| ArgsError::InvalidValue {
+            arg: arg.to_string(),
+            ty: t.to_string(),
+            reason,
+        };
+        let hex_bytes = || {
+            let hex = v
+                .strip_prefix("0x")
+                .ok_or_else(|| invalid("bytes input need start with 0x".to_string()))?;
+
+            hex::decode(hex).map_err(|err| invalid(err.to_string()))
+        };
+
+        match t {
+            "i64" => {
+                let word = match v.strip_prefix("0x") {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => v.parse::<u64>(),
                 }
+                .map_err(|err| invalid(err.to_string()))?;
+
+                words.push(word);
+            }
+            "bytes" => words.extend(hex_bytes()?.into_iter().map(|x| u64::from(x))),
+            "bytes-packed" => {
+                words.extend(hex_bytes()?.chunks(8).map(|x| {
+                    let mut data = [0u8; 8];
+                    data[..x.len()].copy_from_slice(x);
+
+                    u64::from_le_bytes(data)
+                }));
             }
-        })
-        .flatten()
-        .collect()
+            "file" => {
+                let bytes = std::fs::read(v).map_err(|source| ArgsError::File {
+                    path: PathBuf::from(v),
+                    source,
+                })?;
+
+                words.extend(bytes.chunks(8).map(|x| {
+                    let mut data = [0u8; 8];
+                    data[..x.len()].copy_from_slice(x);
+
+                    u64::from_be_bytes(data)
+                }));
+            }
+            "context" => words.extend(ContextFile::read(Path::new(v))?.values),
+            _ => {
+                return Err(ArgsError::UnsupportedType {
+                    arg: arg.to_string(),
+                    ty: t.to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(words)
 }
@@ -0,0 +1,123 @@
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ContextFileError {
+    #[error("Failed to access context file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Context file {path:?} is malformed: {reason}")]
+    Malformed { path: PathBuf, reason: String },
+    #[error("Context was produced by image {found:?}, expected image {expected:?}.")]
+    ImageMismatch {
+        expected: (String, String),
+        found: (String, String),
+    },
+    #[error("Context continued from inputs with hash {found}, but the previous context has hash {expected}.")]
+    NotContinued { expected: String, found: String },
+}
+
+/// Sha256 of the context words, each in little-endian order.
+pub fn context_hash(values: &[u64]) -> String {
+    let mut hasher = Sha256::new();
+
+    for value in values {
+        hasher.update(value.to_le_bytes());
+    }
+
+    format!("{:x}", hasher.finalize())
+}
+
+/// The context written by a run, along with the image that wrote it and the context it continued
+/// from, so that the runs of a context chain can be checked to follow each other.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ContextFile {
+    /// Checksum of the image, as recorded in the config at setup.
+    pub image_checksum: (String, String),
+    /// `context_hash` of the context inputs of the run.
+    pub context_inputs_hash: String,
+    /// Words written by `wasm_write_context`, read back by `wasm_read_context` in the next run.
+    pub values: Vec<u64>,
+}
+
+impl ContextFile {
+    pub fn new(image_checksum: (String, String), context_inputs: &[u64], values: Vec<u64>) -> Self {
+        Self {
+            image_checksum,
+            context_inputs_hash: context_hash(context_inputs),
+            values,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, ContextFileError> {
+        let fd = File::open(path).map_err(|source| ContextFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        serde_json::from_reader(fd).map_err(|err| ContextFileError::Malformed {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ContextFileError> {
+        let fd = File::create(path).map_err(|source| ContextFileError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        serde_json::to_writer_pretty(fd, self).map_err(|err| ContextFileError::Malformed {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })
+    }
+
+    pub fn check_image(&self, image_checksum: &(String, String)) -> Result<(), ContextFileError> {
+        if &self.image_checksum != image_checksum {
+            return Err(ContextFileError::ImageMismatch {
+                expected: image_checksum.clone(),
+                found: self.image_checksum.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the run writing `self` was given the values of `previous` as context inputs,
+    /// and that both runs executed the same image.
+    pub fn continues(&self, previous: &ContextFile) -> Result<(), ContextFileError> {
+        self.check_image(&previous.image_checksum)?;
+
+        let expected = context_hash(&previous.values);
+        if self.context_inputs_hash != expected {
+            return Err(ContextFileError::NotContinued {
+                expected,
+                found: self.context_inputs_hash.clone(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that each context of `chain` continues from the one before it, returning the index of
+/// the first context that doesn't along with the reason.
+pub fn check_context_chain(chain: &[ContextFile]) -> Result<(), (usize, ContextFileError)> {
+    for (index, pair) in chain.windows(2).enumerate() {
+        pair[1]
+            .continues(&pair[0])
+            .map_err(|err| (index + 1, err))?;
+    }
+
+    Ok(())
+}
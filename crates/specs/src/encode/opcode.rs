@@ -33,10 +33,11 @@ pub fn encode_call_indirect<T: FromBn>(type_index: T) -> T {
         + type_index * T::from_bn(&OPCODE_ARG0_SHIFT)
 }
 
-pub fn encode_call_host<T: FromBn>(op: T, is_ret: T) -> T {
+pub fn encode_call_host<T: FromBn>(op: T, is_ret: T, is_buffer: T) -> T {
     T::from_bn(&(BigUint::from(OpcodeClass::CallHost as u64))) * T::from_bn(&OPCODE_CLASS_SHIFT)
         + op * T::from_bn(&OPCODE_ARG0_SHIFT)
         + is_ret * T::from_bn(&OPCODE_ARG1_SHIFT)
+        + is_buffer * T::from_bn(&(BigUint::from(2u64) * &*OPCODE_ARG1_SHIFT))
 }
 
 pub fn encode_br<T: FromBn>(drop: T, keep: T, dst_pc: T) -> T {
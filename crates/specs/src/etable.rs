@@ -1,16 +1,20 @@
-use std::io::Read;
-use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::external_host_call_table::ExternalHostCallTable;
 use crate::host_function::HostPlugin;
+use crate::imtable::InitMemoryTableDiff;
 use crate::itable::InstructionTable;
 use crate::itable::InstructionTableEntry;
 use crate::step::StepInfo;
-
-const JSON: bool = false;
+use crate::trace_file::ChunkTag;
+use crate::trace_file::TraceFileError;
+use crate::trace_file::TraceFileKind;
+use crate::trace_file::TraceFileReader;
+use crate::trace_file::TraceFileWriter;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct EventTableEntry {
@@ -61,27 +65,48 @@ impl EventTable {
         Self(entries)
     }
 
-    pub fn write(&self, path: &PathBuf) -> std::io::Result<()> {
-        let mut fd = std::fs::File::create(path)?;
+    pub fn write(&self, path: &PathBuf) -> Result<(), TraceFileError> {
+        let mut writer = EventTableWriter::create(path)?;
 
-        if JSON {
-            fd.write_all(&serde_json::to_string_pretty(self).unwrap().as_bytes())?;
-        } else {
-            fd.write_all(&bincode::serialize(self).unwrap())?;
+        for entries in self.0.chunks(EVENT_TABLE_CHUNK_ENTRIES) {
+            writer.write_entries(entries)?;
         }
+        writer.write_external_host_calls(&self.0)?;
+        writer.finish()?;
+
         Ok(())
     }
 
-    pub fn read(path: &PathBuf) -> std::io::Result<Self> {
-        let mut fd = std::fs::File::open(path)?;
-        let mut buf = Vec::new();
-        fd.read_to_end(&mut buf)?;
+    pub fn read(path: &PathBuf) -> Result<Self, TraceFileError> {
+        Ok(Self(
+            EventTableReader::open(path)?.collect::<Result<Vec<_>, _>>()?,
+        ))
+    }
+
+    /// Reads the first entry without loading the rest of the table.
+    pub fn read_first_entry(path: &PathBuf) -> Result<Option<EventTableEntry>, TraceFileError> {
+        EventTableReader::open(path)?.next().transpose()
+    }
 
-        if JSON {
-            Ok(serde_json::from_slice(&buf).unwrap())
-        } else {
-            Ok(bincode::deserialize(&mut buf).unwrap())
+    /// Reads the external host call side table, `None` if the file doesn't carry it.
+    pub fn read_external_host_call_table(
+        path: &PathBuf,
+    ) -> Result<Option<ExternalHostCallTable>, TraceFileError> {
+        let mut reader = TraceFileReader::open(path, TraceFileKind::EventTable)?;
+        let mut entries: Option<Vec<EventTableEntry>> = None;
+
+        while let Some(chunk) = reader.next_chunk::<Vec<_>>(ChunkTag::ExternalHostCalls)? {
+            entries.get_or_insert_with(Vec::new).extend(chunk);
         }
+
+        Ok(entries.map(|entries| EventTable(entries).filter_external_host_call_table()))
+    }
+
+    /// Reads the memory writes side table, `None` if the file doesn't carry it.
+    pub fn read_memory_writes(
+        path: &PathBuf,
+    ) -> Result<Option<InitMemoryTableDiff>, TraceFileError> {
+        TraceFileReader::open(path, TraceFileKind::EventTable)?.next_chunk(ChunkTag::MemoryWrites)
     }
 
     pub fn unwrap(self) -> Vec<EventTableEntry> {
@@ -107,3 +132,88 @@ impl EventTable {
             .collect::<Vec<_>>()
     }
 }
+
+/// Number of entries in a chunk of an event table file.
+pub const EVENT_TABLE_CHUNK_ENTRIES: usize = 1 << 14;
+
+/// Appends entries and side tables of an event table to a trace file.
+pub struct EventTableWriter(TraceFileWriter);
+
+impl EventTableWriter {
+    pub fn create(path: &Path) -> Result<Self, TraceFileError> {
+        Ok(Self(TraceFileWriter::create(
+            path,
+            TraceFileKind::EventTable,
+        )?))
+    }
+
+    pub fn write_entries(&mut self, entries: &[EventTableEntry]) -> Result<(), TraceFileError> {
+        self.0.append(ChunkTag::Table, entries)
+    }
+
+    /// Appends the external host calls within `entries` to the side table.
+    pub fn write_external_host_calls(
+        &mut self,
+        entries: &[EventTableEntry],
+    ) -> Result<(), TraceFileError> {
+        let external_host_calls = entries
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry.step_info,
+                    StepInfo::ExternalHostCall { .. } | StepInfo::ExternalHostCallBuffer { .. }
+                )
+            })
+            .collect::<Vec<_>>();
+
+        self.0
+            .append(ChunkTag::ExternalHostCalls, &external_host_calls)
+    }
+
+    pub fn write_memory_writes(
+        &mut self,
+        memory_writes: &InitMemoryTableDiff,
+    ) -> Result<(), TraceFileError> {
+        self.0.append(ChunkTag::MemoryWrites, memory_writes)
+    }
+
+    pub fn finish(self) -> Result<PathBuf, TraceFileError> {
+        self.0.finish()
+    }
+}
+
+/// Reads the entries of an event table file one chunk at a time.
+pub struct EventTableReader {
+    reader: TraceFileReader,
+    chunk: std::vec::IntoIter<EventTableEntry>,
+}
+
+impl EventTableReader {
+    pub fn open(path: &Path) -> Result<Self, TraceFileError> {
+        Ok(Self {
+            reader: TraceFileReader::open(path, TraceFileKind::EventTable)?,
+            chunk: vec![].into_iter(),
+        })
+    }
+}
+
+impl Iterator for EventTableReader {
+    type Item = Result<EventTableEntry, TraceFileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.chunk.next() {
+                return Some(Ok(entry));
+            }
+
+            match self
+                .reader
+                .next_chunk::<Vec<EventTableEntry>>(ChunkTag::Table)
+            {
+                Ok(Some(chunk)) => self.chunk = chunk.into_iter(),
+                Ok(None) => return None,
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
@@ -8,15 +8,30 @@ use crate::types::ValueType;
 pub mod encode;
 mod table;
 
+/// The maximal number of u64 words transferred by one buffer host call.
+pub const EXTERNAL_HOST_CALL_BUFFER_WORDS: usize = 4;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
 pub enum ExternalHostCallSignature {
+    /// `(i64) -> ()`
     Argument,
+    /// `() -> i64`
     Return,
+    /// `(i32 ptr, i32 len) -> ()`, passes the words in `[ptr, ptr + len)` to the host.
+    ArgumentBuffer,
+    /// `(i32 ptr, i32 len) -> ()`, writes the words returned by the host into `[ptr, ptr + len)`.
+    ReturnBuffer,
 }
 
 impl ExternalHostCallSignature {
     pub fn is_ret(&self) -> bool {
         *self == ExternalHostCallSignature::Return
+            || *self == ExternalHostCallSignature::ReturnBuffer
+    }
+
+    pub fn is_buffer(&self) -> bool {
+        *self == ExternalHostCallSignature::ArgumentBuffer
+            || *self == ExternalHostCallSignature::ReturnBuffer
     }
 }
 
@@ -31,6 +46,12 @@ impl Into<Signature> for ExternalHostCallSignature {
                 params: vec![],
                 return_type: Some(ValueType::I64),
             },
+            ExternalHostCallSignature::ArgumentBuffer | ExternalHostCallSignature::ReturnBuffer => {
+                Signature {
+                    params: vec![ValueType::I32, ValueType::I32],
+                    return_type: None,
+                }
+            }
         }
     }
 }
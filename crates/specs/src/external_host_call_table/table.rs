@@ -8,16 +8,23 @@ impl EventTable {
         let entries = self
             .entries()
             .iter()
-            .filter_map(|entry| {
-                if let StepInfo::ExternalHostCall { op, value, sig, .. } = &entry.step_info {
-                    Some(ExternalHostCallEntry {
+            .flat_map(|entry| match &entry.step_info {
+                StepInfo::ExternalHostCall { op, value, sig, .. } => vec![ExternalHostCallEntry {
+                    op: *op,
+                    value: value.unwrap(),
+                    sig: *sig,
+                }],
+                StepInfo::ExternalHostCallBuffer {
+                    op, values, sig, ..
+                } => values
+                    .iter()
+                    .map(|value| ExternalHostCallEntry {
                         op: *op,
-                        value: value.unwrap(),
+                        value: *value,
                         sig: *sig,
                     })
-                } else {
-                    None
-                }
+                    .collect(),
+                _ => vec![],
             })
             .collect();
 
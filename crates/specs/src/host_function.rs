@@ -27,14 +27,16 @@ pub enum HostFunctionDesc {
         op: usize,
         sig: ExternalHostCallSignature,
     },
+    /// Host function without circuit, it can only be called within phantom functions.
+    Phantom { name: String },
 }
 
 impl HostFunctionDesc {
     pub fn name(&self) -> &String {
         match self {
-            HostFunctionDesc::Internal { name, .. } | HostFunctionDesc::External { name, .. } => {
-                name
-            }
+            HostFunctionDesc::Internal { name, .. }
+            | HostFunctionDesc::External { name, .. }
+            | HostFunctionDesc::Phantom { name } => name,
         }
     }
 }
@@ -1,10 +1,14 @@
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::mtable::LocationType;
 use crate::mtable::VarType;
 use serde::Deserialize;
 use serde::Serialize;
 
+/// Number of consecutive offsets of a location type held by one chunk of the table.
+pub const INIT_MEMORY_CHUNK_SIZE: u32 = 1024;
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct InitMemoryTableEntry {
     pub ltype: LocationType,
@@ -16,25 +20,123 @@ pub struct InitMemoryTableEntry {
     pub eid: u32,
 }
 
-#[derive(Default, Debug)]
-pub struct InitMemoryTable(pub HashMap<(LocationType, u32), InitMemoryTableEntry>);
+type Chunk = HashMap<u32, InitMemoryTableEntry>;
+
+/// The memory image, split into chunks of `INIT_MEMORY_CHUNK_SIZE` offsets.
+///
+/// Chunks are shared between a table and the tables derived from it by `update`, so the image
+/// after each slice only copies the chunks written by the slice.
+#[derive(Default, Debug, Clone)]
+pub struct InitMemoryTable {
+    chunks: HashMap<(LocationType, u32), Arc<Chunk>>,
+}
+
+/// The last write of a slice to each memory location, as entries of the post image.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct InitMemoryTableDiff(HashMap<(LocationType, u32), InitMemoryTableEntry>);
+
+impl InitMemoryTableDiff {
+    pub fn insert(&mut self, entry: InitMemoryTableEntry) {
+        self.0.insert((entry.ltype, entry.offset), entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &InitMemoryTableEntry> {
+        self.0.values()
+    }
+}
+
+impl Serialize for InitMemoryTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.entries())
+    }
+}
+
+impl<'de> Deserialize<'de> for InitMemoryTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Ok(InitMemoryTable::new(Vec::deserialize(deserializer)?))
+    }
+}
+
+fn chunk_key(ltype: LocationType, offset: u32) -> (LocationType, u32) {
+    (ltype, offset / INIT_MEMORY_CHUNK_SIZE)
+}
 
 impl InitMemoryTable {
     pub fn new(entries: Vec<InitMemoryTableEntry>) -> Self {
-        let mut map = HashMap::new();
+        let mut table = Self::default();
+
+        entries.into_iter().for_each(|entry| table.insert(entry));
+
+        table
+    }
+
+    fn insert(&mut self, entry: InitMemoryTableEntry) {
+        let chunk = self
+            .chunks
+            .entry(chunk_key(entry.ltype, entry.offset))
+            .or_default();
+
+        Arc::make_mut(chunk).insert(entry.offset, entry);
+    }
 
-        entries.into_iter().for_each(|entry| {
-            map.insert((entry.ltype, entry.offset), entry);
-        });
+    /// Returns the table after applying `diff`. Chunks untouched by `diff` are shared with `self`.
+    pub fn update(&self, diff: &InitMemoryTableDiff) -> Self {
+        let mut table = self.clone();
+
+        diff.entries().for_each(|entry| table.insert(entry.clone()));
+
+        table
+    }
+
+    /// Entries of `self` in the chunks that are not shared with `base`. It contains every entry of
+    /// `self` differing from `base` if `self` is derived from `base` by `update`.
+    pub fn updated_entries<'a>(
+        &'a self,
+        base: &'a InitMemoryTable,
+    ) -> impl Iterator<Item = &'a InitMemoryTableEntry> {
+        self.chunks
+            .iter()
+            .filter(move |(key, chunk)| {
+                base.chunks
+                    .get(*key)
+                    .map_or(true, |base_chunk| !Arc::ptr_eq(*chunk, base_chunk))
+            })
+            .flat_map(|(_, chunk)| chunk.values())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &InitMemoryTableEntry> {
+        self.chunks.values().flat_map(|chunk| chunk.values())
+    }
+
+    pub fn len(&self) -> usize {
+        self.chunks.values().map(|chunk| chunk.len()).sum()
+    }
 
-        Self(map)
+    pub fn is_empty(&self) -> bool {
+        self.chunks.values().all(|chunk| chunk.is_empty())
     }
 
     pub fn to_string(&self) -> String {
-        serde_json::to_string(&self.0).unwrap()
+        serde_json::to_string(&self.entries().collect::<Vec<_>>()).unwrap()
     }
 
     pub fn try_find(&self, ltype: LocationType, offset: u32) -> Option<&InitMemoryTableEntry> {
-        self.0.get(&(ltype, offset))
+        self.chunks
+            .get(&chunk_key(ltype, offset))
+            .and_then(|chunk| chunk.get(&offset))
     }
 }
@@ -0,0 +1,435 @@
+//! Typed description of the input streams read by `wasm_input` and of the outputs written by
+//! `wasm_output`.
+//!
+//! Every stream is a sequence of u64 words, a schema lists the named fields a stream is made of
+//! and how each of them is packed into words:
+//!
+//! * `u32` and `u64` take one word.
+//! * `field` is a BN254 scalar, four words holding its little-endian 64-bit limbs.
+//! * `bytes32` is four words, each packing 8 bytes in little-endian order as `bytes-packed` does.
+//! * `string` is one word holding the length in bytes, followed by its UTF-8 bytes packed as
+//!   `bytes-packed` does.
+//! * `{"array": {"of": <type>, "len": <n>}}` is the elements one after another. Without `len`
+//!   the array is preceded by one word holding its number of elements.
+
+use std::fmt;
+use std::fs::File;
+use std::path::Path;
+use std::path::PathBuf;
+
+use num_bigint::BigUint;
+use num_traits::Num;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Map;
+use serde_json::Value;
+use thiserror::Error;
+
+lazy_static! {
+    static ref FIELD_MODULUS: BigUint = BigUint::from_str_radix(
+        "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001",
+        16
+    )
+    .unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Public,
+    Private,
+    Context,
+    Outputs,
+}
+
+impl fmt::Display for Stream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Stream::Public => write!(f, "public input"),
+            Stream::Private => write!(f, "private input"),
+            Stream::Context => write!(f, "context input"),
+            Stream::Outputs => write!(f, "output"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldType {
+    U32,
+    U64,
+    Field,
+    Bytes32,
+    String,
+    Array {
+        of: Box<FieldType>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        len: Option<usize>,
+    },
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldType::U32 => write!(f, "u32"),
+            FieldType::U64 => write!(f, "u64"),
+            FieldType::Field => write!(f, "field"),
+            FieldType::Bytes32 => write!(f, "bytes32"),
+            FieldType::String => write!(f, "string"),
+            FieldType::Array { of, len: Some(len) } => write!(f, "[{}; {}]", of, len),
+            FieldType::Array { of, len: None } => write!(f, "[{}]", of),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FieldSchema {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: FieldType,
+}
+
+#[derive(Debug, Error)]
+pub enum IoSchemaError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{path:?} is malformed: {reason}")]
+    Malformed { path: PathBuf, reason: String },
+    #[error("`{0}` is not an input stream, expected public, private or context.")]
+    UnknownStream(String),
+    #[error("{0} fields are not an object of named values.")]
+    NotAnObject(Stream),
+    #[error("{stream} `{field}` is declared in the schema but has no value.")]
+    MissingField { stream: Stream, field: String },
+    #[error("{stream} `{field}` is not declared in the schema.")]
+    UnknownField { stream: Stream, field: String },
+    #[error("{stream} `{field}` expects {expected} but found {found}.")]
+    InvalidValue {
+        stream: Stream,
+        field: String,
+        expected: String,
+        found: String,
+    },
+    #[error("{stream} `{field}` is incomplete, the stream ends before it.")]
+    UnexpectedEnd { stream: Stream, field: String },
+    #[error("{count} {stream} word(s) are left after the last field of the schema.")]
+    TrailingWords { stream: Stream, count: usize },
+}
+
+/// Streams encoded from named values, ready to be passed as `ExecutionArg`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EncodedInputs {
+    pub public_inputs: Vec<u64>,
+    pub private_inputs: Vec<u64>,
+    pub context_inputs: Vec<u64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct IoSchema {
+    #[serde(default)]
+    pub public: Vec<FieldSchema>,
+    #[serde(default)]
+    pub private: Vec<FieldSchema>,
+    #[serde(default)]
+    pub context: Vec<FieldSchema>,
+    #[serde(default)]
+    pub outputs: Vec<FieldSchema>,
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T, IoSchemaError> {
+    serde_json::from_reader(File::open(path)?).map_err(|err| IoSchemaError::Malformed {
+        path: path.to_path_buf(),
+        reason: err.to_string(),
+    })
+}
+
+impl IoSchema {
+    pub fn read(path: &Path) -> Result<Self, IoSchemaError> {
+        read_json(path)
+    }
+
+    pub fn fields(&self, stream: Stream) -> &Vec<FieldSchema> {
+        match stream {
+            Stream::Public => &self.public,
+            Stream::Private => &self.private,
+            Stream::Context => &self.context,
+            Stream::Outputs => &self.outputs,
+        }
+    }
+
+    /// Encodes the values of a stream, given as an object mapping each field to its value.
+    pub fn encode_stream(&self, stream: Stream, values: &Value) -> Result<Vec<u64>, IoSchemaError> {
+        let empty = Map::new();
+        let values = match values {
+            Value::Null => &empty,
+            Value::Object(values) => values,
+            _ => return Err(IoSchemaError::NotAnObject(stream)),
+        };
+
+        let fields = self.fields(stream);
+
+        if let Some(unknown) = values
+            .keys()
+            .find(|name| fields.iter().all(|field| &field.name != *name))
+        {
+            return Err(IoSchemaError::UnknownField {
+                stream,
+                field: unknown.clone(),
+            });
+        }
+
+        let mut words = vec![];
+
+        for field in fields {
+            let value = values
+                .get(&field.name)
+                .ok_or_else(|| IoSchemaError::MissingField {
+                    stream,
+                    field: field.name.clone(),
+                })?;
+
+            encode_value(stream, &field.name, &field.ty, value, &mut words)?;
+        }
+
+        Ok(words)
+    }
+
+    /// Encodes the input streams from an object with the optional keys `public`, `private` and
+    /// `context`, each mapping the fields of the stream to their values.
+    pub fn encode(&self, values: &Value) -> Result<EncodedInputs, IoSchemaError> {
+        if let Value::Object(values) = values {
+            if let Some(unknown) = values
+                .keys()
+                .find(|key| !["public", "private", "context"].contains(&key.as_str()))
+            {
+                return Err(IoSchemaError::UnknownStream(unknown.clone()));
+            }
+        }
+
+        let values_of = |key: &str| values.get(key).unwrap_or(&Value::Null);
+
+        Ok(EncodedInputs {
+            public_inputs: self.encode_stream(Stream::Public, values_of("public"))?,
+            private_inputs: self.encode_stream(Stream::Private, values_of("private"))?,
+            context_inputs: self.encode_stream(Stream::Context, values_of("context"))?,
+        })
+    }
+
+    /// Reads the values to encode from a JSON file, see [`IoSchema::encode`].
+    pub fn encode_file(&self, path: &Path) -> Result<EncodedInputs, IoSchemaError> {
+        self.encode(&read_json(path)?)
+    }
+
+    /// Decodes the words of a stream back into an object mapping each field to its value. Every
+    /// word must belong to a field.
+    pub fn decode_stream(
+        &self,
+        stream: Stream,
+        words: &[u64],
+    ) -> Result<Map<String, Value>, IoSchemaError> {
+        let mut words = words.iter().cloned();
+        let mut values = Map::new();
+
+        for field in self.fields(stream) {
+            let value = decode_value(stream, &field.name, &field.ty, &mut words)?;
+
+            values.insert(field.name.clone(), value);
+        }
+
+        match words.count() {
+            0 => Ok(values),
+            count => Err(IoSchemaError::TrailingWords { stream, count }),
+        }
+    }
+
+    /// Decodes `ExecutionResult::outputs` into the fields declared by `outputs`.
+    pub fn decode_outputs(&self, outputs: &[u64]) -> Result<Map<String, Value>, IoSchemaError> {
+        self.decode_stream(Stream::Outputs, outputs)
+    }
+}
+
+fn pack_bytes(bytes: &[u8], words: &mut Vec<u64>) {
+    words.extend(bytes.chunks(8).map(|chunk| {
+        let mut data = [0u8; 8];
+        data[..chunk.len()].copy_from_slice(chunk);
+
+        u64::from_le_bytes(data)
+    }))
+}
+
+fn parse_u64(s: &str) -> Option<u64> {
+    match s.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn encode_value(
+    stream: Stream,
+    field: &str,
+    ty: &FieldType,
+    value: &Value,
+    words: &mut Vec<u64>,
+) -> Result<(), IoSchemaError> {
+    let invalid = || IoSchemaError::InvalidValue {
+        stream,
+        field: field.to_string(),
+        expected: ty.to_string(),
+        found: value.to_string(),
+    };
+
+    match ty {
+        FieldType::U32 | FieldType::U64 => {
+            let word = match value {
+                Value::Number(number) => number.as_u64(),
+                Value::String(s) => parse_u64(s),
+                _ => None,
+            }
+            .ok_or_else(invalid)?;
+
+            if *ty == FieldType::U32 && word > u32::MAX as u64 {
+                return Err(invalid());
+            }
+
+            words.push(word);
+        }
+        FieldType::Field => {
+            let element = match value {
+                Value::Number(number) => number.as_u64().map(BigUint::from),
+                Value::String(s) => match s.strip_prefix("0x") {
+                    Some(hex) => BigUint::from_str_radix(hex, 16).ok(),
+                    None => BigUint::from_str_radix(s, 10).ok(),
+                },
+                _ => None,
+            }
+            .filter(|element| *element < *FIELD_MODULUS)
+            .ok_or_else(invalid)?;
+
+            let mut limbs = element.to_u64_digits();
+            limbs.resize(4, 0);
+
+            words.extend(limbs);
+        }
+        FieldType::Bytes32 => {
+            let bytes = value
+                .as_str()
+                .and_then(|s| s.strip_prefix("0x"))
+                .and_then(|hex| hex::decode(hex).ok())
+                .filter(|bytes| bytes.len() == 32)
+                .ok_or_else(invalid)?;
+
+            pack_bytes(&bytes, words);
+        }
+        FieldType::String => {
+            let s = value.as_str().ok_or_else(invalid)?;
+
+            words.push(s.len() as u64);
+            pack_bytes(s.as_bytes(), words);
+        }
+        FieldType::Array { of, len } => {
+            let elements = value.as_array().ok_or_else(invalid)?;
+
+            match len {
+                Some(len) if *len != elements.len() => return Err(invalid()),
+                Some(_) => (),
+                None => words.push(elements.len() as u64),
+            }
+
+            for (index, element) in elements.iter().enumerate() {
+                encode_value(stream, &format!("{}[{}]", field, index), of, element, words)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn decode_value(
+    stream: Stream,
+    field: &str,
+    ty: &FieldType,
+    words: &mut impl Iterator<Item = u64>,
+) -> Result<Value, IoSchemaError> {
+    let mut next = || {
+        words.next().ok_or_else(|| IoSchemaError::UnexpectedEnd {
+            stream,
+            field: field.to_string(),
+        })
+    };
+    let invalid = |found: String| IoSchemaError::InvalidValue {
+        stream,
+        field: field.to_string(),
+        expected: ty.to_string(),
+        found,
+    };
+
+    let value = match ty {
+        FieldType::U32 => {
+            let word = next()?;
+
+            if word > u32::MAX as u64 {
+                return Err(invalid(word.to_string()));
+            }
+
+            Value::from(word)
+        }
+        FieldType::U64 => Value::from(next()?),
+        FieldType::Field => {
+            let limbs = (0..4).map(|_| next()).collect::<Result<Vec<_>, _>>()?;
+            let element = BigUint::new(
+                limbs
+                    .iter()
+                    .flat_map(|limb| [*limb as u32, (*limb >> 32) as u32])
+                    .collect(),
+            );
+
+            if element >= *FIELD_MODULUS {
+                return Err(invalid(format!("0x{:x}", element)));
+            }
+
+            Value::String(format!("0x{:x}", element))
+        }
+        FieldType::Bytes32 => {
+            let bytes = (0..4)
+                .map(|_| next().map(u64::to_le_bytes))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+
+            Value::String(format!("0x{}", hex::encode(bytes)))
+        }
+        FieldType::String => {
+            // The length is written by the guest, the words it spans are counted without
+            // overflowing and the stream ends before a length that doesn't fit.
+            let len = next()?;
+            let bytes = (0..len / 8 + u64::from(len % 8 != 0))
+                .map(|_| next().map(u64::to_le_bytes))
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+            let bytes = usize::try_from(len)
+                .ok()
+                .and_then(|len| bytes.get(..len))
+                .ok_or_else(|| invalid(format!("a string of {} bytes", len)))?;
+
+            let s = String::from_utf8(bytes.to_vec())
+                .map_err(|_| invalid(format!("0x{}", hex::encode(bytes))))?;
+
+            Value::String(s)
+        }
+        FieldType::Array { of, len } => {
+            let len = match len {
+                Some(len) => *len,
+                None => next()? as usize,
+            };
+
+            let elements = (0..len)
+                .map(|index| decode_value(stream, &format!("{}[{}]", field, index), of, words))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Value::Array(elements)
+        }
+    };
+
+    Ok(value)
+}
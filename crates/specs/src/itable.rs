@@ -424,6 +424,7 @@ impl Into<BigUint> for &Opcode {
             Opcode::ExternalHostCall { op, sig } => encode_call_host(
                 BigUint::from(*op as u64),
                 BigUint::from(sig.is_ret() as u64),
+                BigUint::from(sig.is_buffer() as u64),
             ),
 
             Opcode::Load {
@@ -1,19 +1,20 @@
-use std::io::Read;
-use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use serde::Deserialize;
 use serde::Serialize;
 
+use crate::trace_file::read_trace_file;
+use crate::trace_file::write_trace_file;
+use crate::trace_file::TraceFileError;
+use crate::trace_file::TraceFileKind;
+
 // Inherited frame table entries:
 // 1. zkmain
 // 2. start(optional)
 // 3. active frames inherited from last slice
 pub const INHERITED_FRAME_TABLE_ENTRIES: usize = 4096;
 
-const JSON: bool = false;
-
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct FrameTableEntryInternal {
     // caller eid (unique)
@@ -81,6 +82,39 @@ impl InheritedFrameTable {
     }
 }
 
+impl Serialize for InheritedFrameTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Padding entries are restored on deserialization.
+        let len = self
+            .0
+            .iter()
+            .rposition(|entry| entry.0.is_some())
+            .map_or(0, |pos| pos + 1);
+
+        serializer.collect_seq(&self.0[..len])
+    }
+}
+
+impl<'de> Deserialize<'de> for InheritedFrameTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::<InheritedFrameTableEntry>::deserialize(deserializer)?
+            .try_into()
+            .map_err(|entries: Vec<_>| {
+                serde::de::Error::custom(format!(
+                    "{} inherited frame entries exceed the limit {}",
+                    entries.len(),
+                    INHERITED_FRAME_TABLE_ENTRIES
+                ))
+            })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CalledFrameTableEntry(pub FrameTableEntryInternal);
 
@@ -112,26 +146,11 @@ pub struct FrameTable {
 }
 
 impl FrameTable {
-    pub fn read(path: &PathBuf) -> std::io::Result<Self> {
-        let mut fd = std::fs::File::open(path)?;
-        let mut buf = Vec::new();
-        fd.read_to_end(&mut buf)?;
-
-        if JSON {
-            Ok(serde_json::from_slice(&buf).unwrap())
-        } else {
-            Ok(bincode::deserialize(&mut buf).unwrap())
-        }
+    pub fn read(path: &PathBuf) -> Result<Self, TraceFileError> {
+        read_trace_file(path, TraceFileKind::FrameTable)
     }
 
-    pub fn write(&self, path: &PathBuf) -> std::io::Result<()> {
-        let mut fd = std::fs::File::create(path)?;
-
-        if JSON {
-            fd.write_all(&serde_json::to_string_pretty(self).unwrap().as_bytes())?;
-        } else {
-            fd.write_all(&bincode::serialize(self).unwrap())?;
-        }
-        Ok(())
+    pub fn write(&self, path: &PathBuf) -> Result<(), TraceFileError> {
+        write_trace_file(path, TraceFileKind::FrameTable, self)
     }
 }
@@ -3,7 +3,9 @@
 #![deny(dead_code)]
 
 use std::fs::File;
+use std::io::BufReader;
 use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -15,6 +17,10 @@ use imtable::InitMemoryTable;
 use itable::InstructionTable;
 use jtable::FrameTable;
 use jtable::InheritedFrameTable;
+use phantom::PhantomFunctionTable;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde::Serialize;
 use state::InitializationState;
 
 use crate::external_host_call_table::ExternalHostCallTable;
@@ -25,22 +31,27 @@ extern crate lazy_static;
 pub mod args;
 pub mod brtable;
 pub mod configure_table;
+pub mod context;
 pub mod encode;
 pub mod etable;
 pub mod external_host_call_table;
 pub mod host_function;
 pub mod imtable;
+pub mod io_schema;
 pub mod itable;
 pub mod jtable;
 pub mod mtable;
+pub mod phantom;
 pub mod slice;
 pub mod state;
 pub mod step;
+pub mod trace_file;
 pub mod types;
 
 pub enum TraceBackend {
     File {
-        event_table_writer: Box<dyn Fn(usize, &EventTable) -> PathBuf>,
+        /// Path of the event table of a slice, the table is streamed to it while tracing.
+        event_table_path: Box<dyn Fn(usize) -> PathBuf>,
         frame_table_writer: Box<dyn Fn(usize, &FrameTable) -> PathBuf>,
     },
     Memory,
@@ -48,7 +59,8 @@ pub enum TraceBackend {
 
 pub enum TableBackend<Table> {
     Memory(Table),
-    Json(PathBuf),
+    /// A table written in the trace file format, see `trace_file`.
+    File(PathBuf),
 }
 
 #[derive(Debug)]
@@ -60,6 +72,7 @@ pub struct CompilationTable {
     pub configure_table: Arc<ConfigureTable>,
     pub initial_frame_table: Arc<InheritedFrameTable>,
     pub initialization_state: Arc<InitializationState<u32>>,
+    pub phantom_functions: Arc<PhantomFunctionTable>,
 }
 
 #[derive(Default)]
@@ -73,62 +86,174 @@ pub struct Tables {
     pub execution_tables: ExecutionTable,
 }
 
+// Lists the slice files of a trace directory, see `Tables::write`.
+#[derive(Serialize, Deserialize)]
+struct TablesManifest {
+    etable: Vec<String>,
+    frame_table: Vec<String>,
+}
+
+const TABLES_MANIFEST: &str = "tables.json";
+
+fn read_json<T: DeserializeOwned>(dir: &Path, filename: &str) -> std::io::Result<T> {
+    let fd = File::open(dir.join(filename))?;
+
+    Ok(serde_json::from_reader(BufReader::new(fd))?)
+}
+
 impl Tables {
+    /// Writes the compilation tables, the slices of the execution tables and the external host
+    /// call table to `dir`. Slices already written to files elsewhere are copied into `dir`, so
+    /// `Tables::read` rebuilds the tables from `dir` alone.
     pub fn write(
         &self,
         dir: &PathBuf,
         name_of_etable_slice: impl Fn(usize) -> String,
         name_of_frame_table_slice: impl Fn(usize) -> String,
-    ) {
-        fn write_file(folder: &PathBuf, filename: &str, buf: &String) {
+    ) -> std::io::Result<()> {
+        fn write_file(folder: &PathBuf, filename: &str, buf: &String) -> std::io::Result<()> {
             let folder = folder.join(filename);
-            let mut fd = File::create(folder.as_path()).unwrap();
+            let mut fd = File::create(folder.as_path())?;
 
-            fd.write_all(buf.as_bytes()).unwrap();
+            fd.write_all(buf.as_bytes())
         }
 
+        fn copy_file(from: &PathBuf, to: &PathBuf) -> std::io::Result<()> {
+            if from != to {
+                std::fs::copy(from, to)?;
+            }
+
+            Ok(())
+        }
+
+        let mut manifest = TablesManifest {
+            etable: vec![],
+            frame_table: vec![],
+        };
+
         let mut external_host_call_table = vec![];
-        self.execution_tables
-            .etable
-            .iter()
-            .enumerate()
-            .for_each(|(slice, e)| match e {
+        for (slice, e) in self.execution_tables.etable.iter().enumerate() {
+            let filename = name_of_etable_slice(slice);
+            let path = dir.join(&filename);
+
+            match e {
                 TableBackend::Memory(etable) => {
                     external_host_call_table.extend(etable.filter_external_host_call_table().0);
 
-                    let path = dir.join(name_of_etable_slice(slice));
-
-                    etable.write(&path).unwrap();
+                    etable.write(&path)?;
                 }
-                TableBackend::Json(path) => {
-                    let etable = EventTable::read(&path).unwrap();
-                    external_host_call_table.extend(etable.filter_external_host_call_table().0);
+                TableBackend::File(file) => {
+                    let table = match EventTable::read_external_host_call_table(file)? {
+                        Some(table) => table,
+                        None => EventTable::read(file)?.filter_external_host_call_table(),
+                    };
+                    external_host_call_table.extend(table.0);
+
+                    copy_file(file, &path)?;
                 }
-            });
+            }
+
+            manifest.etable.push(filename);
+        }
         let external_host_call_table = ExternalHostCallTable::new(external_host_call_table);
 
+        let compilation_tables = &self.compilation_tables;
         write_file(
             dir,
             "itable.json",
-            &serde_json::to_string_pretty(&self.compilation_tables.itable).unwrap(),
-        );
-        self.execution_tables
-            .frame_table
-            .iter()
-            .enumerate()
-            .for_each(|(slice, frame_table)| {
-                if let TableBackend::Memory(frame_table) = frame_table {
-                    write_file(
-                        dir,
-                        &name_of_frame_table_slice(slice),
-                        &serde_json::to_string_pretty(frame_table).unwrap(),
-                    );
-                }
-            });
+            &serde_json::to_string_pretty(&compilation_tables.itable)?,
+        )?;
+        write_file(
+            dir,
+            "imtable.json",
+            &serde_json::to_string(&compilation_tables.imtable)?,
+        )?;
+        write_file(
+            dir,
+            "br_table.json",
+            &serde_json::to_string_pretty(&compilation_tables.br_table)?,
+        )?;
+        write_file(
+            dir,
+            "elem_table.json",
+            &serde_json::to_string_pretty(&compilation_tables.elem_table)?,
+        )?;
+        write_file(
+            dir,
+            "configure_table.json",
+            &serde_json::to_string_pretty(&compilation_tables.configure_table)?,
+        )?;
+        write_file(
+            dir,
+            "initial_frame_table.json",
+            &serde_json::to_string_pretty(&compilation_tables.initial_frame_table)?,
+        )?;
+        write_file(
+            dir,
+            "initialization_state.json",
+            &serde_json::to_string_pretty(&compilation_tables.initialization_state)?,
+        )?;
+        write_file(
+            dir,
+            "phantom_functions.json",
+            &serde_json::to_string_pretty(&compilation_tables.phantom_functions)?,
+        )?;
+
+        for (slice, frame_table) in self.execution_tables.frame_table.iter().enumerate() {
+            let filename = name_of_frame_table_slice(slice);
+            let path = dir.join(&filename);
+
+            match frame_table {
+                TableBackend::Memory(frame_table) => frame_table.write(&path)?,
+                TableBackend::File(file) => copy_file(file, &path)?,
+            }
+
+            manifest.frame_table.push(filename);
+        }
         write_file(
             dir,
             "external_host_table.json",
-            &serde_json::to_string_pretty(&external_host_call_table).unwrap(),
-        );
+            &serde_json::to_string_pretty(&external_host_call_table)?,
+        )?;
+        write_file(
+            dir,
+            TABLES_MANIFEST,
+            &serde_json::to_string_pretty(&manifest)?,
+        )
+    }
+
+    /// Reads the tables written by `Tables::write`, the slices of the execution tables are left
+    /// in their files.
+    pub fn read(dir: &PathBuf) -> std::io::Result<Tables> {
+        let manifest: TablesManifest = read_json(dir, TABLES_MANIFEST)?;
+
+        let compilation_tables = CompilationTable {
+            itable: Arc::new(read_json(dir, "itable.json")?),
+            imtable: Arc::new(read_json(dir, "imtable.json")?),
+            br_table: Arc::new(read_json(dir, "br_table.json")?),
+            elem_table: Arc::new(read_json(dir, "elem_table.json")?),
+            configure_table: Arc::new(read_json(dir, "configure_table.json")?),
+            initial_frame_table: Arc::new(read_json(dir, "initial_frame_table.json")?),
+            initialization_state: Arc::new(read_json(dir, "initialization_state.json")?),
+            phantom_functions: Arc::new(read_json(dir, "phantom_functions.json")?),
+        };
+
+        let execution_tables = ExecutionTable {
+            etable: manifest
+                .etable
+                .iter()
+                .map(|filename| TableBackend::File(dir.join(filename)))
+                .collect(),
+            frame_table: manifest
+                .frame_table
+                .iter()
+                .map(|filename| TableBackend::File(dir.join(filename)))
+                .collect(),
+        };
+
+        Ok(Tables {
+            compilation_tables,
+            execution_tables,
+        })
     }
 }
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+
+use crate::host_function::Signature;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PhantomFunctionEntry {
+    pub fid: u32,
+    /// Name of the export matched by the phantom function patterns.
+    pub name: String,
+    pub signature: Signature,
+    /// The replaced body in the Wasm text format. It is executed but neither traced nor proven,
+    /// the instruction table holds the instructions that return its result instead.
+    pub body: Vec<String>,
+}
+
+impl PhantomFunctionEntry {
+    /// Sha256 of the replaced body, one instruction per line.
+    pub fn body_hash(&self) -> String {
+        format!("{:x}", Sha256::digest(self.body.join("\n").as_bytes()))
+    }
+}
+
+/// Which function a phantom function pattern resolved to, recorded at setup so that a change of
+/// the image or of its name section can't silently change what is left out of the proof.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedPhantomFunction {
+    pub name: String,
+    pub fid: u32,
+    pub body_hash: String,
+}
+
+/// Phantom functions of the image, ordered by function index.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+pub struct PhantomFunctionTable(Vec<PhantomFunctionEntry>);
+
+impl PhantomFunctionTable {
+    pub fn new(mut entries: Vec<PhantomFunctionEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.fid);
+
+        Self(entries)
+    }
+
+    pub fn entries(&self) -> &Vec<PhantomFunctionEntry> {
+        &self.0
+    }
+
+    pub fn resolve(&self) -> Vec<ResolvedPhantomFunction> {
+        self.0
+            .iter()
+            .map(|entry| ResolvedPhantomFunction {
+                name: entry.name.clone(),
+                fid: entry.fid,
+                body_hash: entry.body_hash(),
+            })
+            .collect()
+    }
+}
@@ -80,6 +80,15 @@ pub enum StepInfo {
         value: Option<u64>,
         sig: ExternalHostCallSignature,
     },
+    ExternalHostCallBuffer {
+        op: usize,
+        address: u32,
+        // length in bytes
+        len: u32,
+        // one value per u64 word in [address, address + len)
+        values: Vec<u64>,
+        sig: ExternalHostCallSignature,
+    },
 
     GetLocal {
         vtype: VarType,
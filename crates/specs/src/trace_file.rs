@@ -0,0 +1,297 @@
+//! Container format of the event and frame tables written by the file trace backend.
+//!
+//! ```text
+//! header: | magic (8) | version (u32 le) | kind (u8) | schema version (u32 le) |
+//! chunk:  | tag (u8) | length (u64 le) | digest (32) | payload (length) |
+//! ```
+//!
+//! A file is a header followed by any number of chunks, so tables can be appended chunk by chunk
+//! while tracing and read back one chunk at a time. The payload of a chunk is the zstd compressed
+//! bincode encoding of its value and the digest is the sha256 of the payload. The tag tells the
+//! table itself apart from the side tables stored next to it, readers skip the chunks they don't
+//! ask for without decoding them.
+//!
+//! The schema version identifies the serialized shape of `StepInfo` and of the opcode, memory, host
+//! call and value types it embeds, so a trace is never decoded by a build whose step layout differs
+//! from the one that wrote it. Changes to the layout of the table entries around `StepInfo` must
+//! bump `TRACE_FILE_VERSION`.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::ErrorKind;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use thiserror::Error;
+
+pub const TRACE_FILE_MAGIC: [u8; 8] = *b"ZKWTRACE";
+pub const TRACE_FILE_VERSION: u32 = 3;
+/// Must be bumped whenever a field or a variant of `StepInfo`, or of a type it embeds, is added,
+/// removed, reordered or retyped. Edits that keep the serialized shape, such as renames of local
+/// variables or doc changes, don't invalidate existing traces.
+pub const STEP_INFO_SCHEMA_VERSION: u32 = 1;
+
+const ZSTD_LEVEL: i32 = 3;
+const HEADER_SIZE: usize = 8 + 4 + 1 + 4;
+const CHUNK_HEADER_SIZE: usize = 1 + 8 + 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFileKind {
+    EventTable = 0,
+    FrameTable = 1,
+}
+
+impl TraceFileKind {
+    fn from_u8(kind: u8) -> Option<Self> {
+        match kind {
+            0 => Some(TraceFileKind::EventTable),
+            1 => Some(TraceFileKind::FrameTable),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChunkTag {
+    /// The table, or a run of its entries.
+    Table = 0,
+    /// The event table entries of external host calls of the slice.
+    ExternalHostCalls = 1,
+    /// The last write of the slice to each memory location.
+    MemoryWrites = 2,
+}
+
+#[derive(Debug, Error)]
+pub enum TraceFileError {
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0:?} is not a trace file.")]
+    NotATraceFile(PathBuf),
+    #[error("{path:?} has trace format version {found} but this build reads version {expected}, please regenerate the trace.")]
+    UnsupportedVersion {
+        path: PathBuf,
+        found: u32,
+        expected: u32,
+    },
+    #[error("{path:?} holds {found} but {expected:?} is expected.")]
+    UnexpectedKind {
+        path: PathBuf,
+        found: String,
+        expected: TraceFileKind,
+    },
+    #[error(
+        "{0:?} was written by a build with a different step layout, please regenerate the trace."
+    )]
+    SchemaMismatch(PathBuf),
+    #[error("{0:?} is corrupted, the digest of its content does not match.")]
+    DigestMismatch(PathBuf),
+    #[error("{path:?} is malformed: {reason}")]
+    Malformed { path: PathBuf, reason: String },
+}
+
+impl From<TraceFileError> for std::io::Error {
+    fn from(err: TraceFileError) -> Self {
+        match err {
+            TraceFileError::Io(err) => err,
+            err => std::io::Error::new(std::io::ErrorKind::InvalidData, err),
+        }
+    }
+}
+
+/// Appends chunks to a trace file, nothing but the chunk being written is held in memory.
+pub struct TraceFileWriter {
+    path: PathBuf,
+    fd: BufWriter<File>,
+}
+
+impl TraceFileWriter {
+    pub fn create(path: &Path, kind: TraceFileKind) -> Result<Self, TraceFileError> {
+        let mut fd = BufWriter::new(File::create(path)?);
+
+        fd.write_all(&TRACE_FILE_MAGIC)?;
+        fd.write_all(&TRACE_FILE_VERSION.to_le_bytes())?;
+        fd.write_all(&[kind as u8])?;
+        fd.write_all(&STEP_INFO_SCHEMA_VERSION.to_le_bytes())?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            fd,
+        })
+    }
+
+    pub fn append<T: Serialize + ?Sized>(
+        &mut self,
+        tag: ChunkTag,
+        value: &T,
+    ) -> Result<(), TraceFileError> {
+        let encoded = bincode::serialize(value).map_err(|err| TraceFileError::Malformed {
+            path: self.path.clone(),
+            reason: err.to_string(),
+        })?;
+        let payload = zstd::encode_all(&encoded[..], ZSTD_LEVEL)?;
+        let digest: [u8; 32] = Sha256::digest(&payload).into();
+
+        self.fd.write_all(&[tag as u8])?;
+        self.fd.write_all(&(payload.len() as u64).to_le_bytes())?;
+        self.fd.write_all(&digest)?;
+        self.fd.write_all(&payload)?;
+
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<PathBuf, TraceFileError> {
+        self.fd.flush()?;
+
+        Ok(self.path)
+    }
+}
+
+/// Reads the chunks of a trace file one at a time.
+pub struct TraceFileReader {
+    path: PathBuf,
+    fd: BufReader<File>,
+    file_len: u64,
+}
+
+impl TraceFileReader {
+    pub fn open(path: &Path, kind: TraceFileKind) -> Result<Self, TraceFileError> {
+        let mut fd = BufReader::new(File::open(path)?);
+        let file_len = fd.get_ref().metadata()?.len();
+
+        let mut header = [0u8; HEADER_SIZE];
+        match fd.read_exact(&mut header) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => {
+                return Err(TraceFileError::NotATraceFile(path.to_path_buf()))
+            }
+            Err(err) => return Err(err.into()),
+        }
+
+        if header[0..8] != TRACE_FILE_MAGIC {
+            return Err(TraceFileError::NotATraceFile(path.to_path_buf()));
+        }
+
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != TRACE_FILE_VERSION {
+            return Err(TraceFileError::UnsupportedVersion {
+                path: path.to_path_buf(),
+                found: version,
+                expected: TRACE_FILE_VERSION,
+            });
+        }
+
+        if TraceFileKind::from_u8(header[12]) != Some(kind) {
+            return Err(TraceFileError::UnexpectedKind {
+                path: path.to_path_buf(),
+                found: TraceFileKind::from_u8(header[12])
+                    .map_or(format!("unknown kind {}", header[12]), |kind| {
+                        format!("{:?}", kind)
+                    }),
+                expected: kind,
+            });
+        }
+
+        let schema_version = u32::from_le_bytes(header[13..HEADER_SIZE].try_into().unwrap());
+        if schema_version != STEP_INFO_SCHEMA_VERSION {
+            return Err(TraceFileError::SchemaMismatch(path.to_path_buf()));
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            fd,
+            file_len,
+        })
+    }
+
+    fn malformed(&self, reason: String) -> TraceFileError {
+        TraceFileError::Malformed {
+            path: self.path.clone(),
+            reason,
+        }
+    }
+
+    /// Returns the value of the next chunk tagged with `tag`, skipping chunks with other tags.
+    pub fn next_chunk<T: DeserializeOwned>(
+        &mut self,
+        tag: ChunkTag,
+    ) -> Result<Option<T>, TraceFileError> {
+        loop {
+            let mut chunk_header = [0u8; CHUNK_HEADER_SIZE];
+            match self.fd.read_exact(&mut chunk_header[0..1]) {
+                Ok(()) => (),
+                Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+                Err(err) => return Err(err.into()),
+            }
+            self.fd
+                .read_exact(&mut chunk_header[1..])
+                .map_err(|err| self.malformed(err.to_string()))?;
+
+            let len = u64::from_le_bytes(chunk_header[1..9].try_into().unwrap());
+
+            // Reject a corrupted length before it is used to seek or allocate the payload.
+            let remaining = self.file_len.saturating_sub(self.fd.stream_position()?);
+            if len > remaining {
+                return Err(self.malformed(format!(
+                    "the chunk takes {} bytes but only {} bytes remain",
+                    len, remaining
+                )));
+            }
+
+            if chunk_header[0] != tag as u8 {
+                self.fd.seek(SeekFrom::Current(len as i64))?;
+                continue;
+            }
+
+            let mut payload = vec![0u8; len as usize];
+            self.fd
+                .read_exact(&mut payload)
+                .map_err(|err| self.malformed(err.to_string()))?;
+
+            if Sha256::digest(&payload)[..] != chunk_header[9..] {
+                return Err(TraceFileError::DigestMismatch(self.path.clone()));
+            }
+
+            let encoded =
+                zstd::decode_all(&payload[..]).map_err(|err| self.malformed(err.to_string()))?;
+
+            return bincode::deserialize(&encoded)
+                .map(Some)
+                .map_err(|err| self.malformed(err.to_string()));
+        }
+    }
+}
+
+/// Writes a table as a single chunk.
+pub(crate) fn write_trace_file<T: Serialize>(
+    path: &Path,
+    kind: TraceFileKind,
+    table: &T,
+) -> Result<(), TraceFileError> {
+    let mut writer = TraceFileWriter::create(path, kind)?;
+    writer.append(ChunkTag::Table, table)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Reads a table written as a single chunk.
+pub(crate) fn read_trace_file<T: DeserializeOwned>(
+    path: &Path,
+    kind: TraceFileKind,
+) -> Result<T, TraceFileError> {
+    TraceFileReader::open(path, kind)?
+        .next_chunk(ChunkTag::Table)?
+        .ok_or_else(|| TraceFileError::Malformed {
+            path: path.to_path_buf(),
+            reason: "the table is missing".to_string(),
+        })
+}
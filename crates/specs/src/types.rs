@@ -60,4 +60,7 @@ pub enum FunctionType {
         op: usize,
         sig: ExternalHostCallSignature,
     },
+    HostFunctionPhantom {
+        function_name: String,
+    },
 }
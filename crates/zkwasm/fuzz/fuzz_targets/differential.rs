@@ -0,0 +1,20 @@
+#![no_main]
+
+use arbitrary::Unstructured;
+use delphinus_zkwasm_fuzz::differential;
+use delphinus_zkwasm_fuzz::generator::generate;
+use delphinus_zkwasm_fuzz::generator::GLOBALS;
+use delphinus_zkwasm_fuzz::wat2wasm;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let wat = match generate(&mut Unstructured::new(data)) {
+        Ok(wat) => wat,
+        Err(_) => return,
+    };
+    let wasm = wat2wasm(&wat);
+
+    if let Err(err) = differential(&wasm, GLOBALS) {
+        panic!("{}\n{}", err, wat);
+    }
+});
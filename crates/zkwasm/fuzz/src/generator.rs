@@ -0,0 +1,423 @@
+//! Random, valid and terminating Wasm programs in the text format.
+//!
+//! Programs stick to the integer instructions of MVP plus sign extension, except for rare
+//! floating-point expressions which exercise the pre-check of the loader. Functions only call
+//! the functions defined before them and loops are counted, so every program terminates.
+//! Divisors are kept away from 0 and -1 and addresses within the first page, so programs don't
+//! trap either.
+
+use arbitrary::Result;
+use arbitrary::Unstructured;
+
+const MAX_FUNCTIONS: usize = 3;
+const MAX_PARAMS: usize = 3;
+const MAX_LOCALS: usize = 3;
+const MAX_STATEMENTS: usize = 8;
+const MAX_DEPTH: u32 = 3;
+const MAX_LOOPS: u32 = 2;
+const MAX_LOOP_ITERATIONS: u32 = 4;
+const MAX_CALLS: u32 = 2;
+const DATA_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ValType {
+    I32,
+    I64,
+}
+
+impl ValType {
+    fn name(&self) -> &'static str {
+        match self {
+            ValType::I32 => "i32",
+            ValType::I64 => "i64",
+        }
+    }
+}
+
+impl<'a> arbitrary::Arbitrary<'a> for ValType {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(if u.arbitrary()? {
+            ValType::I32
+        } else {
+            ValType::I64
+        })
+    }
+}
+
+const GLOBAL_TYPES: [ValType; 4] = [ValType::I32, ValType::I64, ValType::I32, ValType::I64];
+
+/// The number of globals, exported as `g0`, `g1`, ... so that they can be compared after the
+/// execution.
+pub const GLOBALS: usize = GLOBAL_TYPES.len();
+
+struct Generator<'a, 'b> {
+    u: &'a mut Unstructured<'b>,
+    // Parameters of the functions generated so far, all of them return an i64.
+    functions: Vec<Vec<ValType>>,
+
+    // State of the function being generated.
+    locals: Vec<ValType>,
+    loops: u32,
+    calls: u32,
+}
+
+/// Generate a module exporting `zkmain` returning an i64, its memory as `memory` and its
+/// globals as `g0`, `g1`, ...
+pub fn generate(u: &mut Unstructured) -> Result<String> {
+    Generator {
+        u,
+        functions: vec![],
+        locals: vec![],
+        loops: 0,
+        calls: 0,
+    }
+    .module()
+}
+
+impl<'a, 'b> Generator<'a, 'b> {
+    fn module(mut self) -> Result<String> {
+        let mut module = String::from("(module\n  (memory (export \"memory\") 1 2)\n");
+
+        let data = (0..DATA_SIZE)
+            .map(|_| Ok(format!("\\{:02x}", self.u.arbitrary::<u8>()?)))
+            .collect::<Result<String>>()?;
+        module += &format!("  (data (i32.const 0) \"{}\")\n", data);
+
+        for (index, ty) in GLOBAL_TYPES.iter().enumerate() {
+            let init = self.constant(*ty)?;
+
+            module += &format!(
+                "  (global (export \"g{}\") (mut {}) {})\n",
+                index,
+                ty.name(),
+                init
+            );
+        }
+
+        let functions = self.u.int_in_range(0..=MAX_FUNCTIONS)?;
+        for index in 0..functions {
+            let params = (0..self.u.int_in_range(0..=MAX_PARAMS)?)
+                .map(|_| self.u.arbitrary())
+                .collect::<Result<Vec<ValType>>>()?;
+
+            module += &self.function(&format!("$f{}", index), &params)?;
+            self.functions.push(params);
+        }
+
+        module += &self.function("(export \"zkmain\")", &[])?;
+        module += ")\n";
+
+        Ok(module)
+    }
+
+    fn function(&mut self, name: &str, params: &[ValType]) -> Result<String> {
+        let locals = (0..self.u.int_in_range(0..=MAX_LOCALS)?)
+            .map(|_| self.u.arbitrary())
+            .collect::<Result<Vec<ValType>>>()?;
+
+        self.locals = params.iter().chain(locals.iter()).cloned().collect();
+        self.loops = 0;
+        self.calls = 0;
+
+        let mut function = format!("  (func {}", name);
+        for param in params {
+            function += &format!(" (param {})", param.name());
+        }
+        function += " (result i64)";
+        for local in &locals {
+            function += &format!(" (local {})", local.name());
+        }
+        // Loop counters are not visible to the generated expressions.
+        for counter in 0..MAX_LOOPS {
+            function += &format!(" (local $c{} i32)", counter);
+        }
+        function += "\n";
+
+        function += &self.statements(0)?;
+        function += &format!("    {}\n  )\n", self.expr(ValType::I64, 0)?);
+
+        Ok(function)
+    }
+
+    fn statements(&mut self, depth: u32) -> Result<String> {
+        let mut statements = String::new();
+
+        for _ in 0..self.u.int_in_range(0..=MAX_STATEMENTS)? {
+            statements += &format!("    {}\n", self.statement(depth)?);
+        }
+
+        Ok(statements)
+    }
+
+    fn statement(&mut self, depth: u32) -> Result<String> {
+        let ty = self.u.arbitrary()?;
+
+        Ok(match self.u.int_in_range(0..=7)? {
+            0 => match self.local(ty)? {
+                Some(local) => format!("(local.set {} {})", local, self.expr(ty, depth)?),
+                None => format!("(drop {})", self.expr(ty, depth)?),
+            },
+            1 => {
+                let global = self.global(ty)?;
+
+                format!("(global.set {} {})", global, self.expr(ty, depth)?)
+            }
+            2 => {
+                let size = match ty {
+                    ValType::I32 => *self.u.choose(&["", "8", "16"])?,
+                    ValType::I64 => *self.u.choose(&["", "8", "16", "32"])?,
+                };
+
+                format!(
+                    "({}.store{} offset={} {} {})",
+                    ty.name(),
+                    size,
+                    self.u.int_in_range(0..=15)?,
+                    self.address(depth)?,
+                    self.expr(ty, depth)?
+                )
+            }
+            3 if depth < MAX_DEPTH => format!(
+                "(if {} (then\n{}) (else\n{}))",
+                self.expr(ValType::I32, depth + 1)?,
+                self.statements(depth + 1)?,
+                self.statements(depth + 1)?
+            ),
+            4 if depth < MAX_DEPTH && self.loops < MAX_LOOPS => {
+                let counter = self.loops;
+                let iterations = self.u.int_in_range(1..=MAX_LOOP_ITERATIONS)?;
+
+                self.loops += 1;
+                let body = self.statements(depth + 1)?;
+                self.loops -= 1;
+
+                format!(
+                    "(local.set $c{counter} (i32.const 0)) (loop\n{body}    (br_if 0 (i32.lt_u (local.tee $c{counter} (i32.add (local.get $c{counter}) (i32.const 1))) (i32.const {iterations}))))",
+                )
+            }
+            5 if depth < MAX_DEPTH => format!(
+                "(block\n{}    (br_if 0 {})\n{})",
+                self.statements(depth + 1)?,
+                self.expr(ValType::I32, depth + 1)?,
+                self.statements(depth + 1)?
+            ),
+            6 if depth < MAX_DEPTH => format!(
+                "(block (block (block (br_table 0 1 2 {}))\n{})\n{})",
+                self.expr(ValType::I32, depth + 1)?,
+                self.statements(depth + 1)?,
+                self.statements(depth + 1)?
+            ),
+            _ => format!("(drop {})", self.expr(ty, depth)?),
+        })
+    }
+
+    fn constant(&mut self, ty: ValType) -> Result<String> {
+        Ok(match ty {
+            ValType::I32 => format!("(i32.const {})", self.u.arbitrary::<i32>()?),
+            ValType::I64 => format!("(i64.const {})", self.u.arbitrary::<i64>()?),
+        })
+    }
+
+    fn local(&mut self, ty: ValType) -> Result<Option<usize>> {
+        let candidates = self
+            .locals
+            .iter()
+            .enumerate()
+            .filter(|(_, local)| **local == ty)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if candidates.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(*self.u.choose(&candidates)?))
+        }
+    }
+
+    fn global(&mut self, ty: ValType) -> Result<usize> {
+        let candidates = (0..GLOBALS)
+            .filter(|index| GLOBAL_TYPES[*index] == ty)
+            .collect::<Vec<_>>();
+
+        Ok(*self.u.choose(&candidates)?)
+    }
+
+    // An address in the first page leaving room for the offset and an 8-byte access.
+    fn address(&mut self, depth: u32) -> Result<String> {
+        Ok(format!(
+            "(i32.and {} (i32.const 0xffe0))",
+            self.expr(ValType::I32, depth + 1)?
+        ))
+    }
+
+    fn leaf(&mut self, ty: ValType) -> Result<String> {
+        Ok(match self.u.int_in_range(0..=2)? {
+            0 => match self.local(ty)? {
+                Some(local) => format!("(local.get {})", local),
+                None => self.constant(ty)?,
+            },
+            1 => format!("(global.get {})", self.global(ty)?),
+            _ => self.constant(ty)?,
+        })
+    }
+
+    fn expr(&mut self, ty: ValType, depth: u32) -> Result<String> {
+        if depth >= MAX_DEPTH || self.u.ratio(1, 4)? {
+            return self.leaf(ty);
+        }
+
+        let t = ty.name();
+        let depth = depth + 1;
+
+        Ok(match self.u.int_in_range(0..=12)? {
+            0 => {
+                let op = *self.u.choose(&["add", "sub", "mul", "and", "or", "xor"])?;
+
+                format!(
+                    "({}.{} {} {})",
+                    t,
+                    op,
+                    self.expr(ty, depth)?,
+                    self.expr(ty, depth)?
+                )
+            }
+            1 => {
+                let op = *self.u.choose(&["div_s", "div_u", "rem_s", "rem_u"])?;
+
+                // The divisor is in [1, 65536].
+                format!(
+                    "({t}.{op} {} ({t}.add ({t}.and {} ({t}.const 0xffff)) ({t}.const 1)))",
+                    self.expr(ty, depth)?,
+                    self.expr(ty, depth)?
+                )
+            }
+            2 => {
+                let op = *self.u.choose(&["shl", "shr_s", "shr_u", "rotl", "rotr"])?;
+
+                format!(
+                    "({}.{} {} {})",
+                    t,
+                    op,
+                    self.expr(ty, depth)?,
+                    self.expr(ty, depth)?
+                )
+            }
+            3 => {
+                let op = *self.u.choose(&["clz", "ctz", "popcnt"])?;
+
+                format!("({}.{} {})", t, op, self.expr(ty, depth)?)
+            }
+            4 => {
+                let op = match ty {
+                    ValType::I32 => *self.u.choose(&["", "8_s", "8_u", "16_s", "16_u"])?,
+                    ValType::I64 => *self
+                        .u
+                        .choose(&["", "8_s", "8_u", "16_s", "16_u", "32_s", "32_u"])?,
+                };
+
+                format!(
+                    "({}.load{} offset={} {})",
+                    t,
+                    op,
+                    self.u.int_in_range(0..=15)?,
+                    self.address(depth)?
+                )
+            }
+            5 => format!(
+                "(select {} {} {})",
+                self.expr(ty, depth)?,
+                self.expr(ty, depth)?,
+                self.expr(ValType::I32, depth)?
+            ),
+            6 => format!(
+                "(if (result {}) {} (then {}) (else {}))",
+                t,
+                self.expr(ValType::I32, depth)?,
+                self.expr(ty, depth)?,
+                self.expr(ty, depth)?
+            ),
+            7 => format!(
+                "(block (result {}) (br_if 0 {} {}))",
+                t,
+                self.expr(ty, depth)?,
+                self.expr(ValType::I32, depth)?
+            ),
+            8 if !self.functions.is_empty() && self.calls < MAX_CALLS => {
+                let callee = self.u.choose_index(self.functions.len())?;
+                let params = self.functions[callee].clone();
+
+                self.calls += 1;
+
+                let mut call = format!("(call $f{}", callee);
+                for param in params {
+                    call += &format!(" {}", self.expr(param, depth)?);
+                }
+                call += ")";
+
+                match ty {
+                    ValType::I32 => format!("(i32.wrap_i64 {})", call),
+                    ValType::I64 => call,
+                }
+            }
+            9 => match self.local(ty)? {
+                Some(local) => format!("(local.tee {} {})", local, self.expr(ty, depth)?),
+                None => self.leaf(ty)?,
+            },
+            10 => match ty {
+                ValType::I32 => {
+                    let operand = self.u.arbitrary()?;
+                    let op = *self.u.choose(&[
+                        "eq", "ne", "lt_s", "lt_u", "gt_s", "gt_u", "le_s", "le_u", "ge_s", "ge_u",
+                    ])?;
+
+                    if self.u.ratio(1, 4)? {
+                        format!("({}.eqz {})", operand.name(), self.expr(operand, depth)?)
+                    } else {
+                        format!(
+                            "({}.{} {} {})",
+                            operand.name(),
+                            op,
+                            self.expr(operand, depth)?,
+                            self.expr(operand, depth)?
+                        )
+                    }
+                }
+                ValType::I64 => {
+                    let op = *self.u.choose(&["extend_i32_s", "extend_i32_u"])?;
+
+                    format!("(i64.{} {})", op, self.expr(ValType::I32, depth)?)
+                }
+            },
+            11 => match ty {
+                ValType::I32 => {
+                    if self.u.ratio(1, 2)? {
+                        format!("(i32.wrap_i64 {})", self.expr(ValType::I64, depth)?)
+                    } else {
+                        let op = *self.u.choose(&["extend8_s", "extend16_s"])?;
+
+                        format!("(i32.{} {})", op, self.expr(ty, depth)?)
+                    }
+                }
+                ValType::I64 => {
+                    let op = *self.u.choose(&["extend8_s", "extend16_s", "extend32_s"])?;
+
+                    format!("(i64.{} {})", op, self.expr(ty, depth)?)
+                }
+            },
+            12 => match ty {
+                ValType::I32 if self.u.ratio(1, 32)? => format!(
+                    "(i32.reinterpret_f32 (f32.convert_i32_s {}))",
+                    self.expr(ty, depth)?
+                ),
+                // Grow by 0 or 1 page.
+                ValType::I32 if self.u.arbitrary()? => format!(
+                    "(memory.grow (i32.and {} (i32.const 1)))",
+                    self.expr(ty, depth)?
+                ),
+                ValType::I32 => "(memory.size)".to_string(),
+                ValType::I64 => "(i64.extend_i32_u (memory.size))".to_string(),
+            },
+            _ => self.leaf(ty)?,
+        })
+    }
+}
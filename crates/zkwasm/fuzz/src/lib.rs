@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use delphinus_zkwasm::circuits::config::MIN_K;
+use delphinus_zkwasm::error::BuildingCircuitError;
+use delphinus_zkwasm::halo2_proofs::pairing::bn256::Fr;
+use delphinus_zkwasm::loader::slice::Slices;
+use delphinus_zkwasm::loader::ZkWasmLoader;
+use delphinus_zkwasm::runtime::host::default_env::DefaultHostEnvBuilder;
+use delphinus_zkwasm::runtime::host::default_env::ExecutionArg;
+use delphinus_zkwasm::runtime::host::HostEnvBuilder;
+use delphinus_zkwasm::runtime::memory_event_of_step;
+use delphinus_zkwasm::runtime::monitor::table_monitor::TableMonitor;
+use delphinus_zkwasm::runtime::trace_checker::TraceConsistency;
+use specs::etable::EventTable;
+use specs::mtable::AccessType;
+use specs::mtable::LocationType;
+use specs::mtable::VarType;
+use specs::TableBackend;
+use specs::Tables;
+use specs::TraceBackend;
+
+pub mod generator;
+
+pub fn wat2wasm(wat: &str) -> Vec<u8> {
+    let mut features = wabt::Features::new();
+    features.enable_sign_extension();
+
+    wabt::wat2wasm_with_features(wat, features).expect("failed to parse wat")
+}
+
+/// The observable state after running `zkmain`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub result: Option<i64>,
+    pub globals: Vec<u64>,
+    /// The heap in 8-byte blocks.
+    pub memory: Vec<u64>,
+}
+
+/// Run the module on an interpreter independent of the traced one.
+pub fn run_reference(wasm: &[u8], globals: usize) -> Result<Outcome> {
+    use wasmi_reference::Engine;
+    use wasmi_reference::Linker;
+    use wasmi_reference::Module;
+    use wasmi_reference::Store;
+    use wasmi_reference::Value;
+
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm).map_err(|err| anyhow!("{}", err))?;
+    let mut store = Store::new(&engine, ());
+    let instance = Linker::<()>::new(&engine)
+        .instantiate(&mut store, &module)
+        .and_then(|instance| instance.start(&mut store))
+        .map_err(|err| anyhow!("{}", err))?;
+
+    let result = instance
+        .get_typed_func::<(), i64>(&store, "zkmain")
+        .map_err(|err| anyhow!("{}", err))?
+        .call(&mut store, ())
+        .map_err(|err| anyhow!("{}", err))?;
+
+    let globals = (0..globals)
+        .map(|index| {
+            let global = instance
+                .get_global(&store, &format!("g{}", index))
+                .ok_or(anyhow!("global g{} is not exported", index))?;
+
+            match global.get(&store) {
+                Value::I32(value) => Ok(value as u32 as u64),
+                Value::I64(value) => Ok(value as u64),
+                value => Err(anyhow!("unexpected global {:?}", value)),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or(anyhow!("memory is not exported"))?
+        .data(&store)
+        .chunks(8)
+        .map(|block| u64::from_le_bytes(block.try_into().unwrap()))
+        .collect();
+
+    Ok(Outcome {
+        result: Some(result),
+        globals,
+        memory,
+    })
+}
+
+// Replay the writes of the trace on top of the initial memory.
+fn final_state(tables: &Tables, globals: usize) -> Result<(Vec<u64>, Vec<u64>)> {
+    let mut written = HashMap::new();
+    let mut pages = 0;
+
+    for etable in &tables.execution_tables.etable {
+        let etable = match etable {
+            TableBackend::Memory(etable) => EventTable::new(etable.entries().clone()),
+            TableBackend::File(path) => EventTable::read(path)?,
+        };
+
+        for entry in etable.entries() {
+            pages = entry.allocated_memory_pages;
+
+            for event in memory_event_of_step(entry) {
+                if event.atype == AccessType::Write {
+                    written.insert((event.ltype, event.offset), (event.vtype, event.value));
+                }
+            }
+        }
+    }
+
+    let value_at = |ltype, offset| {
+        let (vtype, value) = written.get(&(ltype, offset)).cloned().unwrap_or_else(|| {
+            tables
+                .compilation_tables
+                .imtable
+                .try_find(ltype, offset)
+                .map_or((VarType::I64, 0), |entry| (entry.vtype, entry.value))
+        });
+
+        match vtype {
+            VarType::I32 => value as u32 as u64,
+            VarType::I64 => value,
+        }
+    };
+
+    let globals = (0..globals as u32)
+        .map(|index| value_at(LocationType::Global, index))
+        .collect();
+    let memory = (0..pages * 64 * 1024 / 8)
+        .map(|offset| value_at(LocationType::Heap, offset))
+        .collect();
+
+    Ok((globals, memory))
+}
+
+/// Run the module on zkWasm, check its trace and circuit, and return the outcome. Returns
+/// `Ok(None)` if the module is rejected by the pre-check or does not fit in the circuit.
+pub fn run_zkwasm(wasm: &[u8], globals: usize) -> Result<Option<Outcome>> {
+    // Rejected by the pre-check, e.g. floating-point instructions.
+    let module = match ZkWasmLoader::parse_module(&wasm.to_vec()) {
+        Ok(module) => module,
+        Err(_) => return Ok(None),
+    };
+
+    let env = DefaultHostEnvBuilder.create_env(
+        MIN_K,
+        ExecutionArg {
+            public_inputs: vec![],
+            private_inputs: vec![],
+            context_inputs: vec![],
+            indexed_inputs: vec![],
+        },
+    );
+    let mut monitor = TableMonitor::new(MIN_K, &vec![], TraceBackend::Memory, &env);
+    let loader = ZkWasmLoader::new(MIN_K, env)?;
+    let runner = loader.compile(&module, &mut monitor)?;
+    let execution_result = loader.run(runner, &mut monitor)?;
+    let instances: Vec<Fr> = execution_result.public_inputs_and_outputs();
+
+    let tables = monitor.into_tables();
+
+    let inconsistencies = tables.check_consistency()?;
+    if !inconsistencies.is_empty() {
+        return Err(anyhow!(
+            "Inconsistent trace:\n{}",
+            inconsistencies
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("\n")
+        ));
+    }
+
+    let (globals, memory) = final_state(&tables, globals)?;
+
+    let slices = match Slices::new(MIN_K, tables) {
+        Ok(slices) => slices,
+        Err(
+            BuildingCircuitError::MultiSlicesNotSupport(_)
+            | BuildingCircuitError::EtableEntriesExceedLimit(..)
+            | BuildingCircuitError::PagesExceedLimit(..),
+        ) => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    if let Err(err) = slices.mock_test_all(instances) {
+        return match err.downcast_ref::<BuildingCircuitError>() {
+            Some(_) => Ok(None),
+            None => Err(err),
+        };
+    }
+
+    Ok(Some(Outcome {
+        result: execution_result
+            .result
+            .and_then(|value| value.try_into::<i64>()),
+        globals,
+        memory,
+    }))
+}
+
+/// Run the module on both zkWasm and the reference interpreter, and check that the circuit is
+/// satisfied and the outcomes agree.
+pub fn differential(wasm: &[u8], globals: usize) -> Result<()> {
+    let outcome = match run_zkwasm(wasm, globals)? {
+        Some(outcome) => outcome,
+        None => return Ok(()),
+    };
+    let expected = run_reference(wasm, globals)?;
+
+    if outcome != expected {
+        return Err(anyhow!(
+            "zkWasm and the reference interpreter disagree:\n    zkWasm: {:?}\n    reference: {:?}",
+            outcome,
+            expected
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::Unstructured;
+
+    use crate::differential;
+    use crate::generator::generate;
+    use crate::generator::GLOBALS;
+    use crate::wat2wasm;
+
+    #[test]
+    fn test_differential_seeds() {
+        for seed in 0..8u8 {
+            let data = (0..4096)
+                .map(|i: u32| (i as u8).wrapping_mul(31).wrapping_add(seed))
+                .collect::<Vec<_>>();
+            let wat = generate(&mut Unstructured::new(&data)).unwrap();
+            let wasm = wat2wasm(&wat);
+
+            differential(&wasm, GLOBALS).unwrap();
+        }
+    }
+}
@@ -12,10 +12,14 @@ use crate::circuits::utils::Context;
 use super::BitTableChip;
 use super::BitTableOp;
 use super::BitTableTrait;
+use super::LimbLayout;
 use super::BLOCK_SEL_OFFSET;
-use super::STEP_SIZE;
-use super::U32_OFFSET;
-use super::U8_OFFSET;
+
+fn u32_to_le_limbs(layout: LimbLayout, value: u32) -> Vec<u32> {
+    (0..layout.limbs_per_u32())
+        .map(|index| (value >> (index * layout.limb_bits)) & ((1 << layout.limb_bits) - 1))
+        .collect()
+}
 
 pub(crate) struct BitTableAssign {
     op: BitTableOp,
@@ -73,7 +77,7 @@ impl BitTableTrait for EventTableWithMemoryInfo {
 
 impl<F: FieldExt> BitTableChip<F> {
     fn init(&self, ctx: &mut Context<'_, F>) -> Result<(), Error> {
-        for _ in 0..self.max_available_rows / STEP_SIZE {
+        for _ in 0..self.max_available_rows / self.config.layout.step_size() {
             ctx.region.assign_fixed(
                 || "bit table: block sel",
                 self.config.block_sel,
@@ -81,7 +85,7 @@ impl<F: FieldExt> BitTableChip<F> {
                 || Ok(F::one()),
             )?;
 
-            for i in U8_OFFSET {
+            for i in self.config.layout.limb_offsets() {
                 ctx.region.assign_fixed(
                     || "bit table: lookup sel",
                     self.config.lookup_sel,
@@ -90,7 +94,7 @@ impl<F: FieldExt> BitTableChip<F> {
                 )?;
             }
 
-            for i in U32_OFFSET {
+            for i in self.config.layout.u32_offset() {
                 ctx.region.assign_fixed(
                     || "bit table: u32 sel",
                     self.config.u32_sel,
@@ -99,7 +103,7 @@ impl<F: FieldExt> BitTableChip<F> {
                 )?;
             }
 
-            ctx.step(STEP_SIZE);
+            ctx.step(self.config.layout.step_size());
         }
 
         Ok(())
@@ -108,7 +112,7 @@ impl<F: FieldExt> BitTableChip<F> {
     fn assign_op(&self, ctx: &mut Context<'_, F>, op: BitTableOp) -> Result<(), Error> {
         let op_index = F::from(op.index() as u64);
 
-        for i in 0..STEP_SIZE {
+        for i in 0..self.config.layout.step_size() {
             ctx.region.assign_advice(
                 || "bit table op",
                 self.config.op,
@@ -118,7 +122,7 @@ impl<F: FieldExt> BitTableChip<F> {
         }
 
         if op == BitTableOp::Popcnt {
-            for i in U32_OFFSET {
+            for i in self.config.layout.u32_offset() {
                 ctx.region.assign_advice(
                     || "bit table op",
                     self.config.helper,
@@ -140,8 +144,14 @@ impl<F: FieldExt> BitTableChip<F> {
         let low_u32 = value as u32;
         let high_u32 = (value >> 32) as u32;
 
-        let low_u8s_popcnt = low_u32.to_le_bytes().map(|byte| byte.count_ones());
-        let high_u8s_popcnt = high_u32.to_le_bytes().map(|byte| byte.count_ones());
+        let layout = self.config.layout;
+
+        let low_limbs_popcnt = u32_to_le_limbs(layout, low_u32)
+            .into_iter()
+            .map(|limb| limb.count_ones());
+        let high_limbs_popcnt = u32_to_le_limbs(layout, high_u32)
+            .into_iter()
+            .map(|limb| limb.count_ones());
         let low_u32_popcnt = low_u32.count_ones();
         let high_u32_popcnt = high_u32.count_ones();
 
@@ -153,7 +163,7 @@ impl<F: FieldExt> BitTableChip<F> {
         )?;
 
         macro_rules! assign_u32 {
-            ($v: expr, $bytes: expr, $offset: expr) => {{
+            ($v: expr, $limbs: expr, $offset: expr) => {{
                 ctx.region.assign_advice(
                     || "bit table: assign u32",
                     col,
@@ -161,19 +171,19 @@ impl<F: FieldExt> BitTableChip<F> {
                     || Ok(F::from($v as u64)),
                 )?;
 
-                for (index, byte_count_ones) in $bytes.into_iter().enumerate() {
+                for (index, limb_count_ones) in $limbs.into_iter().enumerate() {
                     ctx.region.assign_advice(
-                        || "bit table: assign u8",
+                        || "bit table: assign limb",
                         col,
                         ctx.offset + 1 + index + $offset,
-                        || Ok(F::from(byte_count_ones as u64)),
+                        || Ok(F::from(limb_count_ones as u64)),
                     )?;
                 }
             }};
         }
 
-        assign_u32!(low_u32_popcnt, low_u8s_popcnt, U32_OFFSET[0]);
-        assign_u32!(high_u32_popcnt, high_u8s_popcnt, U32_OFFSET[1]);
+        assign_u32!(low_u32_popcnt, low_limbs_popcnt, layout.u32_offset()[0]);
+        assign_u32!(high_u32_popcnt, high_limbs_popcnt, layout.u32_offset()[1]);
 
         Ok(())
     }
@@ -186,6 +196,7 @@ impl<F: FieldExt> BitTableChip<F> {
     ) -> Result<(), Error> {
         let low_u32 = value as u32;
         let high_u32 = (value >> 32) as u32;
+        let layout = self.config.layout;
 
         ctx.region.assign_advice(
             || "bit table: assign u64",
@@ -196,7 +207,7 @@ impl<F: FieldExt> BitTableChip<F> {
 
         macro_rules! assign_u32 {
             ($v: expr, $offset: expr) => {{
-                let bytes = $v.to_le_bytes();
+                let limbs = u32_to_le_limbs(layout, $v);
 
                 ctx.region.assign_advice(
                     || "bit table: assign u32",
@@ -205,19 +216,19 @@ impl<F: FieldExt> BitTableChip<F> {
                     || Ok(F::from($v as u64)),
                 )?;
 
-                for (index, byte) in bytes.into_iter().enumerate() {
+                for (index, limb) in limbs.into_iter().enumerate() {
                     ctx.region.assign_advice(
-                        || "bit table: assign u8",
+                        || "bit table: assign limb",
                         col,
                         ctx.offset + 1 + index + $offset,
-                        || Ok(F::from(byte as u64)),
+                        || Ok(F::from(limb as u64)),
                     )?;
                 }
             }};
         }
 
-        assign_u32!(low_u32, U32_OFFSET[0]);
-        assign_u32!(high_u32, U32_OFFSET[1]);
+        assign_u32!(low_u32, layout.u32_offset()[0]);
+        assign_u32!(high_u32, layout.u32_offset()[1]);
 
         Ok(())
     }
@@ -227,7 +238,7 @@ impl<F: FieldExt> BitTableChip<F> {
         ctx: &mut Context<'_, F>,
         entries: &Vec<BitTableAssign>,
     ) -> Result<(), Error> {
-        assert!(entries.len() <= self.max_available_rows / STEP_SIZE);
+        assert!(entries.len() <= self.max_available_rows / self.config.layout.step_size());
 
         for entry in entries {
             self.assign_op(ctx, entry.op)?;
@@ -240,7 +251,7 @@ impl<F: FieldExt> BitTableChip<F> {
                 self.assign_u64_le(ctx, self.config.result, entry.result)?;
             }
 
-            ctx.step(STEP_SIZE);
+            ctx.step(self.config.layout.step_size());
         }
 
         Ok(())
@@ -16,6 +16,7 @@ use crate::prev;
 
 use self::assign::BitTableAssign;
 
+use super::config::MIN_K;
 use super::rtable::RangeTableConfig;
 
 mod assign;
@@ -53,15 +54,58 @@ pub struct BitTableConfig<F: FieldExt> {
     right: Column<Advice>,
     result: Column<Advice>,
 
+    layout: LimbLayout,
+
     _mark: PhantomData<F>,
 }
 
-pub(in crate::circuits) const STEP_SIZE: usize = 11;
+/// How u32 values are split into limbs looked up in the rtable op table. The op table holds
+/// `3 * (1 << (2 * limb_bits))` rows, so circuits smaller than K=18 split u32 into u4 limbs
+/// instead of u8 limbs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct LimbLayout {
+    pub(crate) limb_bits: usize,
+}
+
+impl LimbLayout {
+    pub(crate) const U4: LimbLayout = LimbLayout { limb_bits: 4 };
+    pub(crate) const U8: LimbLayout = LimbLayout { limb_bits: 8 };
+
+    pub(crate) fn for_k(k: u32) -> Self {
+        if k < MIN_K {
+            Self::U4
+        } else {
+            Self::U8
+        }
+    }
+
+    pub(self) fn limbs_per_u32(&self) -> usize {
+        32 / self.limb_bits
+    }
+
+    pub(in crate::circuits) fn step_size(&self) -> usize {
+        1 + 2 * (1 + self.limbs_per_u32())
+    }
+
+    pub(self) fn u32_offset(&self) -> [usize; 2] {
+        [1, 2 + self.limbs_per_u32()]
+    }
+
+    pub(self) fn limb_offsets(&self) -> impl Iterator<Item = usize> {
+        let limbs_per_u32 = self.limbs_per_u32();
+
+        self.u32_offset()
+            .into_iter()
+            .flat_map(move |offset| offset + 1..offset + 1 + limbs_per_u32)
+    }
+}
+
 pub(self) const BLOCK_SEL_OFFSET: usize = 1;
-pub(self) const U32_OFFSET: [usize; 2] = [1, 6];
-pub(self) const U8_OFFSET: [usize; 8] = [2, 3, 4, 5, 7, 8, 9, 10];
 
 /*
+ * The layout below is for u8 limbs, below K=18 each u32 is split into 8 * u4 limbs in the
+ * same way, see `LimbLayout`.
+ *
  * Columns:
  * --------------------------------------------------------------------------------
  * block: enable etable lookup. put the bit on the second line to minimize
@@ -104,7 +148,11 @@ impl<F: FieldExt> BitTableConfig<F> {
      * 1. lookup (op, l_u8, r_u8, res_u8) in rtable if lookup_sel is enabled.
      * 2. etable lookups (op, l_u64, r_u64, res_u64) in this table's entries.
      */
-    pub(crate) fn configure(meta: &mut ConstraintSystem<F>, rtable: &RangeTableConfig<F>) -> Self {
+    pub(crate) fn configure(
+        meta: &mut ConstraintSystem<F>,
+        rtable: &RangeTableConfig<F>,
+        layout: LimbLayout,
+    ) -> Self {
         let block_sel = meta.fixed_column();
         let u32_sel = meta.fixed_column();
         let lookup_sel = meta.fixed_column();
@@ -150,13 +198,14 @@ impl<F: FieldExt> BitTableConfig<F> {
             // For bit operator
             macro_rules! compose_u32_helper {
                 ($col:expr) => {
-                    (0..4)
+                    (0..layout.limbs_per_u32() as i32)
                         .into_iter()
                         .map(|x| {
                             if x == 0 {
                                 nextn!(meta, $col, 1)
                             } else {
-                                (nextn!(meta, $col, x + 1)) * constant_from!(1u64 << (8 * x))
+                                (nextn!(meta, $col, x + 1))
+                                    * constant_from!(1u64 << (layout.limb_bits as i32 * x))
                             }
                         })
                         .reduce(|acc, x| acc + x)
@@ -167,7 +216,7 @@ impl<F: FieldExt> BitTableConfig<F> {
             // For popcnt operator
             macro_rules! acc_u32_helper {
                 ($col:expr) => {
-                    (0..4)
+                    (0..layout.limbs_per_u32() as i32)
                         .into_iter()
                         .map(|x| (nextn!(meta, $col, 1 + x)))
                         .reduce(|acc, x| acc + x)
@@ -204,6 +253,8 @@ impl<F: FieldExt> BitTableConfig<F> {
         });
 
         meta.create_gate("bit table: 3. acc u64", |meta| {
+            let high_u32_rotation = (layout.u32_offset()[1] - BLOCK_SEL_OFFSET) as i32;
+
             let is_popcnt = curr!(meta, helper);
             let is_bit = constant_from!(1) - is_popcnt.clone();
 
@@ -212,7 +263,7 @@ impl<F: FieldExt> BitTableConfig<F> {
                     fixed_curr!(meta, block_sel)
                         * (prev!(meta, $col)
                             - curr!(meta, $col)
-                            - nextn!(meta, $col, 5) * constant_from!(1u64 << 32))
+                            - nextn!(meta, $col, high_u32_rotation) * constant_from!(1u64 << 32))
                 };
             }
 
@@ -226,7 +277,9 @@ impl<F: FieldExt> BitTableConfig<F> {
                 ($col: expr) => {
                     fixed_curr!(meta, block_sel)
                         * is_popcnt
-                        * (prev!(meta, $col) - curr!(meta, $col) - nextn!(meta, $col, 5))
+                        * (prev!(meta, $col)
+                            - curr!(meta, $col)
+                            - nextn!(meta, $col, high_u32_rotation))
                 };
             }
 
@@ -247,6 +300,7 @@ impl<F: FieldExt> BitTableConfig<F> {
             left,
             right,
             result,
+            layout,
             _mark: PhantomData,
         }
     }
@@ -259,9 +313,11 @@ pub struct BitTableChip<F: FieldExt> {
 
 impl<F: FieldExt> BitTableChip<F> {
     pub fn new(config: BitTableConfig<F>, max_available_rows: usize) -> Self {
+        let step_size = config.layout.step_size();
+
         BitTableChip {
             config,
-            max_available_rows: max_available_rows / STEP_SIZE * STEP_SIZE,
+            max_available_rows: max_available_rows / step_size * step_size,
         }
     }
 }
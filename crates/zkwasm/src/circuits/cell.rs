@@ -132,9 +132,33 @@ macro_rules! define_cell {
 
 define_cell!(AllocatedBitCell, F::one());
 define_cell!(AllocatedU8Cell, F::from(u8::MAX as u64));
-define_cell!(AllocatedU16Cell, F::from(u16::MAX as u64));
 define_cell!(AllocatedUnlimitedCell, -F::one());
 
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct AllocatedU16Cell<F: FieldExt> {
+    pub(crate) cell: AllocatedCell<F>,
+    /// Little-endian u8 limbs of the cell, only with the u4 limbs layout, see `U16Column`.
+    pub(crate) limbs: Option<[AllocatedCell<F>; 2]>,
+}
+
+impl<F: FieldExt> CellExpression<F> for AllocatedU16Cell<F> {
+    fn curr_expr(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
+        self.cell.curr_expr(meta)
+    }
+
+    fn assign(&self, ctx: &mut Context<'_, F>, value: F) -> Result<AssignedCell<F, F>, Error> {
+        if let Some(limbs) = self.limbs {
+            let u16_value = value.get_lower_128() as u64;
+
+            for (i, limb) in limbs.into_iter().enumerate() {
+                limb.assign(ctx, F::from((u16_value >> (i * 8)) & 0xff))?;
+            }
+        }
+
+        self.cell.assign(ctx, value)
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct AllocatedCommonRangeCell<F: FieldExt> {
     pub(crate) cell: AllocatedCell<F>,
@@ -1,31 +1,14 @@
-use std::sync::Mutex;
-
 pub const POW_TABLE_POWER_START: u64 = 128;
 
+/// The smallest K of the default u8 limbs layout, the default minimum size of a circuit.
 pub const MIN_K: u32 = 18;
-const MAX_K: u32 = 22;
-
-lazy_static! {
-    static ref ZKWASM_K: Mutex<Option<u32>> = Mutex::new(None);
-}
-
-pub(crate) fn set_zkwasm_k(k: u32) {
-    assert!(k >= MIN_K);
-    assert!(k <= MAX_K);
-
-    let mut zkwasm_k = (*ZKWASM_K).lock().unwrap();
-    *zkwasm_k = Some(k);
-}
-
-pub(in crate::circuits) fn zkwasm_k() -> u32 {
-    ZKWASM_K
-        .lock()
-        .unwrap()
-        .expect("ZKWASM_K is not set, please make sure 'init_zkwasm_runtime' have called.")
-}
+/// The rtable op table takes about 3 * 2^16 rows with u8 limbs, circuits between MIN_SMALL_K and
+/// MIN_K use the u4 limbs layout instead, see `bit_table::LimbLayout`.
+pub const MIN_SMALL_K: u32 = 16;
+pub const MAX_K: u32 = 26;
 
-pub(crate) fn init_zkwasm_runtime(k: u32) {
-    set_zkwasm_k(k);
+pub(crate) fn is_supported_k(k: u32) -> bool {
+    (MIN_SMALL_K..=MAX_K).contains(&k)
 }
 
 pub(crate) fn common_range(k: u32) -> u32 {
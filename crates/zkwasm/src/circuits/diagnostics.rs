@@ -0,0 +1,196 @@
+use std::fmt;
+
+use halo2_proofs::dev::VerifyFailure;
+use specs::itable::InstructionTableEntry;
+use specs::itable::OpcodeClass;
+use specs::mtable::MemoryTableEntry;
+use specs::slice::Slice;
+use thiserror::Error;
+
+use crate::circuits::etable::EVENT_TABLE_ENTRY_ROWS;
+use crate::circuits::mtable::MEMORY_TABLE_ENTRY_ROWS;
+use crate::circuits::utils::table_entry::MemoryWritingTable;
+use crate::runtime::memory_event_of_step;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitTable {
+    EventTable,
+    MemoryTable,
+    FrameTable,
+    ImageTable,
+    PostImageTable,
+    BitTable,
+    Unknown,
+}
+
+/// A `VerifyFailure` of the mock prover mapped back to the trace.
+#[derive(Clone, Debug)]
+pub struct ConstraintFailure {
+    /// The failure as reported by halo2.
+    pub failure: String,
+    pub table: CircuitTable,
+    pub row: Option<usize>,
+    /// The event whose rows the failure is on, or which wrote the memory table entry.
+    pub eid: Option<u32>,
+    pub opcode_class: Option<OpcodeClass>,
+    pub instruction: Option<InstructionTableEntry>,
+    pub memory_entry: Option<MemoryTableEntry>,
+}
+
+impl fmt::Display for ConstraintFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.table)?;
+        if let Some(row) = self.row {
+            write!(f, " row {}", row)?;
+        }
+        if let Some(eid) = self.eid {
+            write!(f, " eid {}", eid)?;
+        }
+        if let Some(opcode_class) = self.opcode_class {
+            write!(f, " {:?}", opcode_class)?;
+        }
+        if let Some(instruction) = &self.instruction {
+            write!(
+                f,
+                " (fid {} iid {}: {:?})",
+                instruction.fid, instruction.iid, instruction.opcode
+            )?;
+        }
+        if let Some(memory_entry) = &self.memory_entry {
+            write!(
+                f,
+                " {:?}[{}] {:?} = {}",
+                memory_entry.ltype, memory_entry.offset, memory_entry.atype, memory_entry.value
+            )?;
+        }
+
+        write!(f, ": {}", self.failure)
+    }
+}
+
+#[derive(Debug, Error)]
+pub struct MockTestError {
+    pub slice: Option<usize>,
+    pub failures: Vec<ConstraintFailure>,
+}
+
+impl fmt::Display for MockTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.slice {
+            Some(slice) => write!(f, "Mock test of slice {} failed", slice)?,
+            None => write!(f, "Mock test failed")?,
+        }
+        writeln!(f, " with {} failure(s):", self.failures.len())?;
+
+        for failure in &self.failures {
+            writeln!(f, "    {}", failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Gates and lookups are named after the table owning them, e.g. "c8a. ..." and "op_bin ..." in
+// the event table and "mc5. ..." in the memory table.
+fn classify(failure: &str) -> CircuitTable {
+    if failure.contains("jtable") {
+        return CircuitTable::FrameTable;
+    }
+    if failure.contains("post image table") {
+        return CircuitTable::PostImageTable;
+    }
+    if failure.contains("bit_table") || failure.contains("bit table") {
+        return CircuitTable::BitTable;
+    }
+
+    let is_numbered = |token: &str, prefix: &str| {
+        token
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.chars().next())
+            .map_or(false, |c| c.is_ascii_digit())
+    };
+
+    for token in failure.split(|c: char| c.is_whitespace() || "'\"(".contains(c)) {
+        if is_numbered(token, "mc") {
+            return CircuitTable::MemoryTable;
+        }
+        if is_numbered(token, "c") || token.starts_with("op_") {
+            return CircuitTable::EventTable;
+        }
+    }
+
+    if failure.contains("image") || failure.contains("itable") {
+        return CircuitTable::ImageTable;
+    }
+
+    CircuitTable::Unknown
+}
+
+// The fields of `VerifyFailure` differ between halo2 versions while its display always ends
+// with the row, e.g. "... is not satisfied on row 8".
+fn failure_row(failure: &str) -> Option<usize> {
+    let (_, rest) = failure.rsplit_once("row")?;
+    let digits = rest
+        .trim_start_matches(|c: char| c == ':' || c == '=' || c.is_whitespace())
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+
+    digits.parse().ok()
+}
+
+pub(crate) fn diagnose(
+    k: u32,
+    slice: &Slice,
+    failures: &[VerifyFailure],
+) -> Vec<ConstraintFailure> {
+    let etable = slice.etable.entries();
+    let mtable = MemoryWritingTable::from(k, slice.create_memory_table(memory_event_of_step));
+
+    failures
+        .iter()
+        .map(|failure| {
+            let description = failure.to_string();
+            let table = classify(&description);
+            let row = failure_row(&description);
+
+            let mut diagnosis = ConstraintFailure {
+                failure: description,
+                table,
+                row,
+                eid: None,
+                opcode_class: None,
+                instruction: None,
+                memory_entry: None,
+            };
+
+            let event = match (table, row) {
+                (CircuitTable::EventTable, Some(row)) => {
+                    etable.get(row / EVENT_TABLE_ENTRY_ROWS as usize)
+                }
+                (CircuitTable::MemoryTable, Some(row)) => mtable
+                    .0
+                    .get(row / MEMORY_TABLE_ENTRY_ROWS as usize)
+                    .and_then(|entry| {
+                        diagnosis.memory_entry = Some(entry.entry.clone());
+
+                        etable
+                            .binary_search_by_key(&entry.entry.eid, |event| event.eid)
+                            .ok()
+                            .map(|index| &etable[index])
+                    }),
+                _ => None,
+            };
+
+            if let Some(event) = event {
+                let instruction = event.get_instruction(&slice.itable);
+
+                diagnosis.eid = Some(event.eid);
+                diagnosis.opcode_class = Some((&instruction.opcode).into());
+                diagnosis.instruction = Some(instruction.clone());
+            }
+
+            diagnosis
+        })
+        .collect()
+}
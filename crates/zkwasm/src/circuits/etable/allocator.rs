@@ -1,6 +1,7 @@
 use super::AllocatedU32StateCell;
 use super::EVENT_TABLE_ENTRY_ROWS;
 use crate::circuits::bit_table::BitTableOp;
+use crate::circuits::bit_table::LimbLayout;
 use crate::circuits::cell::*;
 use crate::circuits::etable::ConstraintBuilder;
 use crate::circuits::rtable::RangeTableConfig;
@@ -117,6 +118,35 @@ impl<F: FieldExt> AllocatedMemoryTableLookupReadCell<F> {
     }
 }
 
+impl<F: FieldExt> AllocatedMemoryTableLookupReadCell<F> {
+    /// Assign a cell allocated by `alloc_memory_table_lookup_read_or_write_cell_with_value`
+    /// for a writing access.
+    pub(crate) fn assign_write(
+        &self,
+        ctx: &mut Context<'_, F>,
+        eid: u32,
+        end_eid: u32,
+        offset: u32,
+        l_type: LocationType,
+        is_i32: bool,
+        value: u64,
+    ) -> Result<(), Error> {
+        self.encode_cell.assign_bn(
+            ctx,
+            &encode_memory_table_entry(
+                (offset as u64).into(),
+                (l_type as u64).into(),
+                (is_i32 as u64).into(),
+            ),
+        )?;
+        self.start_eid_cell.assign_u32(ctx, eid)?;
+        self.end_eid_cell.assign_u32(ctx, end_eid)?;
+        self.value_cell.assign(ctx, value.into())?;
+
+        Ok(())
+    }
+}
+
 impl<F: FieldExt> AllocatedMemoryTableLookupWriteCell<F> {
     pub(crate) fn assign(
         &self,
@@ -158,18 +188,18 @@ const BIT_COLUMNS: usize = 12;
 const U8_COLUMNS: usize = 1;
 const U32_CELLS: usize = 2;
 const U32_PERMUTATION_CELLS: usize = if cfg!(feature = "continuation") {
-    10
+    14
 } else {
     0
 };
 const U64_CELLS: usize = 5;
 const U16_COLUMNS: usize =
     U64_CELLS + ((U32_CELLS + U32_PERMUTATION_CELLS).next_multiple_of(2) / 2);
-const COMMON_RANGE_COLUMNS: usize = if cfg!(feature = "continuation") { 4 } else { 6 };
+const COMMON_RANGE_COLUMNS: usize = if cfg!(feature = "continuation") { 4 } else { 7 };
 const UNLIMITED_COLUMNS: usize = if cfg!(feature = "continuation") {
-    10
+    11
 } else {
-    8
+    9
 };
 const MEMORY_TABLE_LOOKUP_COLUMNS: usize = 2;
 
@@ -351,6 +381,7 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
         rtable: &RangeTableConfig<F>,
         mtable: &impl ConfigureLookupTable<F>,
         cols: &mut impl Iterator<Item = Column<Advice>>,
+        limb_layout: LimbLayout,
     ) -> Self {
         let mut allocator = Self::_new(
             meta,
@@ -359,6 +390,7 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
             rtable,
             mtable,
             cols,
+            limb_layout,
         );
         for _ in 0..U32_CELLS {
             let cell = allocator.prepare_alloc_u32_cell();
@@ -383,6 +415,7 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
         rtable: &RangeTableConfig<F>,
         mtable: &impl ConfigureLookupTable<F>,
         cols: &mut impl Iterator<Item = Column<Advice>>,
+        limb_layout: LimbLayout,
     ) -> Self {
         let mut all_cols = BTreeMap::new();
         all_cols.insert(
@@ -402,7 +435,14 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
         all_cols.insert(
             EventTableCellType::U16,
             [0; U16_COLUMNS]
-                .map(|_| vec![U16Column::configure(meta, (l_0, l_active, l_active_last)).col])
+                .map(|_| {
+                    let column =
+                        U16Column::configure(meta, (l_0, l_active, l_active_last), limb_layout);
+
+                    std::iter::once(column.col)
+                        .chain(column.limbs.into_iter().flatten())
+                        .collect()
+                })
                 .into_iter()
                 .collect(),
         );
@@ -534,8 +574,12 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
     }
 
     pub(crate) fn alloc_u16_cell(&mut self) -> AllocatedU16Cell<F> {
+        // The limbs follow the u16 column in its group, see `U16Column`.
+        let cells = self.alloc_group(&EventTableCellType::U16);
+
         AllocatedU16Cell {
-            cell: self.alloc(&EventTableCellType::U16),
+            cell: cells[0],
+            limbs: (cells.len() == 3).then(|| [cells[1], cells[2]]),
         }
     }
 
@@ -723,6 +767,62 @@ impl<F: FieldExt> EventTableCellAllocator<F> {
         cell
     }
 
+    /// Allocate a memory table lookup which is a reading access if `is_write` is 0,
+    /// otherwise a writing access.
+    pub(crate) fn alloc_memory_table_lookup_read_or_write_cell_with_value(
+        &mut self,
+        name: &'static str,
+        constraint_builder: &mut ConstraintBuilder<F>,
+        eid: AllocatedU32StateCell<F>,
+        location_type: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + 'static,
+        offset: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + 'static,
+        is_i32: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + 'static,
+        is_write: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + 'static,
+        enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F> + 'static,
+    ) -> AllocatedMemoryTableLookupReadCell<F> {
+        let cells: Vec<_> = self
+            .alloc_group(&EventTableCellType::MTableLookup)
+            .into_iter()
+            .map(|x| AllocatedUnlimitedCell { cell: x })
+            .collect();
+
+        let cell = AllocatedMemoryTableLookupReadCell {
+            start_eid_cell: cells[0],
+            end_eid_cell: cells[1],
+            encode_cell: cells[2],
+            value_cell: cells[3],
+            start_eid_diff_cell: self.alloc_u32_state_cell(),
+            end_eid_diff_cell: self.alloc_u32_state_cell(),
+        };
+
+        constraint_builder.constraints.push((
+            name,
+            Box::new(move |meta| {
+                let enable = enable(meta);
+                let is_write = is_write(meta);
+                let is_read = constant_from!(1) - is_write.clone();
+                vec![
+                    (eid.expr(meta)
+                        - cell.start_eid_cell.expr(meta)
+                        - cell.start_eid_diff_cell.expr(meta)
+                        - constant_from!(1))
+                        * is_read.clone()
+                        * enable.clone(),
+                    (eid.expr(meta) + cell.end_eid_diff_cell.expr(meta)
+                        - cell.end_eid_cell.expr(meta))
+                        * is_read
+                        * enable.clone(),
+                    (cell.start_eid_cell.expr(meta) - eid.expr(meta)) * is_write * enable.clone(),
+                    (encode_memory_table_entry(offset(meta), location_type(meta), is_i32(meta))
+                        - cell.encode_cell.expr(meta))
+                        * enable,
+                ]
+            }),
+        ));
+
+        cell
+    }
+
     pub(crate) fn alloc_u32_cell(&mut self) -> AllocatedU32Cell<F> {
         self.free_u32_cells.pop().expect("no more free u32 cells")
     }
@@ -333,9 +333,8 @@ impl<F: FieldExt> EventTableChip<F> {
                     if op_config.0.is_context_output_op(&entry.eentry) {
                         context_out_index += 1;
                     }
-                    if op_config.0.is_external_host_call(&entry.eentry) {
-                        external_host_call_call_index += 1;
-                    }
+                    external_host_call_call_index +=
+                        op_config.0.external_host_call_ops(&entry.eentry);
 
                     rest_mops -= op_config.0.memory_writing_ops(&entry.eentry);
                     rest_call_ops -= op_config.0.call_ops();
@@ -1,6 +1,7 @@
 use self::allocator::*;
 use self::constraint_builder::ConstraintBuilder;
 use super::bit_table::BitTableConfig;
+use super::bit_table::LimbLayout;
 use super::cell::*;
 use super::external_host_call_table::ExternalHostCallTableConfig;
 use super::image_table::ImageTableConfig;
@@ -56,6 +57,7 @@ use halo2_proofs::plonk::Fixed;
 use halo2_proofs::plonk::VirtualCells;
 use specs::encode::instruction_table::encode_instruction_table_entry;
 use specs::etable::EventTableEntry;
+use specs::external_host_call_table::EXTERNAL_HOST_CALL_BUFFER_WORDS;
 use specs::itable::OpcodeClass;
 use specs::itable::OpcodeClassPlain;
 use std::collections::BTreeMap;
@@ -75,7 +77,7 @@ type AllocatedU32StateCell<F> = AllocatedCommonRangeCell<F>;
 pub(crate) const EVENT_TABLE_ENTRY_ROWS: i32 = 4;
 pub(crate) const OP_CAPABILITY: usize = 32;
 
-const FOREIGN_LOOKUP_CAPABILITY: usize = 6;
+const FOREIGN_LOOKUP_CAPABILITY: usize = 8;
 
 #[derive(Clone)]
 pub struct EventTableCommonConfig<F: FieldExt> {
@@ -105,7 +107,8 @@ pub struct EventTableCommonConfig<F: FieldExt> {
     pow_table_lookup_modulus_cell: AllocatedUnlimitedCell<F>,
     pow_table_lookup_power_cell: AllocatedUnlimitedCell<F>,
     bit_table_lookup_cells: AllocatedBitTableLookupCells<F>,
-    external_foreign_call_lookup_cell: AllocatedUnlimitedCell<F>,
+    external_foreign_call_lookup_cells:
+        [AllocatedUnlimitedCell<F>; EXTERNAL_HOST_CALL_BUFFER_WORDS],
 }
 
 pub(in crate::circuits::etable) trait EventTableOpcodeConfigBuilder<F: FieldExt> {
@@ -211,8 +214,8 @@ pub trait EventTableOpcodeConfig<F: FieldExt> {
     ) -> Option<Expression<F>> {
         None
     }
-    fn is_external_host_call(&self, _entry: &EventTableEntry) -> bool {
-        false
+    fn external_host_call_ops(&self, _entry: &EventTableEntry) -> u32 {
+        0
     }
 }
 
@@ -240,6 +243,7 @@ impl<F: FieldExt> EventTableConfig<F> {
         bit_table: &BitTableConfig<F>,
         external_host_call_table: &ExternalHostCallTableConfig<F>,
         foreign_table_configs: &BTreeMap<&'static str, Box<dyn ForeignTableConfig<F>>>,
+        limb_layout: LimbLayout,
     ) -> EventTableConfig<F> {
         let step_sel = meta.fixed_column();
 
@@ -250,6 +254,7 @@ impl<F: FieldExt> EventTableConfig<F> {
             rtable,
             mtable,
             cols,
+            limb_layout,
         );
 
         let ops = [0; OP_CAPABILITY].map(|_| allocator.alloc_bit_cell());
@@ -298,7 +303,8 @@ impl<F: FieldExt> EventTableConfig<F> {
         let is_returned_cell = allocator.alloc_bit_cell();
         let pow_table_lookup_modulus_cell = allocator.alloc_unlimited_cell();
         let pow_table_lookup_power_cell = allocator.alloc_unlimited_cell();
-        let external_foreign_call_lookup_cell = allocator.alloc_unlimited_cell();
+        let external_foreign_call_lookup_cells =
+            [0; EXTERNAL_HOST_CALL_BUFFER_WORDS].map(|_| allocator.alloc_unlimited_cell());
         let bit_table_lookup_cells = allocator.alloc_bit_table_lookup_cells();
 
         let mut foreign_table_reserved_lookup_cells = [(); FOREIGN_LOOKUP_CAPABILITY]
@@ -329,7 +335,7 @@ impl<F: FieldExt> EventTableConfig<F> {
             pow_table_lookup_modulus_cell,
             pow_table_lookup_power_cell,
             bit_table_lookup_cells,
-            external_foreign_call_lookup_cell,
+            external_foreign_call_lookup_cells,
         };
 
         let mut op_bitmaps: BTreeMap<OpcodeClassPlain, usize> = BTreeMap::new();
@@ -657,15 +663,18 @@ impl<F: FieldExt> EventTableConfig<F> {
             |meta| fixed_curr!(meta, step_sel),
         );
 
-        external_host_call_table.configure_in_table(
-            meta,
-            "c8g. external_foreign_call_lookup in foreign table",
-            |meta| {
-                vec![
-                    external_foreign_call_lookup_cell.curr_expr(meta) * fixed_curr!(meta, step_sel),
-                ]
-            },
-        );
+        for external_foreign_call_lookup_cell in external_foreign_call_lookup_cells {
+            external_host_call_table.configure_in_table(
+                meta,
+                "c8g. external_foreign_call_lookup in foreign table",
+                |meta| {
+                    vec![
+                        external_foreign_call_lookup_cell.curr_expr(meta)
+                            * fixed_curr!(meta, step_sel),
+                    ]
+                },
+            );
+        }
 
         bit_table.configure_in_table(meta, "c8f: bit_table_lookup in bit_table", |meta| {
             (
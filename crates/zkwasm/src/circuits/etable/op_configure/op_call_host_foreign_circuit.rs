@@ -4,6 +4,8 @@ use crate::circuits::etable::ConstraintBuilder;
 use crate::circuits::etable::EventTableCommonConfig;
 use crate::circuits::etable::EventTableOpcodeConfig;
 use crate::circuits::etable::EventTableOpcodeConfigBuilder;
+use crate::circuits::mtable::utils::WASM_BLOCKS_PER_PAGE;
+use crate::circuits::mtable::utils::WASM_BLOCK_BYTE_SIZE;
 use crate::circuits::utils::step_status::StepStatus;
 use crate::circuits::utils::table_entry::EventTableEntryWithMemoryInfo;
 use crate::circuits::utils::Context;
@@ -16,6 +18,7 @@ use num_bigint::BigUint;
 use specs::encode::opcode::encode_call_host;
 use specs::external_host_call_table::encode::encode_host_call_entry;
 use specs::external_host_call_table::ExternalHostCallSignature;
+use specs::external_host_call_table::EXTERNAL_HOST_CALL_BUFFER_WORDS;
 use specs::mtable::LocationType;
 use specs::step::StepInfo;
 
@@ -23,10 +26,21 @@ pub struct ExternalCallHostCircuitConfig<F: FieldExt> {
     op: AllocatedCommonRangeCell<F>,
     value_is_ret: AllocatedBitCell<F>,
     value_is_not_ret: AllocatedBitCell<F>,
+    is_buffer: AllocatedBitCell<F>,
 
-    external_foreign_call_lookup_cell: AllocatedUnlimitedCell<F>,
+    // word_enabled[i] is 1 iff the i-th u64 word of the buffer is transferred
+    word_enabled: [AllocatedBitCell<F>; EXTERNAL_HOST_CALL_BUFFER_WORDS],
+    buffer_block_index: AllocatedU32Cell<F>,
+    address_within_allocated_pages_helper: AllocatedCommonRangeCell<F>,
+
+    external_foreign_call_lookup_cells:
+        [AllocatedUnlimitedCell<F>; EXTERNAL_HOST_CALL_BUFFER_WORDS],
+    // the argument for `Argument`, the length for buffer calls
     memory_table_lookup_stack_read: AllocatedMemoryTableLookupReadCell<F>,
+    memory_table_lookup_stack_read_address: AllocatedMemoryTableLookupReadCell<F>,
     memory_table_lookup_stack_write: AllocatedMemoryTableLookupWriteCell<F>,
+    memory_table_lookup_heap:
+        [AllocatedMemoryTableLookupReadCell<F>; EXTERNAL_HOST_CALL_BUFFER_WORDS],
 }
 
 pub struct ExternalCallHostCircuitConfigBuilder {}
@@ -40,9 +54,13 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
         let op = allocator.alloc_common_range_cell();
         let value_is_ret = allocator.alloc_bit_cell();
         let value_is_not_ret = allocator.alloc_bit_cell();
+        let is_buffer = allocator.alloc_bit_cell();
+        let word_enabled = [0; EXTERNAL_HOST_CALL_BUFFER_WORDS].map(|_| allocator.alloc_bit_cell());
+        let buffer_block_index = allocator.alloc_u32_cell();
+        let address_within_allocated_pages_helper = allocator.alloc_common_range_cell();
 
         let index = common_config.external_host_call_index_cell;
-        let external_foreign_call_lookup_cell = common_config.external_foreign_call_lookup_cell;
+        let external_foreign_call_lookup_cells = common_config.external_foreign_call_lookup_cells;
 
         constraint_builder.push(
             "op_call_host is_ret or not",
@@ -51,10 +69,40 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
             }),
         );
 
+        constraint_builder.push(
+            "op_call_host buffer words",
+            Box::new(move |meta| {
+                let mut constraints =
+                    vec![(constant_from!(1) - is_buffer.expr(meta)) * word_enabled[0].expr(meta)];
+
+                for i in 1..EXTERNAL_HOST_CALL_BUFFER_WORDS {
+                    constraints.push(
+                        word_enabled[i].expr(meta)
+                            * (constant_from!(1) - word_enabled[i - 1].expr(meta)),
+                    );
+                }
+
+                constraints
+            }),
+        );
+
+        let words = move |meta: &mut VirtualCells<'_, F>| {
+            word_enabled
+                .iter()
+                .map(|cell| cell.expr(meta))
+                .reduce(|acc, x| acc + x)
+                .unwrap()
+        };
+
         constraint_builder.push(
             "external host call index change",
             Box::new(move |meta| {
-                vec![index.next_expr(meta) - index.curr_expr(meta) - constant_from!(1)]
+                vec![
+                    index.next_expr(meta)
+                        - index.curr_expr(meta)
+                        - (constant_from!(1) - is_buffer.expr(meta))
+                        - words(meta),
+                ]
             }),
         );
 
@@ -68,8 +116,21 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
                 eid,
                 move |____| constant_from!(LocationType::Stack),
                 move |meta| sp.expr(meta) + constant_from!(1),
-                move |____| constant_from!(0),
-                move |meta| value_is_not_ret.expr(meta),
+                move |meta| is_buffer.expr(meta),
+                move |meta| {
+                    value_is_not_ret.expr(meta) + value_is_ret.expr(meta) * is_buffer.expr(meta)
+                },
+            );
+
+        let memory_table_lookup_stack_read_address = allocator
+            .alloc_memory_table_lookup_read_cell_with_value(
+                "op_call_host read buffer address",
+                constraint_builder,
+                eid,
+                move |____| constant_from!(LocationType::Stack),
+                move |meta| sp.expr(meta) + constant_from!(2),
+                move |____| constant_from!(1),
+                move |meta| is_buffer.expr(meta),
             );
 
         let memory_table_lookup_stack_write = allocator
@@ -80,9 +141,63 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
                 move |____| constant_from!(LocationType::Stack),
                 move |meta| sp.expr(meta),
                 move |____| constant_from!(0),
-                move |meta| value_is_ret.expr(meta),
+                move |meta| value_is_ret.expr(meta) * (constant_from!(1) - is_buffer.expr(meta)),
             );
 
+        let memory_table_lookup_heap = [0; EXTERNAL_HOST_CALL_BUFFER_WORDS]
+            .into_iter()
+            .enumerate()
+            .map(|(i, _)| {
+                let word_enabled = word_enabled[i];
+
+                allocator.alloc_memory_table_lookup_read_or_write_cell_with_value(
+                    "op_call_host buffer access",
+                    constraint_builder,
+                    eid,
+                    move |____| constant_from!(LocationType::Heap),
+                    move |meta| buffer_block_index.expr(meta) + constant_from!(i),
+                    move |____| constant_from!(0),
+                    move |meta| value_is_ret.expr(meta),
+                    move |meta| word_enabled.expr(meta),
+                )
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let buffer_len = memory_table_lookup_stack_read.value_cell;
+        let buffer_address = memory_table_lookup_stack_read_address.value_cell;
+
+        constraint_builder.push(
+            "op_call_host buffer layout",
+            Box::new(move |meta| {
+                vec![
+                    is_buffer.expr(meta)
+                        * (buffer_len.expr(meta)
+                            - words(meta) * constant_from!(WASM_BLOCK_BYTE_SIZE)),
+                    is_buffer.expr(meta)
+                        * (buffer_address.expr(meta)
+                            - buffer_block_index.expr(meta) * constant_from!(WASM_BLOCK_BYTE_SIZE)),
+                ]
+            }),
+        );
+
+        let current_memory_page_size = common_config.mpages_cell;
+
+        constraint_builder.push(
+            "op_call_host buffer allocated address",
+            Box::new(move |meta| {
+                vec![
+                    is_buffer.expr(meta)
+                        * (buffer_block_index.expr(meta)
+                            + words(meta)
+                            + address_within_allocated_pages_helper.expr(meta)
+                            - current_memory_page_size.expr(meta)
+                                * constant_from!(WASM_BLOCKS_PER_PAGE)),
+                ]
+            }),
+        );
+
         constraint_builder.push(
             "external host call lookup",
             Box::new(move |meta| {
@@ -91,10 +206,31 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
                     + value_is_ret.expr(meta)
                         * memory_table_lookup_stack_write.value_cell.expr(meta);
 
-                vec![
-                    external_foreign_call_lookup_cell.expr(meta)
-                        - encode_host_call_entry(index.expr(meta), op.expr(meta), operand),
-                ]
+                let mut constraints = vec![
+                    external_foreign_call_lookup_cells[0].expr(meta)
+                        - (constant_from!(1) - is_buffer.expr(meta))
+                            * encode_host_call_entry(index.expr(meta), op.expr(meta), operand)
+                        - word_enabled[0].expr(meta)
+                            * encode_host_call_entry(
+                                index.expr(meta),
+                                op.expr(meta),
+                                memory_table_lookup_heap[0].value_cell.expr(meta),
+                            ),
+                ];
+
+                for i in 1..EXTERNAL_HOST_CALL_BUFFER_WORDS {
+                    constraints.push(
+                        external_foreign_call_lookup_cells[i].expr(meta)
+                            - word_enabled[i].expr(meta)
+                                * encode_host_call_entry(
+                                    index.expr(meta) + constant_from!(i),
+                                    op.expr(meta),
+                                    memory_table_lookup_heap[i].value_cell.expr(meta),
+                                ),
+                    );
+                }
+
+                constraints
             }),
         );
 
@@ -102,16 +238,36 @@ impl<F: FieldExt> EventTableOpcodeConfigBuilder<F> for ExternalCallHostCircuitCo
             op,
             value_is_ret,
             value_is_not_ret,
-            external_foreign_call_lookup_cell,
+            is_buffer,
+            word_enabled,
+            buffer_block_index,
+            address_within_allocated_pages_helper,
+            external_foreign_call_lookup_cells,
             memory_table_lookup_stack_read,
+            memory_table_lookup_stack_read_address,
             memory_table_lookup_stack_write,
+            memory_table_lookup_heap,
         })
     }
 }
 
+impl<F: FieldExt> ExternalCallHostCircuitConfig<F> {
+    fn words(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
+        self.word_enabled
+            .iter()
+            .map(|cell| cell.expr(meta))
+            .reduce(|acc, x| acc + x)
+            .unwrap()
+    }
+}
+
 impl<F: FieldExt> EventTableOpcodeConfig<F> for ExternalCallHostCircuitConfig<F> {
     fn opcode(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
-        encode_call_host(self.op.expr(meta), self.value_is_ret.expr(meta))
+        encode_call_host(
+            self.op.expr(meta),
+            self.value_is_ret.expr(meta),
+            self.is_buffer.expr(meta),
+        )
     }
 
     fn assign(
@@ -125,7 +281,7 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ExternalCallHostCircuitConfig<F>
                 self.op.assign(ctx, F::from(*op as u64))?;
                 self.value_is_ret.assign_bool(ctx, sig.is_ret())?;
                 self.value_is_not_ret.assign_bool(ctx, !sig.is_ret())?;
-                self.external_foreign_call_lookup_cell.assign_bn(
+                self.external_foreign_call_lookup_cells[0].assign_bn(
                     ctx,
                     &encode_host_call_entry(
                         BigUint::from(step.current.external_host_call_call_index),
@@ -158,6 +314,89 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ExternalCallHostCircuitConfig<F>
                             value.unwrap(),
                         )?;
                     }
+                    ExternalHostCallSignature::ArgumentBuffer
+                    | ExternalHostCallSignature::ReturnBuffer => unreachable!(),
+                }
+
+                Ok(())
+            }
+            StepInfo::ExternalHostCallBuffer {
+                op,
+                address,
+                len,
+                values,
+                sig,
+            } => {
+                self.op.assign(ctx, F::from(*op as u64))?;
+                self.value_is_ret.assign_bool(ctx, sig.is_ret())?;
+                self.value_is_not_ret.assign_bool(ctx, !sig.is_ret())?;
+                self.is_buffer.assign_bool(ctx, true)?;
+
+                let block_index = address / WASM_BLOCK_BYTE_SIZE;
+                self.buffer_block_index.assign(ctx, block_index)?;
+                self.address_within_allocated_pages_helper.assign_u32(
+                    ctx,
+                    step.current.allocated_memory_pages * WASM_BLOCKS_PER_PAGE
+                        - (block_index + values.len() as u32),
+                )?;
+
+                self.memory_table_lookup_stack_read.assign(
+                    ctx,
+                    entry.memory_rw_entires[0].start_eid,
+                    step.current.eid,
+                    entry.memory_rw_entires[0].end_eid,
+                    step.current.sp + 1,
+                    LocationType::Stack,
+                    true,
+                    *len as u64,
+                )?;
+
+                self.memory_table_lookup_stack_read_address.assign(
+                    ctx,
+                    entry.memory_rw_entires[1].start_eid,
+                    step.current.eid,
+                    entry.memory_rw_entires[1].end_eid,
+                    step.current.sp + 2,
+                    LocationType::Stack,
+                    true,
+                    *address as u64,
+                )?;
+
+                for (i, value) in values.iter().enumerate() {
+                    self.word_enabled[i].assign_bool(ctx, true)?;
+                    self.external_foreign_call_lookup_cells[i].assign_bn(
+                        ctx,
+                        &encode_host_call_entry(
+                            BigUint::from(step.current.external_host_call_call_index + i as u32),
+                            BigUint::from(*op as u64),
+                            BigUint::from(*value),
+                        ),
+                    )?;
+
+                    let memory_rw_entry = &entry.memory_rw_entires[2 + i];
+
+                    if sig.is_ret() {
+                        self.memory_table_lookup_heap[i].assign_write(
+                            ctx,
+                            step.current.eid,
+                            memory_rw_entry.end_eid,
+                            block_index + i as u32,
+                            LocationType::Heap,
+                            false,
+                            *value,
+                        )?;
+                    } else {
+                        self.memory_table_lookup_heap[i].assign(
+                            ctx,
+                            memory_rw_entry.start_eid,
+                            step.current.eid,
+                            memory_rw_entry.end_eid,
+                            block_index + i as u32,
+                            LocationType::Heap,
+                            false,
+                            *value,
+                        )?;
+                    }
                 }
 
                 Ok(())
@@ -168,7 +407,11 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ExternalCallHostCircuitConfig<F>
     }
 
     fn sp_diff(&self, meta: &mut VirtualCells<'_, F>) -> Option<Expression<F>> {
-        Some(constant_from!(1) - self.value_is_ret.expr(meta) * constant_from!(2))
+        Some(
+            (constant_from!(1) - self.is_buffer.expr(meta))
+                * (constant_from!(1) - self.value_is_ret.expr(meta) * constant_from!(2))
+                + self.is_buffer.expr(meta) * constant_from!(2),
+        )
     }
 
     fn memory_writing_ops(&self, entry: &specs::etable::EventTableEntry) -> u32 {
@@ -177,23 +420,37 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ExternalCallHostCircuitConfig<F>
                 ExternalHostCallSignature::Return => 1u32,
                 _ => 0,
             },
+            StepInfo::ExternalHostCallBuffer { sig, values, .. } => {
+                if sig.is_ret() {
+                    values.len() as u32
+                } else {
+                    0
+                }
+            }
             _ => unreachable!(),
         }
     }
 
     fn mops(&self, meta: &mut VirtualCells<'_, F>) -> Option<Expression<F>> {
-        Some(self.value_is_ret.curr_expr(meta))
+        Some(
+            self.value_is_ret.curr_expr(meta)
+                * (constant_from!(1) - self.is_buffer.expr(meta) + self.words(meta)),
+        )
     }
 
-    fn is_external_host_call(&self, _entry: &specs::etable::EventTableEntry) -> bool {
-        true
+    fn external_host_call_ops(&self, entry: &specs::etable::EventTableEntry) -> u32 {
+        match &entry.step_info {
+            StepInfo::ExternalHostCall { .. } => 1,
+            StepInfo::ExternalHostCallBuffer { values, .. } => values.len() as u32,
+            _ => unreachable!(),
+        }
     }
 
     fn external_host_call_index_increase(
         &self,
-        _meta: &mut VirtualCells<'_, F>,
+        meta: &mut VirtualCells<'_, F>,
         _common_config: &EventTableCommonConfig<F>,
     ) -> Option<Expression<F>> {
-        Some(constant_from!(1))
+        Some(constant_from!(1) - self.is_buffer.expr(meta) + self.words(meta))
     }
 }
@@ -7,10 +7,10 @@ use halo2_proofs::plonk::Fixed;
 use halo2_proofs::plonk::VirtualCells;
 use std::marker::PhantomData;
 
+use super::utils::image_table::init_memory_entries_offset;
 use super::utils::image_table::GLOBAL_CAPABILITY;
-use super::utils::image_table::INIT_MEMORY_ENTRIES_OFFSET;
 use super::utils::image_table::STACK_CAPABILITY;
-use super::zkwasm_circuit::RESERVE_ROWS;
+use super::zkwasm_circuit::reserve_rows;
 
 mod assign;
 mod configure;
@@ -25,11 +25,10 @@ pub const PAGE_ENTRIES: u32 = PAGE_SIZE / 8;
 /// circuit size - reserved rows for blind - init memory entries base offset
 ///   - stack entries - global entries
 pub fn compute_maximal_pages(k: u32) -> u32 {
-    let rows: u32 = ((1usize << k)
-        - RESERVE_ROWS
-        - INIT_MEMORY_ENTRIES_OFFSET
-        - STACK_CAPABILITY
-        - GLOBAL_CAPABILITY)
+    let rows: u32 = (1usize << k)
+        .saturating_sub(
+            reserve_rows(k) + init_memory_entries_offset(k) + STACK_CAPABILITY + GLOBAL_CAPABILITY,
+        )
         .try_into()
         .unwrap();
 
@@ -10,14 +10,20 @@ use num_bigint::BigUint;
 use specs::slice::Slice;
 use std::marker::PhantomData;
 
+use self::bit_table::LimbLayout;
+use self::config::is_supported_k;
+use self::config::MAX_K;
+use self::config::MIN_SMALL_K;
+use self::diagnostics::ConstraintFailure;
+use self::diagnostics::MockTestError;
 use self::etable::EVENT_TABLE_ENTRY_ROWS;
 use self::image_table::compute_maximal_pages;
-use self::zkwasm_circuit::RESERVE_ROWS;
+use self::zkwasm_circuit::reserve_rows;
 
 pub(crate) mod cell;
 pub(crate) mod etable;
 
-mod bit_table;
+pub(crate) mod bit_table;
 mod external_host_call_table;
 mod mtable;
 mod traits;
@@ -31,6 +37,7 @@ pub mod post_image_table;
 pub mod post_image_table;
 
 pub mod config;
+pub mod diagnostics;
 pub mod image_table;
 pub mod jtable;
 pub mod rtable;
@@ -41,17 +48,27 @@ pub type CompilationTable = specs::CompilationTable;
 pub type ExecutionTable = specs::ExecutionTable;
 
 pub(crate) fn compute_slice_capability(k: u32) -> u32 {
-    ((1 << k) - RESERVE_ROWS as u32 - 1024) / EVENT_TABLE_ENTRY_ROWS as u32
+    ((1 << k) - reserve_rows(k) as u32 - 1024) / EVENT_TABLE_ENTRY_ROWS as u32
 }
 
-pub struct OngoingCircuit<F: FieldExt> {
+/// `LIMB_BITS` is the limb size of the bit table, it is fixed at configure time so that it must
+/// agree with `LimbLayout::for_k(k)`.
+pub struct OngoingCircuit<F: FieldExt, const LIMB_BITS: usize = 8> {
     pub k: u32,
     pub slice: Slice,
     _data: PhantomData<F>,
 }
 
-impl<F: FieldExt> OngoingCircuit<F> {
+impl<F: FieldExt, const LIMB_BITS: usize> OngoingCircuit<F, LIMB_BITS> {
     pub fn new(k: u32, slice: Slice) -> Result<Self, BuildingCircuitError> {
+        if !is_supported_k(k) {
+            return Err(BuildingCircuitError::UnsupportedK(k, MIN_SMALL_K, MAX_K));
+        }
+
+        if LimbLayout::for_k(k).limb_bits != LIMB_BITS {
+            return Err(BuildingCircuitError::LimbLayoutMismatch(LIMB_BITS, k));
+        }
+
         {
             // entries is empty when called by without_witness
             let allocated_memory_pages = slice
@@ -92,14 +109,22 @@ impl<F: FieldExt> OngoingCircuit<F> {
     }
 }
 
-pub struct LastSliceCircuit<F: FieldExt> {
+pub struct LastSliceCircuit<F: FieldExt, const LIMB_BITS: usize = 8> {
     pub k: u32,
     pub slice: Slice,
     _data: PhantomData<F>,
 }
 
-impl<F: FieldExt> LastSliceCircuit<F> {
+impl<F: FieldExt, const LIMB_BITS: usize> LastSliceCircuit<F, LIMB_BITS> {
     pub fn new(k: u32, slice: Slice) -> Result<Self, BuildingCircuitError> {
+        if !is_supported_k(k) {
+            return Err(BuildingCircuitError::UnsupportedK(k, MIN_SMALL_K, MAX_K));
+        }
+
+        if LimbLayout::for_k(k).limb_bits != LIMB_BITS {
+            return Err(BuildingCircuitError::LimbLayoutMismatch(LIMB_BITS, k));
+        }
+
         {
             // entries is empty when called by without_witness
             let allocated_memory_pages = slice
@@ -139,35 +164,89 @@ impl<F: FieldExt> LastSliceCircuit<F> {
         })
     }
 }
+/// Circuits below K=18 split u32 into u4 limbs, see `LimbLayout`.
 pub enum ZkWasmCircuit<F: FieldExt> {
     Ongoing(OngoingCircuit<F>),
     LastSliceCircuit(LastSliceCircuit<F>),
+    SmallOngoing(OngoingCircuit<F, 4>),
+    SmallLastSliceCircuit(LastSliceCircuit<F, 4>),
 }
 
 impl<F: FieldExt> ZkWasmCircuit<F> {
     pub fn new(k: u32, slice: Slice) -> Result<Self, BuildingCircuitError> {
+        let small = LimbLayout::for_k(k) == LimbLayout::U4;
+
         if slice.is_last_slice {
-            Ok(ZkWasmCircuit::LastSliceCircuit(LastSliceCircuit::new(
-                k, slice,
-            )?))
+            if small {
+                Ok(ZkWasmCircuit::SmallLastSliceCircuit(LastSliceCircuit::new(
+                    k, slice,
+                )?))
+            } else {
+                Ok(ZkWasmCircuit::LastSliceCircuit(LastSliceCircuit::new(
+                    k, slice,
+                )?))
+            }
+        } else if small {
+            Ok(ZkWasmCircuit::SmallOngoing(OngoingCircuit::new(k, slice)?))
         } else {
             Ok(ZkWasmCircuit::Ongoing(OngoingCircuit::new(k, slice)?))
         }
     }
 
-    pub fn mock_test(&self, instances: Vec<F>) -> anyhow::Result<()> {
+    pub fn k(&self) -> u32 {
         match self {
-            ZkWasmCircuit::Ongoing(circuit) => {
-                let prover = MockProver::run(circuit.k, circuit, vec![instances])?;
-                assert_eq!(prover.verify(), Ok(()));
-            }
+            ZkWasmCircuit::Ongoing(circuit) => circuit.k,
+            ZkWasmCircuit::LastSliceCircuit(circuit) => circuit.k,
+            ZkWasmCircuit::SmallOngoing(circuit) => circuit.k,
+            ZkWasmCircuit::SmallLastSliceCircuit(circuit) => circuit.k,
+        }
+    }
+
+    pub fn slice(&self) -> &Slice {
+        match self {
+            ZkWasmCircuit::Ongoing(circuit) => &circuit.slice,
+            ZkWasmCircuit::LastSliceCircuit(circuit) => &circuit.slice,
+            ZkWasmCircuit::SmallOngoing(circuit) => &circuit.slice,
+            ZkWasmCircuit::SmallLastSliceCircuit(circuit) => &circuit.slice,
+        }
+    }
+
+    /// Run the mock prover and map each failure back to the trace.
+    pub fn mock_verify(&self, instances: Vec<F>) -> anyhow::Result<Vec<ConstraintFailure>> {
+        let k = self.k();
+        let instances = vec![instances];
+
+        let result = match self {
+            ZkWasmCircuit::Ongoing(circuit) => MockProver::run(k, circuit, instances)?.verify(),
             ZkWasmCircuit::LastSliceCircuit(circuit) => {
-                let prover = MockProver::run(circuit.k, circuit, vec![instances])?;
-                assert_eq!(prover.verify(), Ok(()));
+                MockProver::run(k, circuit, instances)?.verify()
             }
-        }
+            ZkWasmCircuit::SmallOngoing(circuit) => {
+                MockProver::run(k, circuit, instances)?.verify()
+            }
+            ZkWasmCircuit::SmallLastSliceCircuit(circuit) => {
+                MockProver::run(k, circuit, instances)?.verify()
+            }
+        };
 
-        Ok(())
+        Ok(match result {
+            Ok(()) => vec![],
+            Err(failures) => diagnostics::diagnose(k, self.slice(), &failures),
+        })
+    }
+
+    pub fn mock_test(&self, instances: Vec<F>) -> anyhow::Result<()> {
+        let failures = self.mock_verify(instances)?;
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(MockTestError {
+                slice: None,
+                failures,
+            }
+            .into())
+        }
     }
 }
 
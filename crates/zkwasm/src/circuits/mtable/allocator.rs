@@ -9,6 +9,7 @@ use halo2_proofs::plonk::Expression;
 use halo2_proofs::plonk::Fixed;
 use halo2_proofs::plonk::VirtualCells;
 
+use crate::circuits::bit_table::LimbLayout;
 use crate::circuits::cell::*;
 use crate::circuits::rtable::RangeTableConfig;
 use crate::circuits::utils::bit::BitColumn;
@@ -83,6 +84,8 @@ const U64_CELLS: usize = 1;
 #[derive(Debug, Clone)]
 pub(super) struct MemoryTableCellAllocator<F: FieldExt> {
     all_cols: BTreeMap<MemoryTableCellType, Vec<Column<Advice>>>,
+    /// Limbs of the u16 columns, only with the u4 limbs layout, see `U16Column`.
+    u16_limbs: Vec<[Column<Advice>; 2]>,
     free_cells: BTreeMap<MemoryTableCellType, (usize, u32)>,
     free_u32_cells: Vec<AllocatedU32Cell<F>>,
     free_u64_cells: Vec<AllocatedU64Cell<F>>,
@@ -155,6 +158,7 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
         (l_0, l_active, l_active_last): (Column<Fixed>, Column<Fixed>, Column<Fixed>),
         rtable: &RangeTableConfig<F>,
         cols: &mut impl Iterator<Item = Column<Advice>>,
+        limb_layout: LimbLayout,
     ) -> Self {
         let mut allocator = Self::_new(
             meta,
@@ -162,6 +166,7 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
             (l_0, l_active, l_active_last),
             rtable,
             cols,
+            limb_layout,
         );
         for _ in 0..U32_CELLS {
             let cell = allocator.prepare_alloc_u32_cell();
@@ -180,6 +185,7 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
         (l_0, l_active, l_active_last): (Column<Fixed>, Column<Fixed>, Column<Fixed>),
         rtable: &RangeTableConfig<F>,
         cols: &mut impl Iterator<Item = Column<Advice>>,
+        limb_layout: LimbLayout,
     ) -> Self {
         let mut all_cols = BTreeMap::new();
         all_cols.insert(
@@ -189,12 +195,11 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
                 .into_iter()
                 .collect(),
         );
+        let u16_columns = [0; U16_COLUMNS]
+            .map(|_| U16Column::configure(meta, (l_0, l_active, l_active_last), limb_layout));
         all_cols.insert(
             MemoryTableCellType::U16,
-            [0; U16_COLUMNS]
-                .map(|_| U16Column::configure(meta, (l_0, l_active, l_active_last)).col)
-                .into_iter()
-                .collect(),
+            u16_columns.iter().map(|column| column.col).collect(),
         );
         all_cols.insert(
             MemoryTableCellType::CommonRange,
@@ -214,6 +219,10 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
         );
         Self {
             all_cols,
+            u16_limbs: u16_columns
+                .iter()
+                .filter_map(|column| column.limbs)
+                .collect(),
             free_cells: BTreeMap::from_iter(
                 vec![
                     (MemoryTableCellType::Bit, (0, 0)),
@@ -269,8 +278,18 @@ impl<F: FieldExt> MemoryTableCellAllocator<F> {
     }
 
     pub(super) fn alloc_u16_cell(&mut self) -> AllocatedU16Cell<F> {
+        let index = self.free_cells.get(&MemoryTableCellType::U16).unwrap().0;
+        let cell = self.alloc(&MemoryTableCellType::U16);
+
         AllocatedU16Cell {
-            cell: self.alloc(&MemoryTableCellType::U16),
+            cell,
+            limbs: self.u16_limbs.get(index).map(|limbs| {
+                limbs.map(|col| AllocatedCell {
+                    col,
+                    rot: cell.rot,
+                    _mark: PhantomData,
+                })
+            }),
         }
     }
 
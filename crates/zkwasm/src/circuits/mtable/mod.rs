@@ -1,4 +1,5 @@
 use self::allocator::*;
+use super::bit_table::LimbLayout;
 use super::cell::*;
 use super::image_table::ImageTableConfig;
 use super::rtable::RangeTableConfig;
@@ -73,6 +74,7 @@ impl<F: FieldExt> MemoryTableConfig<F> {
         cols: &mut (impl Iterator<Item = Column<Advice>> + Clone),
         rtable: &RangeTableConfig<F>,
         image_table: &ImageTableConfig<F>,
+        limb_layout: LimbLayout,
     ) -> Self {
         let entry_sel = meta.fixed_column();
 
@@ -82,6 +84,7 @@ impl<F: FieldExt> MemoryTableConfig<F> {
             (l_0, l_active, l_active_last),
             rtable,
             cols,
+            limb_layout,
         );
         allocator.enable_equality(meta, &MemoryTableCellType::CommonRange);
 
@@ -26,7 +26,6 @@ use crate::circuits::image_table::ImageTableConfig;
 use crate::circuits::jtable::JumpTableConfig;
 use crate::circuits::mtable::MemoryTableConfig;
 use crate::circuits::utils::bn_to_field;
-use crate::circuits::utils::image_table::image_table_offset_to_memory_location;
 use crate::circuits::utils::image_table::ImageTableAssigner;
 use crate::circuits::utils::image_table::ImageTableLayouter;
 use crate::circuits::utils::image_table::GLOBAL_CAPABILITY;
@@ -345,9 +344,9 @@ impl<F: FieldExt> PostImageTableChip<F> {
                             .map(|_| {
                                 let v = rest_memory_writing_ops;
 
-                                if memory_finalized_set
-                                    .contains(&image_table_offset_to_memory_location(offset))
-                                {
+                                if memory_finalized_set.contains(
+                                    &image_table_assigner.offset_to_memory_location(offset),
+                                ) {
                                     rest_memory_writing_ops = rest_memory_writing_ops - F::one();
                                 }
 
@@ -382,7 +381,8 @@ impl<F: FieldExt> PostImageTableChip<F> {
                                     || Ok(rest_memory_writing_ops),
                                 )?;
 
-                                let position = image_table_offset_to_memory_location(offset);
+                                let position =
+                                    image_table_assigner.offset_to_memory_location(offset);
 
                                 if memory_finalized_set.contains(&position) {
                                     region.assign_advice(
@@ -2,6 +2,7 @@ use super::config::common_range;
 use super::config::POW_TABLE_POWER_START;
 use super::utils::bn_to_field;
 use crate::circuits::bit_table::BitTableOp;
+use crate::circuits::bit_table::LimbLayout;
 use crate::constant_from;
 use halo2_proofs::arithmetic::FieldExt;
 use halo2_proofs::circuit::Layouter;
@@ -19,6 +20,8 @@ use strum::IntoEnumIterator;
 const POW_OP: u64 = 4;
 
 /*
+ * left and right are u4 instead of u8 below K=18, see `bit_table::LimbLayout`.
+ *
  * | Comment   | Op  | left(u8) | right                       | result   |
  * | --------- | --- | -------- | --------------------------- | -------- |
  * | Bit(And)  | 0   | 0        | 0                           | 0        |
@@ -134,7 +137,12 @@ impl<F: FieldExt> RangeTableChip<F> {
         RangeTableChip { config }
     }
 
-    pub fn init(&self, layouter: impl Layouter<F>, k: u32) -> Result<(), Error> {
+    pub(crate) fn init(
+        &self,
+        layouter: impl Layouter<F>,
+        k: u32,
+        limb_layout: LimbLayout,
+    ) -> Result<(), Error> {
         layouter.assign_table(
             || "common range table",
             |table| {
@@ -157,8 +165,8 @@ impl<F: FieldExt> RangeTableChip<F> {
                     let mut offset = 0;
 
                     for op in BitOp::iter() {
-                        for left in 0..1u16 << 8 {
-                            for right in 0u16..1 << 8 {
+                        for left in 0..1u16 << limb_layout.limb_bits {
+                            for right in 0u16..1 << limb_layout.limb_bits {
                                 table.assign_cell(
                                     || "range table",
                                     self.config.op_table.op,
@@ -192,7 +200,7 @@ impl<F: FieldExt> RangeTableChip<F> {
                         }
                     }
 
-                    for left in 0..1u16 << 8 {
+                    for left in 0..1u16 << limb_layout.limb_bits {
                         table.assign_cell(
                             || "range table",
                             self.config.op_table.op,
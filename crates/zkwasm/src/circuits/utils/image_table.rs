@@ -21,13 +21,26 @@ use specs::slice::Slice;
 use specs::state::InitializationState;
 use wasmi::DEFAULT_VALUE_STACK_LIMIT;
 
+use crate::circuits::config::MIN_K;
 use crate::circuits::image_table::compute_maximal_pages;
 use crate::circuits::image_table::PAGE_ENTRIES;
 use crate::circuits::utils::bn_to_field;
 
 pub const STACK_CAPABILITY: usize = DEFAULT_VALUE_STACK_LIMIT;
 pub const GLOBAL_CAPABILITY: usize = DEFAULT_VALUE_STACK_LIMIT;
-pub const INIT_MEMORY_ENTRIES_OFFSET: usize = 40960;
+
+/// The offset of init memory entries in the image table, instructions and br table entries of
+/// the image are placed before it. It is fixed for K >= MIN_K and shrinks with smaller
+/// circuits.
+pub fn init_memory_entries_offset(k: u32) -> usize {
+    const INIT_MEMORY_ENTRIES_OFFSET_AT_MIN_K: usize = 40960;
+
+    if k >= MIN_K {
+        INIT_MEMORY_ENTRIES_OFFSET_AT_MIN_K
+    } else {
+        INIT_MEMORY_ENTRIES_OFFSET_AT_MIN_K >> (MIN_K - k)
+    }
+}
 
 pub(crate) struct InitMemoryLayouter {
     pub(crate) pages: u32,
@@ -55,24 +68,14 @@ impl InitMemoryLayouter {
 
         (LocationType::Heap, offset as u32)
     }
-}
-
-pub fn image_table_offset_to_memory_location(offset: usize) -> (LocationType, u32) {
-    // Minus one for default lookup entry.
-    let mut offset = offset - INIT_MEMORY_ENTRIES_OFFSET - 1;
-
-    if offset < STACK_CAPABILITY {
-        return (LocationType::Stack, offset as u32);
-    }
-
-    offset -= STACK_CAPABILITY;
 
-    if offset < GLOBAL_CAPABILITY {
-        return (LocationType::Global, offset as u32);
+    fn offset_from_memory_location(&self, ltype: LocationType, offset: u32) -> usize {
+        match ltype {
+            LocationType::Stack => offset as usize,
+            LocationType::Global => STACK_CAPABILITY + offset as usize,
+            LocationType::Heap => STACK_CAPABILITY + GLOBAL_CAPABILITY + offset as usize,
+        }
     }
-
-    offset -= GLOBAL_CAPABILITY;
-    return (LocationType::Heap, offset as u32);
 }
 
 /*
@@ -86,7 +89,7 @@ pub fn image_table_offset_to_memory_location(offset: usize) -> (LocationType, u3
  * Br Table
  * --------------------
  * Padding
- * -------------------- Init Memory Offset(Constant INIT_MEMORY_ENTRIES_OFFSET)
+ * -------------------- Init Memory Offset(init_memory_entries_offset(k))
  * Stack
  * --------------------
  * Global
@@ -94,7 +97,7 @@ pub fn image_table_offset_to_memory_location(offset: usize) -> (LocationType, u3
  * Heap
  * --------------------
  */
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ImageTableLayouter<T> {
     pub(crate) initialization_state: InitializationState<T>,
     pub(crate) inherited_frame_entries: Box<[T; INHERITED_FRAME_TABLE_ENTRIES]>,
@@ -119,15 +122,15 @@ pub struct ImageTableAssigner {
 
 impl ImageTableAssigner {
     /// `instruction_number` and `br_table_number` came from wasm image. Instructions, br table entries and paddings
-    /// are compacted within a fixed range. `page_capability` is computed based on K.
-    pub fn new(instruction_number: usize, br_table_number: usize, pages_capability: u32) -> Self {
+    /// are compacted within a range determined by K, so is the page capability.
+    pub fn new(k: u32, instruction_number: usize, br_table_number: usize) -> Self {
         let initialization_state_offset = 0;
         let inherited_frame_entries_offset =
             initialization_state_offset + InitializationState::<u32>::field_count();
         let instruction_offset = inherited_frame_entries_offset + INHERITED_FRAME_TABLE_ENTRIES;
         let br_table_offset = instruction_offset + instruction_number;
         let padding_offset = br_table_offset + br_table_number;
-        let init_memory_offset = INIT_MEMORY_ENTRIES_OFFSET;
+        let init_memory_offset = init_memory_entries_offset(k);
 
         assert!(
             padding_offset <= init_memory_offset,
@@ -136,7 +139,7 @@ impl ImageTableAssigner {
         );
 
         Self {
-            heap_capability: pages_capability * PAGE_ENTRIES,
+            heap_capability: compute_maximal_pages(k) * PAGE_ENTRIES,
 
             initialization_state_offset,
             inherited_frame_entries_offset,
@@ -147,6 +150,24 @@ impl ImageTableAssigner {
         }
     }
 
+    pub fn offset_to_memory_location(&self, offset: usize) -> (LocationType, u32) {
+        // Minus one for default lookup entry.
+        let mut offset = offset - self.init_memory_offset - 1;
+
+        if offset < STACK_CAPABILITY {
+            return (LocationType::Stack, offset as u32);
+        }
+
+        offset -= STACK_CAPABILITY;
+
+        if offset < GLOBAL_CAPABILITY {
+            return (LocationType::Global, offset as u32);
+        }
+
+        offset -= GLOBAL_CAPABILITY;
+        (LocationType::Heap, offset as u32)
+    }
+
     pub fn exec_initialization_state<T, Error>(
         &self,
         mut initialization_state_handler: impl FnMut(usize) -> Result<InitializationState<T>, Error>,
@@ -226,6 +247,87 @@ impl ImageTableAssigner {
     }
 }
 
+fn encode_init_memory_entries<F: FieldExt>(k: u32, init_memory_table: &InitMemoryTable) -> Vec<F> {
+    let layouter = InitMemoryLayouter {
+        pages: compute_maximal_pages(k),
+    };
+
+    // The first entry is a default entry.
+    let mut cells = Vec::with_capacity(layouter.len() + 1);
+    cells.push(bn_to_field(
+        &ImageTableEncoder::InitMemory.encode(BigUint::from(0u64)),
+    ));
+    unsafe { cells.set_len(layouter.len() + 1) };
+
+    {
+        let address = &cells;
+
+        const THREADS: usize = 6;
+        let chunk_size = (layouter.len() + THREADS - 1) / THREADS;
+        let default_memory_entry = bn_to_field::<F>(
+            &ImageTableEncoder::InitMemory.encode(
+                InitMemoryTableEntry {
+                    ltype: LocationType::Heap,
+                    is_mutable: true,
+                    offset: 0,
+                    vtype: VarType::I64,
+                    value: 0,
+                    eid: 0,
+                }
+                .encode(),
+            ),
+        );
+
+        (0..layouter.len())
+            .collect::<Vec<_>>()
+            .par_chunks(chunk_size)
+            .for_each(|chunk| {
+                let mut empty_entry = None;
+
+                for pos in chunk {
+                    let (ltype, offset) = layouter.memory_location_from_offset(*pos);
+
+                    let entry = if let Some(entry) = init_memory_table.try_find(ltype, offset) {
+                        Cow::Owned(bn_to_field::<F>(
+                            &ImageTableEncoder::InitMemory.encode(entry.encode()),
+                        ))
+                    } else if ltype == LocationType::Heap {
+                        // Perf: Use default entry to enhance performance.
+                        // Original code:
+                        // let entry = InitMemoryTableEntry {
+                        //     ltype,
+                        //     is_mutable: true,
+                        //     offset,
+                        //     vtype: VarType::I64,
+                        //     value: 0,
+                        //     eid: 0,
+                        // };
+
+                        //   bn_to_field::<F>(&ImageTableEncoder::InitMemory.encode(entry.encode()))
+
+                        Cow::Owned(init_memory_table_entry_encode_update_offset(
+                            default_memory_entry,
+                            || F::from(offset as u64),
+                        ))
+                    } else {
+                        Cow::Borrowed(empty_entry.get_or_insert_with(|| {
+                            bn_to_field::<F>(&ImageTableEncoder::InitMemory.encode(BigUint::zero()))
+                        }))
+                    };
+
+                    let addr = address.as_ptr();
+                    unsafe {
+                        let addr = addr as *mut F;
+
+                        *addr.offset((pos + 1) as isize) = *entry;
+                    }
+                }
+            });
+    }
+
+    cells
+}
+
 pub(crate) fn encode_compilation_table_values<F: FieldExt>(
     k: u32,
     itable: &InstructionTable,
@@ -235,8 +337,54 @@ pub(crate) fn encode_compilation_table_values<F: FieldExt>(
     initialization_state: &InitializationState<u32>,
     init_memory_table: &InitMemoryTable,
 ) -> ImageTableLayouter<F> {
-    let page_capability = compute_maximal_pages(k);
+    encode_image_table_values(
+        k,
+        itable,
+        br_table,
+        elem_table,
+        inherited_frame_table,
+        initialization_state,
+        |_| Ok(encode_init_memory_entries(k, init_memory_table)),
+    )
+}
 
+/// Derives the init memory entries of the image after a slice from the entries of the image
+/// before it. Only the entries in the chunks of `post_init_memory_table` not shared with
+/// `pre_init_memory_table` are encoded again.
+fn update_init_memory_entries<F: FieldExt>(
+    k: u32,
+    pre_init_memory_table: &InitMemoryTable,
+    post_init_memory_table: &InitMemoryTable,
+    pre_init_memory_entries: &[F],
+) -> Vec<F> {
+    let layouter = InitMemoryLayouter {
+        pages: compute_maximal_pages(k),
+    };
+
+    let mut cells = pre_init_memory_entries.to_vec();
+
+    for entry in post_init_memory_table.updated_entries(pre_init_memory_table) {
+        let pos = layouter.offset_from_memory_location(entry.ltype, entry.offset);
+
+        if pos < layouter.len() {
+            // Plus one for the default entry.
+            cells[pos + 1] =
+                bn_to_field::<F>(&ImageTableEncoder::InitMemory.encode(entry.encode()));
+        }
+    }
+
+    cells
+}
+
+fn encode_image_table_values<F: FieldExt>(
+    k: u32,
+    itable: &InstructionTable,
+    br_table: &BrTable,
+    elem_table: &ElemTable,
+    inherited_frame_table: &InheritedFrameTable,
+    initialization_state: &InitializationState<u32>,
+    init_memory_entries_handler: impl FnMut(usize) -> Result<Vec<F>, Error>,
+) -> ImageTableLayouter<F> {
     let initialization_state_handler = |_| Ok(initialization_state.map(|v| F::from((*v) as u64)));
 
     let inherited_frame_entries_handler = |_| {
@@ -285,93 +433,10 @@ pub(crate) fn encode_compilation_table_values<F: FieldExt>(
 
     let padding_handler = |start, end| Ok(vec![F::zero(); end - start]);
 
-    let init_memory_entries_handler = |_| {
-        let layouter = InitMemoryLayouter {
-            pages: page_capability,
-        };
-
-        // The first entry is a default entry.
-        let mut cells = Vec::with_capacity(layouter.len() + 1);
-        cells.push(bn_to_field(
-            &ImageTableEncoder::InitMemory.encode(BigUint::from(0u64)),
-        ));
-        unsafe { cells.set_len(layouter.len() + 1) };
-
-        {
-            let address = &cells;
-
-            const THREADS: usize = 6;
-            let chunk_size = (layouter.len() + THREADS - 1) / THREADS;
-            let default_memory_entry = bn_to_field::<F>(
-                &ImageTableEncoder::InitMemory.encode(
-                    InitMemoryTableEntry {
-                        ltype: LocationType::Heap,
-                        is_mutable: true,
-                        offset: 0,
-                        vtype: VarType::I64,
-                        value: 0,
-                        eid: 0,
-                    }
-                    .encode(),
-                ),
-            );
-
-            (0..layouter.len())
-                .collect::<Vec<_>>()
-                .par_chunks(chunk_size)
-                .for_each(|chunk| {
-                    let mut empty_entry = None;
-
-                    for pos in chunk {
-                        let (ltype, offset) = layouter.memory_location_from_offset(*pos);
-
-                        let entry = if let Some(entry) = init_memory_table.try_find(ltype, offset) {
-                            Cow::Owned(bn_to_field::<F>(
-                                &ImageTableEncoder::InitMemory.encode(entry.encode()),
-                            ))
-                        } else if ltype == LocationType::Heap {
-                            // Perf: Use default entry to enhance performance.
-                            // Original code:
-                            // let entry = InitMemoryTableEntry {
-                            //     ltype,
-                            //     is_mutable: true,
-                            //     offset,
-                            //     vtype: VarType::I64,
-                            //     value: 0,
-                            //     eid: 0,
-                            // };
-
-                            //   bn_to_field::<F>(&ImageTableEncoder::InitMemory.encode(entry.encode()))
-
-                            Cow::Owned(init_memory_table_entry_encode_update_offset(
-                                default_memory_entry,
-                                || F::from(offset as u64),
-                            ))
-                        } else {
-                            Cow::Borrowed(empty_entry.get_or_insert_with(|| {
-                                bn_to_field::<F>(
-                                    &ImageTableEncoder::InitMemory.encode(BigUint::zero()),
-                                )
-                            }))
-                        };
-
-                        let addr = address.as_ptr();
-                        unsafe {
-                            let addr = addr as *mut F;
-
-                            *addr.offset((pos + 1) as isize) = *entry;
-                        }
-                    }
-                });
-        }
-
-        Ok(cells)
-    };
-
     let assigner = ImageTableAssigner::new(
+        k,
         itable.len() + 1,
         br_table.entries().len() + elem_table.entries().len() + 1,
-        page_capability,
     );
 
     let layouter = assigner
@@ -391,7 +456,11 @@ pub(crate) fn encode_compilation_table_values<F: FieldExt>(
 pub(crate) trait EncodeImageTable<F: FieldExt> {
     fn encode_pre_compilation_table_values(&self, k: u32) -> ImageTableLayouter<F>;
 
-    fn encode_post_compilation_table_values(&self, k: u32) -> ImageTableLayouter<F>;
+    fn encode_post_compilation_table_values(
+        &self,
+        k: u32,
+        pre_image_table: &ImageTableLayouter<F>,
+    ) -> ImageTableLayouter<F>;
 }
 
 impl<F: FieldExt> EncodeImageTable<F> for Slice {
@@ -407,15 +476,26 @@ impl<F: FieldExt> EncodeImageTable<F> for Slice {
         )
     }
 
-    fn encode_post_compilation_table_values(&self, k: u32) -> ImageTableLayouter<F> {
-        encode_compilation_table_values(
+    fn encode_post_compilation_table_values(
+        &self,
+        k: u32,
+        pre_image_table: &ImageTableLayouter<F>,
+    ) -> ImageTableLayouter<F> {
+        encode_image_table_values(
             k,
             &self.itable,
             &self.br_table,
             &self.elem_table,
             &self.post_inherited_frame_table,
             &self.post_initialization_state,
-            &self.post_imtable,
+            |_| {
+                Ok(update_init_memory_entries(
+                    k,
+                    &self.imtable,
+                    &self.post_imtable,
+                    &pre_image_table.init_memory_entries,
+                ))
+            },
         )
     }
 }
@@ -1,4 +1,9 @@
+use super::u8::U8Column;
 use super::Context;
+use crate::circuits::bit_table::LimbLayout;
+use crate::constant_from;
+use crate::curr;
+use crate::fixed_curr;
 use halo2_proofs::arithmetic::FieldExt;
 use halo2_proofs::plonk::Advice;
 use halo2_proofs::plonk::Column;
@@ -7,17 +12,52 @@ use halo2_proofs::plonk::Error;
 use halo2_proofs::plonk::Fixed;
 use std::marker::PhantomData;
 
+/// Rows reserved for the shuffle range checks of u16 columns. A u16 column with step 2 takes
+/// (1 << 16) / 2 rows, which is half of the smallest circuits, so the u4 limbs layout splits u16
+/// columns into u8 limbs whose shuffle takes 1 << 8 rows.
+pub(crate) fn range_check_rows(limb_layout: LimbLayout) -> usize {
+    if limb_layout == LimbLayout::U4 {
+        1 << 8
+    } else {
+        1 << 15
+    }
+}
+
 #[derive(Clone)]
 pub struct U16Column<F: FieldExt> {
     pub col: Column<Advice>,
+    /// Little-endian u8 limbs of `col`, only with the u4 limbs layout.
+    pub limbs: Option<[Column<Advice>; 2]>,
     _mark: PhantomData<F>,
 }
 
 impl<F: FieldExt> U16Column<F> {
-    pub fn configure(
+    pub(crate) fn configure(
         meta: &mut ConstraintSystem<F>,
         (l_0, l_active, l_active_last): (Column<Fixed>, Column<Fixed>, Column<Fixed>),
+        limb_layout: LimbLayout,
     ) -> Self {
+        if limb_layout == LimbLayout::U4 {
+            let col = meta.advice_column();
+            let limbs =
+                [0; 2].map(|_| U8Column::configure(meta, (l_0, l_active, l_active_last)).col);
+
+            meta.create_gate("u16 limbs", |meta| {
+                vec![
+                    (curr!(meta, col)
+                        - curr!(meta, limbs[0])
+                        - curr!(meta, limbs[1]) * constant_from!(1 << 8))
+                        * fixed_curr!(meta, l_active),
+                ]
+            });
+
+            return Self {
+                col,
+                limbs: Some(limbs),
+                _mark: PhantomData,
+            };
+        }
+
         let col = meta.advice_column_range(
             l_0,
             l_active,
@@ -29,6 +69,7 @@ impl<F: FieldExt> U16Column<F> {
 
         Self {
             col,
+            limbs: None,
             _mark: PhantomData,
         }
     }
@@ -37,6 +78,17 @@ impl<F: FieldExt> U16Column<F> {
         ctx.region
             .assign_advice(|| "u16 value", self.col, ctx.offset, || Ok(value.into()))?;
 
+        if let Some(limbs) = self.limbs {
+            for (i, limb) in limbs.into_iter().enumerate() {
+                ctx.region.assign_advice(
+                    || "u16 limb",
+                    limb,
+                    ctx.offset,
+                    || Ok(((value >> (i * 8)) & 0xff).into()),
+                )?;
+            }
+        }
+
         Ok(())
     }
 }
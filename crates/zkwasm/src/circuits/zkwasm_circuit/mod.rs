@@ -24,6 +24,7 @@ use specs::slice::Slice;
 use crate::circuits::bit_table::BitTableChip;
 use crate::circuits::bit_table::BitTableConfig;
 use crate::circuits::bit_table::BitTableTrait;
+use crate::circuits::bit_table::LimbLayout;
 use crate::circuits::compute_slice_capability;
 use crate::circuits::etable::EventTableChip;
 use crate::circuits::etable::EventTableConfig;
@@ -43,18 +44,22 @@ use crate::circuits::utils::image_table::ImageTableAssigner;
 use crate::circuits::utils::image_table::ImageTableLayouter;
 use crate::circuits::utils::table_entry::EventTableWithMemoryInfo;
 use crate::circuits::utils::table_entry::MemoryWritingTable;
+use crate::circuits::utils::u16::range_check_rows;
 use crate::exec_with_profile;
 use crate::foreign::context::circuits::assign::ContextContHelperTableChip;
 use crate::foreign::context::circuits::assign::ExtractContextFromTrace;
 use crate::foreign::context::circuits::ContextContHelperTableConfig;
 use crate::foreign::context::circuits::CONTEXT_FOREIGN_TABLE_KEY;
 use crate::foreign::foreign_table_enable_lines;
+use crate::foreign::wasm_input_helper::circuits::assign::ExtractIndexedInputsFromTrace;
+use crate::foreign::wasm_input_helper::circuits::assign::IndexedInputHelperTableChip;
+use crate::foreign::wasm_input_helper::circuits::IndexedInputHelperTableConfig;
 use crate::foreign::wasm_input_helper::circuits::WasmInputHelperTableConfig;
+use crate::foreign::wasm_input_helper::circuits::INDEXED_INPUT_FOREIGN_TABLE_KEY;
 use crate::foreign::wasm_input_helper::circuits::WASM_INPUT_FOREIGN_TABLE_KEY;
 use crate::foreign::ForeignTableConfig;
 use crate::runtime::memory_event_of_step;
 
-use super::config::zkwasm_k;
 use super::etable::assign::EventTablePermutationCells;
 use super::image_table::ImageTableConfig;
 use super::jtable::FrameEtablePermutationCells;
@@ -69,9 +74,13 @@ pub const VAR_COLUMNS: usize = if cfg!(feature = "continuation") {
 };
 
 // Reserve a few rows to keep usable rows away from blind rows.
-// The maximal step size of all tables is bit_table::STEP_SIZE.
-// Reserve (1 << 16) / 2 to allow u16 range checking based on shuffle with step 2.
-pub(crate) const RESERVE_ROWS: usize = crate::circuits::bit_table::STEP_SIZE + (1 << 15);
+// The maximal step size of all tables is the bit table step size, which depends on the limb layout.
+// Also reserve the rows of the u16 range checking based on shuffle, see `range_check_rows`.
+pub(crate) fn reserve_rows(k: u32) -> usize {
+    let limb_layout = LimbLayout::for_k(k);
+
+    limb_layout.step_size() + range_check_rows(limb_layout)
+}
 
 #[derive(Default, Clone)]
 struct AssignedCells<F: FieldExt> {
@@ -98,19 +107,16 @@ pub struct ZkWasmCircuitConfig<F: FieldExt> {
     bit_table: BitTableConfig<F>,
     external_host_call_table: ExternalHostCallTableConfig<F>,
     context_helper_table: ContextContHelperTableConfig<F>,
+    indexed_input_helper_table: IndexedInputHelperTableConfig<F>,
 
     foreign_table_from_zero_index: Column<Fixed>,
 
-    max_available_rows: usize,
-    circuit_maximal_pages: u32,
-    l_last: usize,
-
-    k: u32,
+    blinding_factors: usize,
 }
 
 macro_rules! impl_zkwasm_circuit {
     ($name:ident, $last_slice:expr) => {
-        impl<F: FieldExt> Circuit<F> for $name<F> {
+        impl<F: FieldExt, const LIMB_BITS: usize> Circuit<F> for $name<F, LIMB_BITS> {
             type Config = ZkWasmCircuitConfig<F>;
 
             type FloorPlanner = FlatFloorPlanner;
@@ -146,8 +152,6 @@ macro_rules! impl_zkwasm_circuit {
             }
 
             fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-                let k = zkwasm_k();
-
                 /*
                  * Allocate a column to enable assign_advice_from_constant.
                  */
@@ -173,6 +177,10 @@ macro_rules! impl_zkwasm_circuit {
 
                 let mut cols = [(); VAR_COLUMNS].map(|_| meta.advice_column()).into_iter();
 
+                let limb_layout = LimbLayout {
+                    limb_bits: LIMB_BITS,
+                };
+
                 let rtable = RangeTableConfig::configure(meta);
                 let image_table = ImageTableConfig::configure(meta, memory_addr_sel);
                 let mtable = MemoryTableConfig::configure(
@@ -181,6 +189,7 @@ macro_rules! impl_zkwasm_circuit {
                     &mut cols,
                     &rtable,
                     &image_table,
+                    limb_layout,
                 );
                 let frame_table = JumpTableConfig::configure(meta, $last_slice);
                 let post_image_table = PostImageTableConfig::configure(
@@ -191,12 +200,14 @@ macro_rules! impl_zkwasm_circuit {
                     &image_table,
                 );
                 let external_host_call_table = ExternalHostCallTableConfig::configure(meta);
-                let bit_table = BitTableConfig::configure(meta, &rtable);
+                let bit_table = BitTableConfig::configure(meta, &rtable, limb_layout);
 
                 let wasm_input_helper_table =
                     WasmInputHelperTableConfig::configure(meta, foreign_table_from_zero_index);
                 let context_helper_table =
                     ContextContHelperTableConfig::configure(meta, foreign_table_from_zero_index);
+                let indexed_input_helper_table =
+                    IndexedInputHelperTableConfig::configure(meta, foreign_table_from_zero_index);
 
                 let mut foreign_table_configs: BTreeMap<_, Box<(dyn ForeignTableConfig<F>)>> =
                     BTreeMap::new();
@@ -208,6 +219,10 @@ macro_rules! impl_zkwasm_circuit {
                     CONTEXT_FOREIGN_TABLE_KEY,
                     Box::new(context_helper_table.clone()),
                 );
+                foreign_table_configs.insert(
+                    INDEXED_INPUT_FOREIGN_TABLE_KEY,
+                    Box::new(indexed_input_helper_table.clone()),
+                );
 
                 let etable = EventTableConfig::configure(
                     meta,
@@ -220,19 +235,11 @@ macro_rules! impl_zkwasm_circuit {
                     &bit_table,
                     &external_host_call_table,
                     &foreign_table_configs,
+                    limb_layout,
                 );
 
                 assert_eq!(cols.count(), 0);
 
-                let max_available_rows = (1 << k) - (meta.blinding_factors() + 1 + RESERVE_ROWS);
-                debug!("max_available_rows: {:?}", max_available_rows);
-
-                let circuit_maximal_pages = compute_maximal_pages(k);
-                info!(
-                    "Circuit K: {} supports up to {} pages.",
-                    k, circuit_maximal_pages
-                );
-
                 Self::Config {
                     shuffle_range_check_helper: (l_0, l_active, l_active_last),
                     rtable,
@@ -244,13 +251,10 @@ macro_rules! impl_zkwasm_circuit {
                     bit_table,
                     external_host_call_table,
                     context_helper_table,
+                    indexed_input_helper_table,
                     foreign_table_from_zero_index,
 
-                    max_available_rows,
-                    circuit_maximal_pages,
-                    l_last: (1 << k) - (meta.blinding_factors() + 1),
-
-                    k,
+                    blinding_factors: meta.blinding_factors(),
                 }
             }
 
@@ -261,39 +265,53 @@ macro_rules! impl_zkwasm_circuit {
             ) -> Result<(), Error> {
                 let timer = start_timer!(|| "Prepare assignment");
 
+                // The constraint system does not depend on K, all K related layout is derived
+                // from the circuit itself so that circuits of different sizes can coexist.
+                let k = self.k;
+                let l_last = (1 << k) - (config.blinding_factors + 1);
+                let max_available_rows = l_last - reserve_rows(k);
+                debug!("max_available_rows: {:?}", max_available_rows);
+
+                let circuit_maximal_pages = compute_maximal_pages(k);
+                info!(
+                    "Circuit K: {} supports up to {} pages.",
+                    k, circuit_maximal_pages
+                );
+
                 let rchip = RangeTableChip::new(config.rtable);
                 let image_chip = ImageTableChip::new(config.image_table);
                 let post_image_chip = PostImageTableChip::new(config.post_image_table);
-                let mchip = MemoryTableChip::new(config.mtable, config.max_available_rows);
-                let frame_table_chip =
-                    JumpTableChip::new(config.frame_table, config.max_available_rows);
+                let mchip = MemoryTableChip::new(config.mtable, max_available_rows);
+                let frame_table_chip = JumpTableChip::new(config.frame_table, max_available_rows);
                 let echip = EventTableChip::new(
                     config.etable,
-                    compute_slice_capability(self.k) as usize,
-                    config.max_available_rows,
-                );
-                let bit_chip = BitTableChip::new(config.bit_table, config.max_available_rows);
-                let external_host_call_chip = ExternalHostCallChip::new(
-                    config.external_host_call_table,
-                    config.max_available_rows,
+                    compute_slice_capability(k) as usize,
+                    max_available_rows,
                 );
+                let bit_chip = BitTableChip::new(config.bit_table, max_available_rows);
+                let external_host_call_chip =
+                    ExternalHostCallChip::new(config.external_host_call_table, max_available_rows);
                 let context_chip = ContextContHelperTableChip::new(config.context_helper_table);
+                let indexed_input_chip = IndexedInputHelperTableChip::new(
+                    config.indexed_input_helper_table,
+                    foreign_table_enable_lines(k),
+                );
 
                 let image_table_assigner = exec_with_profile!(|| "Prepare image table assigner", {
                     ImageTableAssigner::new(
+                        k,
                         // Add one for default lookup value
                         self.slice.itable.len() + 1,
                         self.slice.br_table.entries().len()
                             + self.slice.elem_table.entries().len()
                             + 1,
-                        config.circuit_maximal_pages,
                     )
                 });
 
                 let memory_writing_table: MemoryWritingTable = exec_with_profile!(
                     || "Prepare mtable",
                     MemoryWritingTable::from(
-                        config.k,
+                        k,
                         self.slice.create_memory_table(memory_event_of_step),
                     )
                 );
@@ -331,12 +349,12 @@ macro_rules! impl_zkwasm_circuit {
                                             .assign_fixed(
                                                 || "l_active_last",
                                                 l_active_last,
-                                                config.l_last - 1,
+                                                l_last - 1,
                                                 || Ok(F::one()),
                                             )
                                             .unwrap();
 
-                                        for offset in 0..config.l_last {
+                                        for offset in 0..l_last {
                                             region
                                                 .assign_fixed(
                                                     || "l_active_last",
@@ -352,7 +370,15 @@ macro_rules! impl_zkwasm_circuit {
                                 )
                                 .unwrap();
 
-                            rchip.init(_layouter, config.k).unwrap()
+                            rchip
+                                .init(
+                                    _layouter,
+                                    k,
+                                    LimbLayout {
+                                        limb_bits: LIMB_BITS,
+                                    },
+                                )
+                                .unwrap()
                         });
                     });
 
@@ -364,7 +390,7 @@ macro_rules! impl_zkwasm_circuit {
                                 .assign_region(
                                     || "foreign helper",
                                     |region| {
-                                        for offset in 0..foreign_table_enable_lines(config.k) {
+                                        for offset in 0..foreign_table_enable_lines(k) {
                                             region.assign_fixed(
                                                 || "foreign table from zero index",
                                                 config.foreign_table_from_zero_index,
@@ -419,42 +445,62 @@ macro_rules! impl_zkwasm_circuit {
                     });
 
                     let _layouter = layouter.clone();
-                    let _assigned_cells = assigned_cells.clone();
                     s.spawn(move |_| {
-                        exec_with_profile!(|| "Assign pre image table chip", {
-                            let pre_image_table =
-                                self.slice.encode_pre_compilation_table_values(config.k);
-
-                            let cells = image_chip
-                                .assign(_layouter, &image_table_assigner, pre_image_table)
-                                .unwrap();
-
-                            *_assigned_cells.pre_image_table_cells.lock().unwrap() = Some(cells);
-                        });
+                        exec_with_profile!(
+                            || "Assign indexed input chip",
+                            indexed_input_chip
+                                .assign(_layouter, &self.slice.etable.get_indexed_inputs())
+                                .unwrap()
+                        );
                     });
 
                     let _layouter = layouter.clone();
                     let _assigned_cells = assigned_cells.clone();
                     let _memory_writing_table = memory_writing_table.clone();
-                    s.spawn(move |_| {
-                        exec_with_profile!(|| "Assign post image table chip", {
-                            let post_image_table: ImageTableLayouter<F> =
-                                self.slice.encode_post_compilation_table_values(config.k);
+                    s.spawn(move |s| {
+                        let pre_image_table = exec_with_profile!(
+                            || "Encode pre image table",
+                            self.slice.encode_pre_compilation_table_values(k)
+                        );
 
-                            let (rest_memory_writing_ops, memory_finalized_set) =
-                                _memory_writing_table.count_rest_memory_finalize_ops();
+                        // The post image only differs from the pre image in the memory written
+                        // by the slice, derive it from the pre image.
+                        let post_image_table: ImageTableLayouter<F> = exec_with_profile!(
+                            || "Encode post image table",
+                            self.slice
+                                .encode_post_compilation_table_values(k, &pre_image_table)
+                        );
 
-                            let cells = post_image_chip
-                                .assign(
-                                    _layouter,
-                                    &image_table_assigner,
-                                    post_image_table,
-                                    rest_memory_writing_ops,
-                                    memory_finalized_set,
-                                )
+                        {
+                            let _layouter = _layouter.clone();
+                            let _assigned_cells = _assigned_cells.clone();
+                            s.spawn(move |_| {
+                                exec_with_profile!(|| "Assign post image table chip", {
+                                    let (rest_memory_writing_ops, memory_finalized_set) =
+                                        _memory_writing_table.count_rest_memory_finalize_ops();
+
+                                    let cells = post_image_chip
+                                        .assign(
+                                            _layouter,
+                                            &image_table_assigner,
+                                            post_image_table,
+                                            rest_memory_writing_ops,
+                                            memory_finalized_set,
+                                        )
+                                        .unwrap();
+
+                                    *_assigned_cells.post_image_table_cells.lock().unwrap() =
+                                        Some(cells);
+                                });
+                            });
+                        }
+
+                        exec_with_profile!(|| "Assign pre image table chip", {
+                            let cells = image_chip
+                                .assign(_layouter, &image_table_assigner, pre_image_table)
                                 .unwrap();
 
-                            *_assigned_cells.post_image_table_cells.lock().unwrap() = Some(cells);
+                            *_assigned_cells.pre_image_table_cells.lock().unwrap() = Some(cells);
                         });
                     });
 
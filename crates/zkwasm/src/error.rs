@@ -1,3 +1,4 @@
+use specs::trace_file::TraceFileError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -8,10 +9,18 @@ pub enum ExecutionError {}
 
 #[derive(Debug, Error)]
 pub enum BuildingCircuitError {
+    #[error("K({0}) is not supported, it should be in [{1}, {2}].")]
+    UnsupportedK(u32, u32, u32),
+    #[error(
+        "The circuit with {0}-bit limbs does not match K({1}), the limb layout is derived from K."
+    )]
+    LimbLayoutMismatch(usize, u32),
     #[error("Only support single slice for non-continuation mode but {0} provided. You could increase K or enable continuation feature.")]
     MultiSlicesNotSupport(usize),
     #[error("Allocated pages({0}) exceed the limit({1}). Current K is {2}, consider increasing the circuit size K.")]
     PagesExceedLimit(u32, u32, u32),
     #[error("Etable entries({0}) exceed the limit({1}). Current K is {2}, consider increasing the circuit size K.")]
     EtableEntriesExceedLimit(u32, u32, u32),
+    #[error("Failed to load the trace: {0}")]
+    TraceFile(#[from] TraceFileError),
 }
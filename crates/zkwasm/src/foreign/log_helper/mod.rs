@@ -1,16 +1,56 @@
 use std::rc::Rc;
 
 use specs::external_host_call_table::ExternalHostCallSignature;
+use wasmi::MemoryRef;
+use wasmi::Signature;
+use wasmi::Trap;
+use wasmi::TrapKind;
+use wasmi::ValueType;
 
 use crate::runtime::host::host_env::HostEnv;
+use crate::runtime::host::host_trap;
 use crate::runtime::host::ForeignContext;
+use crate::runtime::host::HostFunctionResult;
 use crate::runtime::monitor::observer::Observer;
 use zkwasm_host_circuits::host::ForeignInst::Log;
 use zkwasm_host_circuits::host::ForeignInst::LogChar;
 
+pub const GUEST_LOG_PLUGIN: &str = "foreign_guest_log";
+
+/// A message emitted by the guest through `wasm_log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestLog {
+    pub level: log::Level,
+    /// Name of the guest function issuing the call
+    pub function: Option<String>,
+    pub message: String,
+}
+
 struct Context;
 impl ForeignContext for Context {}
 
+#[derive(Default)]
+struct GuestLogContext {
+    logs: Vec<GuestLog>,
+}
+impl ForeignContext for GuestLogContext {
+    fn expose_guest_logs(&self) -> Vec<GuestLog> {
+        self.logs.clone()
+    }
+}
+
+/// Maps the level of `wasm_log` (1: error, 2: warn, 3: info, 4: debug, 5: trace)
+/// to `log::Level`, out-of-range values are clamped.
+fn guest_log_level(level: u32) -> log::Level {
+    match level {
+        0 | 1 => log::Level::Error,
+        2 => log::Level::Warn,
+        3 => log::Level::Info,
+        4 => log::Level::Debug,
+        _ => log::Level::Trace,
+    }
+}
+
 pub fn register_log_foreign(env: &mut HostEnv) {
     let foreign_log_plugin = env
         .external_env
@@ -47,4 +87,53 @@ pub fn register_log_foreign(env: &mut HostEnv) {
         foreign_log_plugin,
         printchar,
     );
+
+    let foreign_guest_log_plugin = env
+        .phantom_env
+        .register_plugin(GUEST_LOG_PLUGIN, Box::new(GuestLogContext::default()));
+
+    // void wasm_log(uint32_t level, const char* message, uint32_t len)
+    //
+    // The call is not traced, so it is only allowed within phantom functions.
+    env.phantom_env.register_function(
+        "wasm_log",
+        Signature::new(vec![ValueType::I32, ValueType::I32, ValueType::I32], None),
+        foreign_guest_log_plugin,
+        Rc::new(
+            |observer: &Observer,
+             memory: Option<&MemoryRef>,
+             context: &mut dyn ForeignContext,
+             args: wasmi::RuntimeArgs|
+             -> HostFunctionResult {
+                let context = context.downcast_mut::<GuestLogContext>().unwrap();
+
+                let level = guest_log_level(args.nth(0));
+                let address: u32 = args.nth(1);
+                let len: u32 = args.nth(2);
+
+                let message = memory
+                    .ok_or_else(|| host_trap("wasm_log requires a linear memory"))?
+                    .get(address, len as usize)
+                    .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                    .map_err(|_| Trap::new(TrapKind::MemoryAccessOutOfBounds))?;
+                let function = observer.current_function_name();
+
+                log::log!(
+                    target: "zkwasm::guest",
+                    level,
+                    "[{}] {}",
+                    function.as_deref().unwrap_or("unknown"),
+                    message
+                );
+
+                context.logs.push(GuestLog {
+                    level,
+                    function,
+                    message,
+                });
+
+                Ok(None)
+            },
+        ),
+    );
 }
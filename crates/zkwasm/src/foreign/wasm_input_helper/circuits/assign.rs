@@ -0,0 +1,82 @@
+use std::collections::BTreeMap;
+
+use halo2_proofs::arithmetic::FieldExt;
+use halo2_proofs::circuit::Layouter;
+use halo2_proofs::plonk::Error;
+use specs::etable::EventTable;
+use specs::host_function::HostPlugin;
+use specs::step::StepInfo;
+
+use crate::foreign::wasm_input_helper::Op;
+
+use super::IndexedInputHelperTableConfig;
+
+pub struct IndexedInputHelperTableChip<F: FieldExt> {
+    config: IndexedInputHelperTableConfig<F>,
+    enable_lines: usize,
+}
+
+impl<F: FieldExt> IndexedInputHelperTableChip<F> {
+    pub fn new(config: IndexedInputHelperTableConfig<F>, enable_lines: usize) -> Self {
+        Self {
+            config,
+            enable_lines,
+        }
+    }
+
+    pub fn assign(
+        &self,
+        layouter: impl Layouter<F>,
+        inputs: &BTreeMap<u32, u64>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "indexed input helper assign",
+            |region| {
+                for (index, input) in inputs {
+                    // The first fixed index should be 1.
+                    let offset = *index as usize + 1;
+
+                    if offset >= self.enable_lines {
+                        return Err(Error::Synthesis);
+                    }
+
+                    region.assign_advice(
+                        || "indexed input",
+                        self.config.input,
+                        offset,
+                        || Ok(F::from(*input)),
+                    )?;
+                }
+
+                Ok(())
+            },
+        )?;
+
+        Ok(())
+    }
+}
+
+pub trait ExtractIndexedInputsFromTrace {
+    /// The indexed input words read by the slice.
+    fn get_indexed_inputs(&self) -> BTreeMap<u32, u64>;
+}
+
+impl ExtractIndexedInputsFromTrace for EventTable {
+    fn get_indexed_inputs(&self) -> BTreeMap<u32, u64> {
+        self.entries()
+            .iter()
+            .filter_map(|e| match &e.step_info {
+                StepInfo::CallHost {
+                    plugin: HostPlugin::HostInput,
+                    op_index_in_plugin,
+                    args,
+                    ret_val,
+                    ..
+                } if *op_index_in_plugin == Op::WasmIndexedInput as usize => {
+                    Some((args[0] as u32, ret_val.unwrap()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
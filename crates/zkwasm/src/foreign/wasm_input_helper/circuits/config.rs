@@ -5,10 +5,12 @@ use halo2_proofs::plonk::Expression;
 use halo2_proofs::plonk::Fixed;
 use halo2_proofs::plonk::VirtualCells;
 
+use crate::curr;
 use crate::fixed_curr;
 use crate::foreign::ForeignTableConfig;
 use crate::instance_prev;
 
+use super::IndexedInputHelperTableConfig;
 use super::WasmInputHelperTableConfig;
 
 impl<F: FieldExt> WasmInputHelperTableConfig<F> {
@@ -41,3 +43,33 @@ impl<F: FieldExt> ForeignTableConfig<F> for WasmInputHelperTableConfig<F> {
         });
     }
 }
+
+impl<F: FieldExt> IndexedInputHelperTableConfig<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>, from_zero_index: Column<Fixed>) -> Self {
+        let input = meta.named_advice_column("indexed_input".to_string());
+
+        IndexedInputHelperTableConfig {
+            from_zero_index,
+            input,
+            _mark: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: FieldExt> ForeignTableConfig<F> for IndexedInputHelperTableConfig<F> {
+    fn configure_in_table(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+        key: &'static str,
+        expr: &dyn Fn(&mut VirtualCells<'_, F>) -> Vec<Expression<F>>,
+    ) {
+        meta.lookup_any(key, |meta| {
+            let mut exprs = expr(meta);
+
+            vec![
+                (exprs.remove(0), fixed_curr!(meta, self.from_zero_index)),
+                (exprs.remove(0), curr!(meta, self.input)),
+            ]
+        });
+    }
+}
@@ -1,13 +1,16 @@
 use std::marker::PhantomData;
 
 use halo2_proofs::arithmetic::FieldExt;
+use halo2_proofs::plonk::Advice;
 use halo2_proofs::plonk::Column;
 use halo2_proofs::plonk::Fixed;
 use halo2_proofs::plonk::Instance;
 
+pub mod assign;
 pub mod config;
 
 pub const WASM_INPUT_FOREIGN_TABLE_KEY: &'static str = "wasm-input-helper-table";
+pub const INDEXED_INPUT_FOREIGN_TABLE_KEY: &'static str = "wasm-indexed-input-helper-table";
 
 #[derive(Clone)]
 pub struct WasmInputHelperTableConfig<F: FieldExt> {
@@ -15,3 +18,14 @@ pub struct WasmInputHelperTableConfig<F: FieldExt> {
     input: Column<Instance>,
     _mark: PhantomData<F>,
 }
+
+/// Indexed input words, the word of index `i` is at the row `i + 1`.
+///
+/// The lookups only make reads of the same index agree, the column is not bound to the instance:
+/// there is no hash gadget over it.
+#[derive(Clone)]
+pub struct IndexedInputHelperTableConfig<F: FieldExt> {
+    from_zero_index: Column<Fixed>,
+    input: Column<Advice>,
+    _mark: PhantomData<F>,
+}
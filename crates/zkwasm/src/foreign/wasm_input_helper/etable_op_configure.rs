@@ -11,6 +11,7 @@ use specs::mtable::LocationType;
 use specs::mtable::VarType;
 use specs::step::StepInfo;
 
+use super::circuits::INDEXED_INPUT_FOREIGN_TABLE_KEY;
 use super::circuits::WASM_INPUT_FOREIGN_TABLE_KEY;
 
 use crate::circuits::cell::AllocatedBitCell;
@@ -37,13 +38,17 @@ pub struct ETableWasmInputHelperTableConfig<F: FieldExt> {
     plugin_index: usize,
     is_wasm_input_op: AllocatedBitCell<F>,
     is_wasm_output_op: AllocatedBitCell<F>,
+    is_wasm_indexed_input_op: AllocatedBitCell<F>,
 
     is_public: AllocatedBitCell<F>,
     value: AllocatedU64Cell<F>,
+    indexed_input_index: AllocatedUnlimitedCell<F>,
 
     enable_input_table_lookup: AllocatedBitCell<F>,
     public_input_index_for_lookup: AllocatedUnlimitedCell<F>,
     value_for_lookup: AllocatedUnlimitedCell<F>,
+    indexed_input_index_for_lookup: AllocatedUnlimitedCell<F>,
+    indexed_input_value_for_lookup: AllocatedUnlimitedCell<F>,
 
     lookup_read_stack: AllocatedMemoryTableLookupReadCell<F>,
     lookup_write_stack: AllocatedMemoryTableLookupWriteCell<F>,
@@ -75,13 +80,17 @@ impl<F: FieldExt> EventTableForeignCallConfigBuilder<F>
 
         let is_wasm_input_op: AllocatedBitCell<F> = allocator.alloc_bit_cell();
         let is_wasm_output_op: AllocatedBitCell<F> = allocator.alloc_bit_cell();
+        let is_wasm_indexed_input_op: AllocatedBitCell<F> = allocator.alloc_bit_cell();
 
         let is_public = allocator.alloc_bit_cell();
         let value = allocator.alloc_u64_cell();
+        let indexed_input_index = allocator.alloc_unlimited_cell();
 
         let enable_input_table_lookup = allocator.alloc_bit_cell();
         let public_input_index_for_lookup = lookup_cells.next().unwrap();
         let value_for_lookup = lookup_cells.next().unwrap();
+        let indexed_input_index_for_lookup = lookup_cells.next().unwrap();
+        let indexed_input_value_for_lookup = lookup_cells.next().unwrap();
 
         let lookup_read_stack = allocator.alloc_memory_table_lookup_read_cell(
             "wasm input stack read",
@@ -89,10 +98,11 @@ impl<F: FieldExt> EventTableForeignCallConfigBuilder<F>
             eid,
             move |____| constant_from!(LocationType::Stack as u64),
             move |meta| sp.expr(meta) + constant_from!(1),
-            move |meta| is_wasm_input_op.expr(meta),
+            move |meta| is_wasm_input_op.expr(meta) + is_wasm_indexed_input_op.expr(meta),
             move |meta| {
                 is_public.expr(meta) * is_wasm_input_op.expr(meta)
                     + value.u64_cell.expr(meta) * is_wasm_output_op.expr(meta)
+                    + indexed_input_index.expr(meta) * is_wasm_indexed_input_op.expr(meta)
             },
             move |____| constant_from!(1),
         );
@@ -104,16 +114,26 @@ impl<F: FieldExt> EventTableForeignCallConfigBuilder<F>
             move |meta| sp.expr(meta) + constant_from!(1),
             move |____| constant_from!(0),
             move |meta| value.u64_cell.expr(meta),
-            move |meta| is_wasm_input_op.expr(meta),
+            move |meta| is_wasm_input_op.expr(meta) + is_wasm_indexed_input_op.expr(meta),
         );
 
         constraint_builder.push(
             "wasm_input_helper: sel",
             Box::new(move |meta| {
-                vec![is_wasm_input_op.expr(meta) + is_wasm_output_op.expr(meta) - constant_from!(1)]
+                vec![
+                    is_wasm_input_op.expr(meta)
+                        + is_wasm_output_op.expr(meta)
+                        + is_wasm_indexed_input_op.expr(meta)
+                        - constant_from!(1),
+                ]
             }),
         );
 
+        constraint_builder.push(
+            "wasm_input_helper: indexed input is not public",
+            Box::new(move |meta| vec![is_wasm_indexed_input_op.expr(meta) * is_public.expr(meta)]),
+        );
+
         constraint_builder.push(
             "enable lookup bit",
             Box::new(move |meta| {
@@ -147,15 +167,45 @@ impl<F: FieldExt> EventTableForeignCallConfigBuilder<F>
             }),
         );
 
+        // The word of index `i` is at the row `i + 1` of the indexed input table, the row 0
+        // matches the lookup of other steps.
+        constraint_builder.push(
+            "wasm indexed input lookup aux",
+            Box::new(move |meta| {
+                vec![
+                    is_wasm_indexed_input_op.expr(meta)
+                        * (indexed_input_index.expr(meta) + constant_from!(1))
+                        - indexed_input_index_for_lookup.expr(meta),
+                    is_wasm_indexed_input_op.expr(meta) * value.expr(meta)
+                        - indexed_input_value_for_lookup.expr(meta),
+                ]
+            }),
+        );
+
+        constraint_builder.lookup(
+            INDEXED_INPUT_FOREIGN_TABLE_KEY,
+            "lookup indexed input table",
+            Box::new(move |meta| {
+                vec![
+                    indexed_input_index_for_lookup.expr(meta),
+                    indexed_input_value_for_lookup.expr(meta),
+                ]
+            }),
+        );
+
         Box::new(ETableWasmInputHelperTableConfig {
             plugin_index: self.index,
             is_wasm_input_op,
             is_wasm_output_op,
+            is_wasm_indexed_input_op,
             is_public,
             value,
+            indexed_input_index,
             enable_input_table_lookup,
             public_input_index_for_lookup,
             value_for_lookup,
+            indexed_input_index_for_lookup,
+            indexed_input_value_for_lookup,
             lookup_read_stack,
             lookup_write_stack,
         })
@@ -172,6 +222,7 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ETableWasmInputHelperTableConfig
             &(BigUint::from(OpcodeClass::ForeignPluginStart as u64 + self.plugin_index as u64)
                 << OPCODE_CLASS_SHIFT)
         ) + self.is_wasm_output_op.expr(meta)
+            + constant_from!(Op::WasmIndexedInput as u64) * self.is_wasm_indexed_input_op.expr(meta)
     }
 
     fn assign(
@@ -231,6 +282,46 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ETableWasmInputHelperTableConfig
                         false,
                         ret_val.unwrap(),
                     )?;
+                } else if *op_index_in_plugin == Op::WasmIndexedInput as usize {
+                    let arg_type: VarType = (*signature.params.get(0).unwrap()).into();
+                    let ret_type: VarType = signature.return_type.unwrap().into();
+
+                    assert_eq!(args.len(), 1);
+                    assert_eq!(arg_type, VarType::I32);
+                    assert_eq!(ret_type, VarType::I64);
+
+                    let index = *args.get(0).unwrap();
+                    let value = ret_val.unwrap();
+
+                    self.is_wasm_indexed_input_op.assign_bool(ctx, true)?;
+                    self.value.assign(ctx, value)?;
+                    self.indexed_input_index.assign(ctx, index.into())?;
+
+                    self.indexed_input_index_for_lookup
+                        .assign(ctx, (index + 1).into())?;
+                    self.indexed_input_value_for_lookup
+                        .assign(ctx, value.into())?;
+
+                    self.lookup_read_stack.assign(
+                        ctx,
+                        entry.memory_rw_entires[0].start_eid,
+                        step.current.eid,
+                        entry.memory_rw_entires[0].end_eid,
+                        step.current.sp + 1,
+                        LocationType::Stack,
+                        true,
+                        index,
+                    )?;
+
+                    self.lookup_write_stack.assign(
+                        ctx,
+                        step.current.eid,
+                        entry.memory_rw_entires[1].end_eid,
+                        step.current.sp + 1,
+                        LocationType::Stack,
+                        false,
+                        value,
+                    )?;
                 } else {
                     let arg_type: VarType = (*signature.params.get(0).unwrap()).into();
                     assert_eq!(args.len(), 1);
@@ -266,7 +357,7 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ETableWasmInputHelperTableConfig
     }
 
     fn mops(&self, meta: &mut VirtualCells<'_, F>) -> Option<Expression<F>> {
-        Some(self.is_wasm_input_op.expr(meta))
+        Some(self.is_wasm_input_op.expr(meta) + self.is_wasm_indexed_input_op.expr(meta))
     }
 
     fn memory_writing_ops(&self, entry: &EventTableEntry) -> u32 {
@@ -278,7 +369,9 @@ impl<F: FieldExt> EventTableOpcodeConfig<F> for ETableWasmInputHelperTableConfig
             } => {
                 assert_eq!(*plugin, HostPlugin::HostInput);
 
-                if *op_index_in_plugin == Op::WasmInput as usize {
+                if *op_index_in_plugin == Op::WasmInput as usize
+                    || *op_index_in_plugin == Op::WasmIndexedInput as usize
+                {
                     1
                 } else {
                     0
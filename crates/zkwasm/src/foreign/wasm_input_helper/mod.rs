@@ -6,4 +6,5 @@ pub mod test;
 enum Op {
     WasmInput = 0,
     WasmOutput = 1,
+    WasmIndexedInput = 2,
 }
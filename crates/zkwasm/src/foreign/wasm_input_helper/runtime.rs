@@ -3,8 +3,10 @@ use std::rc::Rc;
 
 use specs::host_function::HostPlugin;
 use specs::types::ValueType;
+use wasmi::Trap;
 
 use crate::runtime::host::host_env::HostEnv;
+use crate::runtime::host::host_trap;
 use crate::runtime::host::ForeignContext;
 use crate::runtime::host::ForeignStatics;
 use crate::runtime::monitor::observer::Observer;
@@ -14,15 +16,21 @@ use super::Op;
 pub struct Context {
     pub public_inputs: Vec<u64>,
     pub private_inputs: VecDeque<u64>,
+    pub indexed_inputs: Vec<u64>,
     pub instance: Vec<u64>,
     pub output: Vec<u64>,
 }
 
 impl Context {
-    pub fn new(public_inputs: Vec<u64>, private_inputs: Vec<u64>) -> Self {
+    pub fn new(
+        public_inputs: Vec<u64>,
+        private_inputs: Vec<u64>,
+        indexed_inputs: Vec<u64>,
+    ) -> Self {
         Context {
             public_inputs,
             private_inputs: private_inputs.into(),
+            indexed_inputs,
             instance: vec![],
             output: vec![],
         }
@@ -68,6 +76,19 @@ impl Context {
     pub fn wasm_output(&mut self, value: u64) {
         self.push_output(value);
     }
+
+    pub fn wasm_indexed_input(&mut self, index: u32) -> Result<u64, Trap> {
+        self.indexed_inputs
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| {
+                host_trap(format!(
+                    "failed to read indexed input {}, only {} words are given",
+                    index,
+                    self.indexed_inputs.len()
+                ))
+            })
+    }
 }
 
 impl ForeignContext for Context {
@@ -88,6 +109,7 @@ pub fn register_wasm_input_foreign(
     env: &mut HostEnv,
     public_inputs: Vec<u64>,
     private_inputs: Vec<u64>,
+    indexed_inputs: Vec<u64>,
 ) {
     let wasm_input = Rc::new(
         |_observer: &Observer, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
@@ -110,10 +132,20 @@ pub fn register_wasm_input_foreign(
         },
     );
 
+    let wasm_indexed_input = Rc::new(
+        |_observer: &Observer, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+            let context = context.downcast_mut::<Context>().unwrap();
+            let index: u32 = args.nth(0);
+            let input = context.wasm_indexed_input(index)?;
+
+            Ok(Some(wasmi::RuntimeValue::I64(input as i64)))
+        },
+    );
+
     env.internal_env.register_plugin(
         "wasm input plugin",
         HostPlugin::HostInput,
-        Box::new(Context::new(public_inputs, private_inputs)),
+        Box::new(Context::new(public_inputs, private_inputs, indexed_inputs)),
     );
 
     env.internal_env.register_function(
@@ -137,4 +169,15 @@ pub fn register_wasm_input_foreign(
         Op::WasmOutput as usize,
         wasm_output,
     );
+
+    env.internal_env.register_fallible_function(
+        "wasm_indexed_input",
+        specs::host_function::Signature {
+            params: vec![ValueType::I32],
+            return_type: Some(ValueType::I64),
+        },
+        HostPlugin::HostInput,
+        Op::WasmIndexedInput as usize,
+        wasm_indexed_input,
+    );
 }
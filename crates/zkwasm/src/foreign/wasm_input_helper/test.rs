@@ -1,5 +1,17 @@
 #[cfg(test)]
 mod tests {
+    use anyhow::Result;
+    use halo2_proofs::pairing::bn256::Fr;
+    use specs::TraceBackend;
+    use wasmi::RuntimeValue;
+
+    use crate::loader::slice::Slices;
+    use crate::loader::ZkWasmLoader;
+    use crate::runtime::host::default_env::DefaultHostEnvBuilder;
+    use crate::runtime::host::default_env::ExecutionArg;
+    use crate::runtime::host::HostEnvBuilder;
+    use crate::runtime::monitor::table_monitor::TableMonitor;
+    use crate::runtime::ExecutionResult;
     use crate::test::test_circuit_with_env;
 
     #[test]
@@ -66,4 +78,71 @@ mod tests {
         )
         .unwrap();
     }
+
+    /// Outputs the sum of the indexed inputs `index`, 0 and `index` again.
+    fn run_indexed_input(
+        index: u32,
+        indexed_inputs: Vec<u64>,
+    ) -> Result<(ExecutionResult<RuntimeValue>, TableMonitor)> {
+        let textual_repr = format!(
+            r#"
+        (module
+            (import "env" "wasm_indexed_input" (func $wasm_indexed_input (param i32) (result i64)))
+            (import "env" "wasm_output" (func $wasm_output (param i64)))
+            (export "zkwasm" (func $zkwasm))
+            (func $zkwasm
+                (call $wasm_output
+                  (i64.add
+                    (i64.add
+                      (call $wasm_indexed_input (i32.const {}))
+                      (call $wasm_indexed_input (i32.const 0)))
+                    (call $wasm_indexed_input (i32.const {}))))
+            )
+        )
+        "#,
+            index, index
+        );
+
+        let wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
+        let module = ZkWasmLoader::parse_module(&wasm)?;
+
+        let env = DefaultHostEnvBuilder.create_env(
+            18,
+            ExecutionArg {
+                public_inputs: vec![],
+                private_inputs: vec![],
+                context_inputs: vec![],
+                indexed_inputs,
+            },
+        );
+        let mut monitor = TableMonitor::new(18, &vec![], TraceBackend::Memory, &env);
+        let mut loader = ZkWasmLoader::new(18, env)?;
+        loader.set_entry("zkwasm".to_string());
+
+        let runner = loader.compile(&module, &mut monitor)?;
+        let result = loader.run(runner, &mut monitor)?;
+
+        Ok((result, monitor))
+    }
+
+    #[test]
+    fn test_foreign_wasm_indexed_input() {
+        let (result, monitor) = run_indexed_input(2, vec![1, 10, 100]).unwrap();
+
+        // Indexed inputs are private, only the output is in the instance.
+        assert_eq!(result.public_inputs_and_outputs, vec![201]);
+
+        let instances: Vec<Fr> = result.public_inputs_and_outputs();
+        Slices::new(18, monitor.into_tables())
+            .unwrap()
+            .mock_test_all(instances)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_foreign_wasm_indexed_input_out_of_range() {
+        assert!(run_indexed_input(3, vec![1, 10, 100]).is_err());
+        assert!(run_indexed_input(u32::MAX, vec![1, 10, 100]).is_err());
+        assert!(run_indexed_input(0, vec![]).is_err());
+    }
 }
@@ -10,13 +10,11 @@ pub mod foreign;
 pub mod loader;
 pub mod runtime;
 
-mod profile;
+pub mod profile;
 
 #[cfg(test)]
 pub mod test;
 
-#[macro_use]
-extern crate lazy_static;
 extern crate downcast_rs;
 
 pub extern crate halo2_proofs;
@@ -5,6 +5,8 @@ pub enum PreCheckErr {
     ZkmainNotExists,
     ZkmainIsNotFunction,
     // ZkmainTypeNotMatch,
+    UnsupportedInstruction { fid: u32, instruction: String },
+    UnsupportedValueType(String),
 }
 
 #[derive(Debug)]
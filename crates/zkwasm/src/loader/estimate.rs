@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::circuits::compute_slice_capability;
+use crate::circuits::config::MAX_K;
+use crate::circuits::config::MIN_SMALL_K;
+use crate::circuits::image_table::compute_maximal_pages;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::host::ForeignStatics;
+use crate::runtime::host::HostEnvBuilder;
+use crate::runtime::monitor::statistic_monitor::StatisticMonitor;
+use crate::runtime::monitor::WasmiMonitor;
+
+use super::Module;
+use super::ZkWasmLoader;
+
+/// A rough linear model of proving cost per row of the circuit, used to compare candidate Ks.
+#[derive(Clone, Copy, Debug)]
+pub struct ProvingCostModel {
+    pub seconds_per_row: f64,
+    pub bytes_per_row: u64,
+}
+
+impl Default for ProvingCostModel {
+    // Roughly 2 minutes and 48 GiB for a K = 22 slice on a CPU prover.
+    fn default() -> Self {
+        Self {
+            seconds_per_row: 120.0 / (1 << 22) as f64,
+            bytes_per_row: 12 * 1024,
+        }
+    }
+}
+
+/// What a dry run of the image consumed, independent of K.
+#[derive(Clone, Debug, Serialize)]
+pub struct ExecutionSummary {
+    /// Instructions traced in the event table.
+    pub instructions: usize,
+    /// Maximal memory pages allocated during the execution.
+    pub maximal_memory_pages: u32,
+    /// Used rounds of each host plugin.
+    pub host_used_rounds: BTreeMap<String, usize>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct HostOpUsage {
+    pub used_round: usize,
+    pub max_round: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct KEstimation {
+    pub k: u32,
+    pub slice_capability: u32,
+    pub slices: usize,
+    pub maximal_pages: u32,
+    /// Pages left after the maximal memory usage of the execution, negative if it doesn't fit.
+    pub page_headroom: i64,
+    pub host_ops: BTreeMap<String, HostOpUsage>,
+    /// Estimated seconds to prove all slices.
+    pub estimated_prove_seconds: f64,
+    /// Estimated peak memory in bytes to prove a slice.
+    pub estimated_memory_bytes: u64,
+}
+
+impl KEstimation {
+    pub fn is_feasible(&self) -> bool {
+        (cfg!(feature = "continuation") || self.slices == 1)
+            && self.page_headroom >= 0
+            && self
+                .host_ops
+                .values()
+                .all(|usage| usage.used_round <= usage.max_round)
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Estimation {
+    pub summary: ExecutionSummary,
+    pub candidates: Vec<KEstimation>,
+    /// The feasible K with the smallest estimated proving time.
+    pub recommended: Option<u32>,
+}
+
+impl ExecutionSummary {
+    /// Estimate the cost of proving the execution with a circuit of size `k`. `host_statics` come
+    /// from a host env created with the same `k`.
+    pub fn estimate(
+        &self,
+        k: u32,
+        host_statics: &BTreeMap<String, ForeignStatics>,
+        cost_model: &ProvingCostModel,
+    ) -> KEstimation {
+        let slice_capability = compute_slice_capability(k);
+        let slices = self.instructions.div_ceil(slice_capability as usize).max(1);
+        let maximal_pages = compute_maximal_pages(k);
+
+        let host_ops = host_statics
+            .iter()
+            .map(|(name, statics)| {
+                (
+                    name.clone(),
+                    HostOpUsage {
+                        used_round: self.host_used_rounds.get(name).cloned().unwrap_or(0),
+                        max_round: statics.max_round,
+                    },
+                )
+            })
+            .collect();
+
+        let rows = 1u64 << k;
+
+        KEstimation {
+            k,
+            slice_capability,
+            slices,
+            maximal_pages,
+            page_headroom: maximal_pages as i64 - self.maximal_memory_pages as i64,
+            host_ops,
+            estimated_prove_seconds: cost_model.seconds_per_row * (rows as f64) * slices as f64,
+            estimated_memory_bytes: cost_model.bytes_per_row * rows,
+        }
+    }
+}
+
+impl Estimation {
+    fn new(summary: ExecutionSummary, candidates: Vec<KEstimation>) -> Self {
+        let recommended = candidates
+            .iter()
+            .filter(|candidate| candidate.is_feasible())
+            .min_by(|a, b| {
+                a.estimated_prove_seconds
+                    .total_cmp(&b.estimated_prove_seconds)
+                    .then(a.k.cmp(&b.k))
+            })
+            .map(|candidate| candidate.k);
+
+        Self {
+            summary,
+            candidates,
+            recommended,
+        }
+    }
+}
+
+/// Dry run the image with `StatisticMonitor` and estimate the cost for each K in `candidates`.
+pub fn estimate_k(
+    env_builder: &dyn HostEnvBuilder,
+    module: &Module,
+    phantom_functions: &Vec<String>,
+    arg: ExecutionArg,
+    candidates: RangeInclusive<u32>,
+    cost_model: &ProvingCostModel,
+) -> Result<Estimation> {
+    let candidates = (*candidates.start()).max(MIN_SMALL_K)..=(*candidates.end()).min(MAX_K);
+    if candidates.is_empty() {
+        return Err(anyhow!(
+            "No candidate K in the supported range [{}, {}].",
+            MIN_SMALL_K,
+            MAX_K
+        ));
+    }
+
+    // Execute with the largest circuit so that host plugins are least likely to run out of rounds.
+    let execution_k = *candidates.end();
+
+    let summary = {
+        let env = env_builder.create_env(execution_k, arg);
+        let mut monitor = StatisticMonitor::new(phantom_functions, &env);
+
+        let loader = ZkWasmLoader::new(execution_k, env)?;
+        let runner = loader.compile(module, &mut monitor)?;
+        let result = loader.run(runner, &mut monitor)?;
+
+        let maximal_memory_pages = monitor.expose_observer().borrow().maximal_memory_pages;
+
+        ExecutionSummary {
+            instructions: result.guest_statics,
+            maximal_memory_pages,
+            host_used_rounds: result
+                .host_statics
+                .into_iter()
+                .map(|(name, statics)| (name, statics.used_round))
+                .collect(),
+        }
+    };
+
+    let candidates = candidates
+        .map(|k| {
+            let host_statics = env_builder
+                .create_env_without_value(k)
+                .external_env
+                .get_statics()
+                .into_iter()
+                .collect();
+
+            summary.estimate(k, &host_statics, cost_model)
+        })
+        .collect();
+
+    Ok(Estimation::new(summary, candidates))
+}
@@ -12,7 +12,9 @@ use wasmi::RuntimeValue;
 
 use crate::checksum::ImageCheckSum;
 
-use crate::circuits::config::init_zkwasm_runtime;
+use crate::circuits::config::is_supported_k;
+use crate::circuits::config::MAX_K;
+use crate::circuits::config::MIN_SMALL_K;
 use crate::error::BuildingCircuitError;
 use crate::loader::err::Error;
 use crate::loader::err::PreCheckErr;
@@ -31,6 +33,7 @@ pub use specs::TraceBackend;
 pub use wasmi::Module;
 
 mod err;
+pub mod estimate;
 pub mod slice;
 
 const ENTRY: &str = "zkmain";
@@ -47,7 +50,7 @@ pub struct ZkWasmLoader {
 
 impl ZkWasmLoader {
     pub fn parse_module(image: &Vec<u8>) -> Result<Module> {
-        fn precheck(_module: &Module) -> Result<()> {
+        fn precheck(module: &Module) -> Result<()> {
             #[allow(dead_code)]
             fn check_zkmain_exists(module: &Module) -> Result<()> {
                 use parity_wasm::elements::Internal;
@@ -64,13 +67,138 @@ impl ZkWasmLoader {
                 }
             }
 
+            // Floating-point values and instructions are not supported by the circuit.
+            fn check_instructions_supported(module: &Module) -> Result<()> {
+                use parity_wasm::elements::ImportCountType;
+                use parity_wasm::elements::Instruction;
+                use parity_wasm::elements::Type;
+                use parity_wasm::elements::ValueType;
+
+                let module = module.module();
+
+                let check_value_type = |value_type: &ValueType| match value_type {
+                    ValueType::I32 | ValueType::I64 => Ok(()),
+                    _ => Err(anyhow!(Error::PreCheck(PreCheckErr::UnsupportedValueType(
+                        value_type.to_string()
+                    )))),
+                };
+
+                if let Some(types) = module.type_section() {
+                    for Type::Function(ty) in types.types() {
+                        for value_type in ty.params().iter().chain(ty.results().iter()) {
+                            check_value_type(value_type)?;
+                        }
+                    }
+                }
+
+                if let Some(globals) = module.global_section() {
+                    for global in globals.entries() {
+                        check_value_type(&global.global_type().content_type())?;
+                    }
+                }
+
+                if let Some(code) = module.code_section() {
+                    let imported_functions = module.import_count(ImportCountType::Function) as u32;
+
+                    for (index, body) in code.bodies().iter().enumerate() {
+                        for local in body.locals() {
+                            check_value_type(&local.value_type())?;
+                        }
+
+                        for instruction in body.code().elements() {
+                            if matches!(
+                                instruction,
+                                Instruction::F32Load(..)
+                                    | Instruction::F64Load(..)
+                                    | Instruction::F32Store(..)
+                                    | Instruction::F64Store(..)
+                                    | Instruction::F32Const(..)
+                                    | Instruction::F64Const(..)
+                                    | Instruction::F32Eq
+                                    | Instruction::F32Ne
+                                    | Instruction::F32Lt
+                                    | Instruction::F32Gt
+                                    | Instruction::F32Le
+                                    | Instruction::F32Ge
+                                    | Instruction::F64Eq
+                                    | Instruction::F64Ne
+                                    | Instruction::F64Lt
+                                    | Instruction::F64Gt
+                                    | Instruction::F64Le
+                                    | Instruction::F64Ge
+                                    | Instruction::F32Abs
+                                    | Instruction::F32Neg
+                                    | Instruction::F32Ceil
+                                    | Instruction::F32Floor
+                                    | Instruction::F32Trunc
+                                    | Instruction::F32Nearest
+                                    | Instruction::F32Sqrt
+                                    | Instruction::F32Add
+                                    | Instruction::F32Sub
+                                    | Instruction::F32Mul
+                                    | Instruction::F32Div
+                                    | Instruction::F32Min
+                                    | Instruction::F32Max
+                                    | Instruction::F32Copysign
+                                    | Instruction::F64Abs
+                                    | Instruction::F64Neg
+                                    | Instruction::F64Ceil
+                                    | Instruction::F64Floor
+                                    | Instruction::F64Trunc
+                                    | Instruction::F64Nearest
+                                    | Instruction::F64Sqrt
+                                    | Instruction::F64Add
+                                    | Instruction::F64Sub
+                                    | Instruction::F64Mul
+                                    | Instruction::F64Div
+                                    | Instruction::F64Min
+                                    | Instruction::F64Max
+                                    | Instruction::F64Copysign
+                                    | Instruction::I32TruncSF32
+                                    | Instruction::I32TruncUF32
+                                    | Instruction::I32TruncSF64
+                                    | Instruction::I32TruncUF64
+                                    | Instruction::I64TruncSF32
+                                    | Instruction::I64TruncUF32
+                                    | Instruction::I64TruncSF64
+                                    | Instruction::I64TruncUF64
+                                    | Instruction::F32ConvertSI32
+                                    | Instruction::F32ConvertUI32
+                                    | Instruction::F32ConvertSI64
+                                    | Instruction::F32ConvertUI64
+                                    | Instruction::F32DemoteF64
+                                    | Instruction::F64ConvertSI32
+                                    | Instruction::F64ConvertUI32
+                                    | Instruction::F64ConvertSI64
+                                    | Instruction::F64ConvertUI64
+                                    | Instruction::F64PromoteF32
+                                    | Instruction::I32ReinterpretF32
+                                    | Instruction::I64ReinterpretF64
+                                    | Instruction::F32ReinterpretI32
+                                    | Instruction::F64ReinterpretI64
+                            ) {
+                                return Err(anyhow!(Error::PreCheck(
+                                    PreCheckErr::UnsupportedInstruction {
+                                        fid: imported_functions + index as u32,
+                                        instruction: instruction.to_string(),
+                                    }
+                                )));
+                            }
+                        }
+                    }
+                }
+
+                Ok(())
+            }
+
             #[cfg(not(test))]
-            check_zkmain_exists(_module)?;
+            check_zkmain_exists(module)?;
+            check_instructions_supported(module)?;
             // TODO: check the signature of zkmain function.
             // TODO: check the relation between maximal pages and K.
-            // TODO: check the instructions of phantom functions.
+            // The instructions of phantom functions are checked when the module is registered to
+            // the tracer, which knows the phantom function patterns and host functions.
             // TODO: check phantom functions exists.
-            // TODO: check if instructions are supported.
 
             Ok(())
         }
@@ -105,15 +233,19 @@ impl ZkWasmLoader {
     /// - k: the size of circuit
     /// - env: HostEnv for wasmi
     pub fn new(k: u32, env: HostEnv) -> Result<Self> {
-        let loader = Self {
+        if !is_supported_k(k) {
+            return Err(anyhow!(BuildingCircuitError::UnsupportedK(
+                k,
+                MIN_SMALL_K,
+                MAX_K
+            )));
+        }
+
+        Ok(Self {
             k,
             entry: ENTRY.to_string(),
             env,
-        };
-
-        loader.init_env()?;
-
-        Ok(loader)
+        })
     }
 
     #[cfg(test)]
@@ -140,12 +272,6 @@ impl ZkWasmLoader {
         // Slices::new(self.k, execution_result.tables)
     }
 
-    fn init_env(&self) -> Result<()> {
-        init_zkwasm_runtime(self.k);
-
-        Ok(())
-    }
-
     /// Compute the checksum of the compiled wasm image.
     pub fn checksum<C: CurveAffine>(
         &self,
@@ -14,6 +14,7 @@ use specs::Tables;
 use std::collections::VecDeque;
 use std::sync::Arc;
 
+use crate::circuits::diagnostics::MockTestError;
 use crate::circuits::ZkWasmCircuit;
 use crate::error::BuildingCircuitError;
 use crate::runtime::state::UpdateInitMemoryTable;
@@ -67,21 +68,15 @@ impl<F: FieldExt> Slices<F> {
     }
 
     pub fn mock_test_all(self, instances: Vec<F>) -> anyhow::Result<()> {
-        use halo2_proofs::dev::MockProver;
+        for (index, slice) in self.into_iter().enumerate() {
+            let failures = slice?.mock_verify(instances.clone())?;
 
-        let k = self.k;
-        let mut iter = self.into_iter();
-
-        while let Some(slice) = iter.next() {
-            match slice? {
-                ZkWasmCircuit::Ongoing(circuit) => {
-                    let prover = MockProver::run(k, &circuit, vec![instances.clone()])?;
-                    assert_eq!(prover.verify(), Ok(()));
-                }
-                ZkWasmCircuit::LastSliceCircuit(circuit) => {
-                    let prover = MockProver::run(k, &circuit, vec![instances.clone()])?;
-                    assert_eq!(prover.verify(), Ok(()));
+            if !failures.is_empty() {
+                return Err(MockTestError {
+                    slice: Some(index),
+                    failures,
                 }
+                .into());
             }
         }
 
@@ -97,20 +92,31 @@ impl<F: FieldExt> Iterator for Slices<F> {
             return None;
         }
 
-        let etable = match self.etables.pop_front().unwrap() {
-            TableBackend::Memory(etable) => etable,
-            TableBackend::Json(path) => EventTable::read(&path).unwrap(),
+        let (etable, memory_writes) = match self.etables.pop_front().unwrap() {
+            TableBackend::Memory(etable) => (etable, None),
+            TableBackend::File(path) => {
+                match EventTable::read(&path)
+                    .and_then(|etable| Ok((etable, EventTable::read_memory_writes(&path)?)))
+                {
+                    Ok(etable) => etable,
+                    Err(err) => return Some(Err(err.into())),
+                }
+            }
         };
 
-        let post_imtable = Arc::new(self.imtable.update_init_memory_table(&etable));
+        let post_imtable = Arc::new(match memory_writes {
+            // Recorded while tracing.
+            Some(memory_writes) => self.imtable.update(&memory_writes),
+            None => self.imtable.update_init_memory_table(&etable),
+        });
         let post_initialization_state = Arc::new({
             let next_event_entry = if let Some(next_event_table) = self.etables.front() {
                 match next_event_table {
                     TableBackend::Memory(etable) => etable.entries().first().cloned(),
-                    TableBackend::Json(path) => {
-                        let etable = EventTable::read(&path).unwrap();
-                        etable.entries().first().cloned()
-                    }
+                    TableBackend::File(path) => match EventTable::read_first_entry(&path) {
+                        Ok(entry) => entry,
+                        Err(err) => return Some(Err(err.into())),
+                    },
                 }
             } else {
                 None
@@ -125,21 +131,27 @@ impl<F: FieldExt> Iterator for Slices<F> {
 
         let frame_table = match self.frame_table.pop_front().unwrap() {
             TableBackend::Memory(frame_table) => frame_table,
-            TableBackend::Json(path) => FrameTable::read(&path).unwrap(),
+            TableBackend::File(path) => match FrameTable::read(&path) {
+                Ok(frame_table) => frame_table,
+                Err(err) => return Some(Err(err.into())),
+            },
         }
         .into();
 
-        let post_inherited_frame_table = self.frame_table.front().map_or(
-            Arc::new(InheritedFrameTable::default()),
-            |frame_table| {
+        let post_inherited_frame_table = match self.frame_table.front() {
+            None => Arc::new(InheritedFrameTable::default()),
+            Some(frame_table) => {
                 let post_inherited_frame_table = match frame_table {
                     TableBackend::Memory(frame_table) => frame_table.inherited.clone(),
-                    TableBackend::Json(path) => FrameTable::read(&path).unwrap().inherited,
+                    TableBackend::File(path) => match FrameTable::read(&path) {
+                        Ok(frame_table) => frame_table.inherited,
+                        Err(err) => return Some(Err(err.into())),
+                    },
                 };
 
                 Arc::new((*post_inherited_frame_table).clone().try_into().unwrap())
-            },
-        );
+            }
+        };
 
         let slice = Slice {
             itable: self.itable.clone(),
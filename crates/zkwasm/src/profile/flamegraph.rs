@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io;
+use std::io::Write;
+
+use serde::Serialize;
+use specs::etable::EventTableEntry;
+use specs::etable::EventTableReader;
+use specs::step::StepInfo;
+use specs::trace_file::TraceFileError;
+use specs::TableBackend;
+use specs::Tables;
+
+use crate::circuits::etable::EVENT_TABLE_ENTRY_ROWS;
+use crate::runtime::memory_event_of_step;
+
+/// The cost a traced instruction contributes to its frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameWeight {
+    EtableRows,
+    MemoryOps,
+}
+
+impl FrameWeight {
+    fn of(&self, entry: &EventTableEntry) -> usize {
+        match self {
+            FrameWeight::EtableRows => EVENT_TABLE_ENTRY_ROWS as usize,
+            FrameWeight::MemoryOps => memory_event_of_step(entry).len(),
+        }
+    }
+}
+
+/// Replays the call structure of the event table, keeping the stack of guest functions.
+struct CallStack {
+    frames: Vec<u32>,
+}
+
+impl CallStack {
+    /// Returns the stack the entry executes in.
+    fn enter(&mut self, entry: &EventTableEntry) -> &[u32] {
+        // The start and entry functions are called by the host rather than by a traced call.
+        if self.frames.last() != Some(&entry.fid) {
+            self.frames.push(entry.fid);
+        }
+
+        &self.frames
+    }
+
+    fn leave(&mut self, entry: &EventTableEntry) {
+        match &entry.step_info {
+            StepInfo::Call { index } => self.frames.push(*index),
+            StepInfo::CallIndirect { func_index, .. } => self.frames.push(*func_index),
+            StepInfo::Return { .. } => {
+                self.frames.pop();
+            }
+            _ => (),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ChromeTraceEvent<'a> {
+    name: &'a str,
+    ph: &'static str,
+    ts: usize,
+    pid: u32,
+    tid: u32,
+}
+
+pub trait FlameGraph {
+    /// Walk the event tables in execution order, calling `f` with the stack of each instruction.
+    /// Fails if an event table on the file backend cannot be read.
+    fn walk_call_stacks(
+        &self,
+        f: impl FnMut(&EventTableEntry, &[u32]),
+    ) -> Result<(), TraceFileError>;
+
+    /// Write the folded stacks consumed by `flamegraph.pl` and `inferno`, one line per distinct
+    /// stack with its accumulated weight.
+    fn write_folded_stacks<W: Write>(
+        &self,
+        function_names: &HashMap<u32, String>,
+        weight: FrameWeight,
+        writer: &mut W,
+    ) -> Result<(), TraceFileError> {
+        let mut stacks = BTreeMap::<Vec<u32>, usize>::new();
+
+        self.walk_call_stacks(|entry, stack| {
+            *stacks.entry(stack.to_vec()).or_default() += weight.of(entry);
+        })?;
+
+        for (stack, weight) in stacks {
+            if weight == 0 {
+                continue;
+            }
+
+            let stack = stack
+                .iter()
+                .map(|fid| function_name(function_names, *fid))
+                .collect::<Vec<_>>()
+                .join(";");
+
+            writeln!(writer, "{} {}", stack, weight)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a Chrome trace-event JSON, where the timestamp is the accumulated weight rather than
+    /// wall-clock time.
+    fn write_chrome_trace<W: Write>(
+        &self,
+        function_names: &HashMap<u32, String>,
+        weight: FrameWeight,
+        writer: &mut W,
+    ) -> Result<(), TraceFileError> {
+        let mut events = vec![];
+        let mut opened: Vec<u32> = vec![];
+        let mut ts = 0;
+
+        self.walk_call_stacks(|entry, stack| {
+            let common = opened
+                .iter()
+                .zip(stack.iter())
+                .take_while(|(a, b)| a == b)
+                .count();
+
+            while opened.len() > common {
+                let fid = opened.pop().unwrap();
+                events.push((fid, "E", ts));
+            }
+            for fid in &stack[common..] {
+                opened.push(*fid);
+                events.push((*fid, "B", ts));
+            }
+
+            ts += weight.of(entry);
+        })?;
+
+        while let Some(fid) = opened.pop() {
+            events.push((fid, "E", ts));
+        }
+
+        let names = events
+            .iter()
+            .map(|(fid, _, _)| function_name(function_names, *fid))
+            .collect::<Vec<_>>();
+        let events = events
+            .iter()
+            .zip(names.iter())
+            .map(|((_, ph, ts), name)| ChromeTraceEvent {
+                name,
+                ph: *ph,
+                ts: *ts,
+                pid: 0,
+                tid: 0,
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_writer(writer, &events).map_err(io::Error::from)?;
+
+        Ok(())
+    }
+}
+
+fn function_name(function_names: &HashMap<u32, String>, fid: u32) -> String {
+    function_names
+        .get(&fid)
+        .cloned()
+        .unwrap_or_else(|| format!("func[{}]", fid))
+}
+
+impl FlameGraph for Tables {
+    fn walk_call_stacks(
+        &self,
+        mut f: impl FnMut(&EventTableEntry, &[u32]),
+    ) -> Result<(), TraceFileError> {
+        let mut call_stack = CallStack { frames: vec![] };
+
+        let mut walk_entry = |entry: &EventTableEntry| {
+            f(entry, call_stack.enter(entry));
+            call_stack.leave(entry);
+        };
+
+        for etable in &self.execution_tables.etable {
+            match etable {
+                TableBackend::Memory(etable) => etable.entries().iter().for_each(&mut walk_entry),
+                TableBackend::File(path) => {
+                    for entry in EventTableReader::open(path)? {
+                        walk_entry(&entry?);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,110 +1,142 @@
-use log::debug;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::ops::AddAssign;
+
+use serde::Serialize;
+use specs::etable::EventTableEntry;
+use specs::etable::EventTableReader;
 use specs::itable::OpcodeClass;
+use specs::itable::UnaryOp;
 use specs::mtable::AccessType;
-use specs::mtable::MemoryTableEntry;
+use specs::step::StepInfo;
+use specs::trace_file::TraceFileError;
+use specs::TableBackend;
 use specs::Tables;
-use std::collections::BTreeMap;
-use std::fmt::Debug;
 
+use crate::circuits::etable::EVENT_TABLE_ENTRY_ROWS;
 use crate::runtime::memory_event_of_step;
 
-pub trait InstructionStatistic {
-    fn profile_instruction(&self);
+/// Rows or entries that a group of instructions contributes to each table.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TableCost {
+    pub instructions: usize,
+    pub etable_rows: usize,
+    pub mtable_entries: usize,
+    pub mtable_reads: usize,
+    pub mtable_writes: usize,
+    pub bit_table_ops: usize,
+    pub frame_table_entries: usize,
+    pub external_host_call_rows: usize,
 }
 
-impl InstructionStatistic for Tables {
-    fn profile_instruction(&self) {
-        struct Counter {
-            counter: usize,
-            mentries: Vec<MemoryTableEntry>,
+impl AddAssign<&TableCost> for TableCost {
+    fn add_assign(&mut self, rhs: &TableCost) {
+        self.instructions += rhs.instructions;
+        self.etable_rows += rhs.etable_rows;
+        self.mtable_entries += rhs.mtable_entries;
+        self.mtable_reads += rhs.mtable_reads;
+        self.mtable_writes += rhs.mtable_writes;
+        self.bit_table_ops += rhs.bit_table_ops;
+        self.frame_table_entries += rhs.frame_table_entries;
+        self.external_host_call_rows += rhs.external_host_call_rows;
+    }
+}
+
+impl From<&EventTableEntry> for TableCost {
+    fn from(entry: &EventTableEntry) -> Self {
+        let mentries = memory_event_of_step(entry);
+
+        TableCost {
+            instructions: 1,
+            etable_rows: EVENT_TABLE_ENTRY_ROWS as usize,
+            mtable_entries: mentries.len(),
+            mtable_reads: mentries
+                .iter()
+                .filter(|entry| entry.atype == AccessType::Read)
+                .count(),
+            mtable_writes: mentries
+                .iter()
+                .filter(|entry| entry.atype == AccessType::Write)
+                .count(),
+            bit_table_ops: match &entry.step_info {
+                StepInfo::I32BinBitOp { .. }
+                | StepInfo::I64BinBitOp { .. }
+                | StepInfo::UnaryOp {
+                    class: UnaryOp::Popcnt,
+                    ..
+                } => 1,
+                _ => 0,
+            },
+            frame_table_entries: match &entry.step_info {
+                StepInfo::Call { .. } | StepInfo::CallIndirect { .. } => 1,
+                _ => 0,
+            },
+            external_host_call_rows: match &entry.step_info {
+                StepInfo::ExternalHostCall { .. } => 1,
+                StepInfo::ExternalHostCallBuffer { values, .. } => values.len(),
+                _ => 0,
+            },
         }
+    }
+}
 
-        let mut map = BTreeMap::<OpcodeClass, Counter>::new();
-        for entry in self.execution_tables.etable.entries() {
-            let mut mentries = memory_event_of_step(entry);
+/// Cost of an execution broken down by opcode class and by guest function.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct CostReport {
+    pub total: TableCost,
+    pub by_opcode_class: BTreeMap<String, TableCost>,
+    pub by_function: BTreeMap<String, TableCost>,
+}
 
-            let opcode = &entry
-                .get_instruction(&self.compilation_tables.itable)
-                .opcode;
+pub trait InstructionStatistic {
+    /// `function_names` maps function index to its name, see `Observer::function_names`. Fails if
+    /// an event table on the file backend cannot be read.
+    fn profile_instruction(
+        &self,
+        function_names: &HashMap<u32, String>,
+    ) -> Result<CostReport, TraceFileError>;
+}
 
-            if let Some(counter) = map.get_mut(&opcode.into()) {
-                counter.counter += 1;
-                counter.mentries.append(&mut mentries);
-            } else {
-                map.insert(
-                    opcode.into(),
-                    Counter {
-                        counter: 1,
-                        mentries,
-                    },
-                );
-            }
-        }
+impl InstructionStatistic for Tables {
+    fn profile_instruction(
+        &self,
+        function_names: &HashMap<u32, String>,
+    ) -> Result<CostReport, TraceFileError> {
+        let mut report = CostReport::default();
 
-        let total_mentries: usize = {
-            let a = map
-                .values()
-                .map(|counter| counter.mentries.len())
-                .collect::<Vec<_>>();
-            a.iter().sum()
-        };
+        let mut profile_entry = |entry: &EventTableEntry| {
+            let cost = TableCost::from(entry);
 
-        debug!(
-            "etable entries: {}",
-            self.execution_tables.etable.entries().len()
-        );
-        debug!("mtable entries: {}", total_mentries);
+            let opcode_class: OpcodeClass = (&entry
+                .get_instruction(&self.compilation_tables.itable)
+                .opcode)
+                .into();
+            let function_name = function_names
+                .get(&entry.fid)
+                .cloned()
+                .unwrap_or_else(|| format!("func[{}]", entry.fid));
 
-        struct Summary {
-            counter: usize,
-            mentries: usize,
-            total_mentries: usize,
-            write_counter: usize,
-            read_counter: usize,
-        }
+            report.total += &cost;
+            *report
+                .by_opcode_class
+                .entry(format!("{:?}", opcode_class))
+                .or_default() += &cost;
+            *report.by_function.entry(function_name).or_default() += &cost;
+        };
 
-        impl Debug for Summary {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                write!(
-                    f,
-                    "{{ counter: {}, mentries: {}({:.2}%), write: {}({:.2}%), read: {}({:.2}%) }}",
-                    self.counter,
-                    self.mentries,
-                    self.mentries as f64 / self.total_mentries as f64 * 100f64,
-                    self.write_counter,
-                    self.write_counter as f64 / self.total_mentries as f64 * 100f64,
-                    self.read_counter,
-                    self.read_counter as f64 / self.total_mentries as f64 * 100f64,
-                )
+        for etable in &self.execution_tables.etable {
+            match etable {
+                TableBackend::Memory(etable) => {
+                    etable.entries().iter().for_each(&mut profile_entry)
+                }
+                TableBackend::File(path) => {
+                    for entry in EventTableReader::open(path)? {
+                        profile_entry(&entry?);
+                    }
+                }
             }
         }
 
-        let summary = map
-            .into_iter()
-            .map(|(inst, counter)| {
-                (
-                    inst,
-                    Summary {
-                        counter: counter.counter,
-                        mentries: counter.mentries.len(),
-                        total_mentries,
-                        write_counter: counter
-                            .mentries
-                            .iter()
-                            .filter(|entry| entry.atype == AccessType::Write)
-                            .collect::<Vec<_>>()
-                            .len(),
-                        read_counter: counter
-                            .mentries
-                            .iter()
-                            .filter(|entry| entry.atype == AccessType::Read)
-                            .collect::<Vec<_>>()
-                            .len(),
-                    },
-                )
-            })
-            .collect::<BTreeMap<_, _>>();
-
-        debug!("{:?}", summary);
+        Ok(report)
     }
 }
@@ -1,8 +1,12 @@
-//use instruction_statistic::InstructionStatistic;
+use std::collections::HashMap;
+
+use instruction_statistic::InstructionStatistic;
+use log::debug;
 use specs::Tables;
 
+pub mod flamegraph;
 mod helper;
-// mod instruction_statistic;
+pub mod instruction_statistic;
 
 pub trait Profiler {
     fn profile_tables(&self);
@@ -10,6 +14,6 @@ pub trait Profiler {
 
 impl Profiler for Tables {
     fn profile_tables(&self) {
-        //self.profile_instruction();
+        debug!("{:?}", self.profile_instruction(&HashMap::new()));
     }
 }
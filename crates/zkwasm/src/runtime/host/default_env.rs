@@ -7,7 +7,7 @@ use super::host_env::HostEnv;
 use super::HostEnvBuilder;
 
 // TODO: remove me after refine tracer
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ExecutionArg {
     /// Public inputs for `wasm_input(1)`
     pub public_inputs: Vec<u64>,
@@ -15,6 +15,8 @@ pub struct ExecutionArg {
     pub private_inputs: Vec<u64>,
     /// Context inputs for `wasm_read_context()`
     pub context_inputs: Vec<u64>,
+    /// Private inputs for `wasm_indexed_input(index)`
+    pub indexed_inputs: Vec<u64>,
 }
 
 pub struct DefaultHostEnvBuilder;
@@ -22,7 +24,7 @@ pub struct DefaultHostEnvBuilder;
 impl HostEnvBuilder for DefaultHostEnvBuilder {
     fn create_env_without_value(&self, k: u32) -> HostEnv {
         let mut env = HostEnv::new(k);
-        register_wasm_input_foreign(&mut env, vec![], vec![]);
+        register_wasm_input_foreign(&mut env, vec![], vec![], vec![]);
         register_require_foreign(&mut env);
         register_log_foreign(&mut env);
         register_context_foreign(&mut env, vec![]);
@@ -33,7 +35,12 @@ impl HostEnvBuilder for DefaultHostEnvBuilder {
 
     fn create_env(&self, k: u32, arg: ExecutionArg) -> HostEnv {
         let mut env = HostEnv::new(k);
-        register_wasm_input_foreign(&mut env, arg.public_inputs, arg.private_inputs);
+        register_wasm_input_foreign(
+            &mut env,
+            arg.public_inputs,
+            arg.private_inputs,
+            arg.indexed_inputs,
+        );
         register_require_foreign(&mut env);
         register_log_foreign(&mut env);
         register_context_foreign(&mut env, arg.context_inputs);
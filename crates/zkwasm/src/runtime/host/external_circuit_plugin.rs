@@ -12,13 +12,14 @@ use crate::runtime::monitor::observer::Observer;
 use super::ForeignContext;
 use super::ForeignPlugin;
 use super::ForeignStatics;
+use super::HostFunctionResult;
 use super::MatchForeignOpSignature;
 
 pub(super) struct ForeignOp {
     pub op_index: usize,
     pub sig: ExternalHostCallSignature,
     pub plugin: Rc<ForeignPlugin>,
-    pub cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> Option<RuntimeValue>>,
+    pub cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> HostFunctionResult>,
 }
 
 pub struct ExternalCircuitEnv {
@@ -54,6 +55,25 @@ impl ExternalCircuitEnv {
         sig: ExternalHostCallSignature,
         plugin: Rc<ForeignPlugin>,
         cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> Option<RuntimeValue>>,
+    ) {
+        self.register_fallible_function(
+            name,
+            op_index,
+            sig,
+            plugin,
+            Rc::new(move |observer, context, args| Ok(cb(observer, context, args))),
+        )
+    }
+
+    /// Register a foreign function that traps on values it rejects, e.g. malformed inputs given
+    /// by the guest.
+    pub fn register_fallible_function(
+        &mut self,
+        name: &str,
+        op_index: usize,
+        sig: ExternalHostCallSignature,
+        plugin: Rc<ForeignPlugin>,
+        cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> HostFunctionResult>,
     ) {
         assert!(!*self.finalized.borrow());
 
@@ -7,25 +7,34 @@ use std::rc::Rc;
 use std::time::Instant;
 
 use log::debug;
+use specs::external_host_call_table::ExternalHostCallSignature;
+use specs::external_host_call_table::EXTERNAL_HOST_CALL_BUFFER_WORDS;
 use specs::host_function::HostFunctionDesc;
 
 use wasmi::Externals;
+use wasmi::MemoryRef;
 use wasmi::ModuleImportResolver;
 use wasmi::RuntimeArgs;
 use wasmi::RuntimeValue;
 use wasmi::Trap;
+use wasmi::TrapKind;
 
+use crate::runtime::host::host_trap;
+use crate::runtime::host::HostFunctionCallback;
 use crate::runtime::host::HostFunctionExecutionEnv;
+use crate::runtime::host::HostFunctionResult;
 use crate::runtime::monitor::observer::Observer;
 
 use super::external_circuit_plugin::ExternalCircuitEnv;
 use super::internal_circuit_plugin::InternalCircuitEnv;
+use super::phantom_plugin::PhantomEnv;
 use super::HostFunction;
 
 pub struct HostEnv {
     pub k: u32,
     pub(crate) internal_env: InternalCircuitEnv,
     pub external_env: ExternalCircuitEnv,
+    pub phantom_env: PhantomEnv,
 
     finalized: Rc<RefCell<bool>>,
     cached_lookup: Option<HashMap<usize, HostFunction>>,
@@ -51,6 +60,7 @@ impl HostEnv {
             k,
             internal_env: InternalCircuitEnv::new(finalized.clone()),
             external_env: ExternalCircuitEnv::new(finalized.clone()),
+            phantom_env: PhantomEnv::new(finalized.clone()),
             cached_lookup: None,
             finalized,
             time_profile: BTreeMap::new(),
@@ -78,7 +88,7 @@ impl HostEnv {
                         },
                         execution_env: HostFunctionExecutionEnv {
                             ctx: op.plugin.ctx.clone(),
-                            cb: op.cb.clone(),
+                            cb: HostFunctionCallback::Traced(op.cb.clone()),
                         },
                     },
                 )
@@ -106,7 +116,26 @@ impl HostEnv {
                             .unwrap()
                             .ctx
                             .clone(),
-                        cb: op.cb.clone(),
+                        cb: HostFunctionCallback::Traced(op.cb.clone()),
+                    },
+                },
+            );
+
+            internal_op_allocator_offset += 1;
+        }
+
+        for (name, op) in &mut self.phantom_env.functions {
+            op.index = Some(internal_op_allocator_offset);
+
+            lookup.insert(
+                internal_op_allocator_offset,
+                HostFunction {
+                    desc: HostFunctionDesc::Phantom {
+                        name: name.to_owned(),
+                    },
+                    execution_env: HostFunctionExecutionEnv {
+                        ctx: op.plugin.ctx.clone(),
+                        cb: HostFunctionCallback::Phantom(op.cb.clone()),
                     },
                 },
             );
@@ -151,12 +180,78 @@ impl ModuleImportResolver for HostEnv {
         self.external_env
             .resolve_func(function_name, signature)
             .or_else(|_| self.internal_env.resolve_func(function_name, signature))
+            .or_else(|_| self.phantom_env.resolve_func(function_name, signature))
     }
 }
 
 pub struct ExecEnv {
     pub host_env: HostEnv,
     pub observer: Rc<RefCell<Observer>>,
+    /// Linear memory of the instance, used by buffer host calls.
+    pub memory: Option<MemoryRef>,
+}
+
+impl ExecEnv {
+    /// Invokes `cb` once per u64 word of the buffer `[address, address + len)`.
+    ///
+    /// For `ArgumentBuffer` each word is passed to `cb` as an i64 argument, for
+    /// `ReturnBuffer` the value returned by each invocation is written back into the buffer.
+    fn invoke_buffer(
+        memory: Option<&MemoryRef>,
+        sig: ExternalHostCallSignature,
+        args: RuntimeArgs,
+        mut cb: impl FnMut(RuntimeArgs) -> HostFunctionResult,
+    ) -> Result<(), Trap> {
+        let address: u32 = args.nth(0);
+        let len: u32 = args.nth(1);
+
+        if address % 8 != 0 || len % 8 != 0 {
+            return Err(host_trap(
+                "buffer of host call should be aligned to 8 bytes",
+            ));
+        }
+        if len as usize > EXTERNAL_HOST_CALL_BUFFER_WORDS * 8 {
+            return Err(host_trap(format!(
+                "buffer of host call exceeds {} bytes",
+                EXTERNAL_HOST_CALL_BUFFER_WORDS * 8
+            )));
+        }
+
+        let end = address
+            .checked_add(len)
+            .ok_or(Trap::new(TrapKind::MemoryAccessOutOfBounds))?;
+        let memory = memory.ok_or(Trap::new(TrapKind::MemoryAccessOutOfBounds))?;
+
+        for offset in (address..end).step_by(8) {
+            match sig {
+                ExternalHostCallSignature::ArgumentBuffer => {
+                    let mut buf = [0u8; 8];
+                    memory
+                        .get_into(offset, &mut buf)
+                        .map_err(|_| Trap::new(TrapKind::MemoryAccessOutOfBounds))?;
+
+                    let word = [RuntimeValue::I64(i64::from_le_bytes(buf))];
+                    cb(RuntimeArgs::from(&word[..]))?;
+                }
+                ExternalHostCallSignature::ReturnBuffer => {
+                    let word: u64 = cb(RuntimeArgs::from(&[][..]))?
+                        .ok_or_else(|| {
+                            host_trap("host function of ReturnBuffer should return a value")
+                        })?
+                        .into();
+
+                    memory
+                        .set(offset, &word.to_le_bytes())
+                        .map_err(|_| Trap::new(TrapKind::MemoryAccessOutOfBounds))?;
+                }
+                ExternalHostCallSignature::Argument | ExternalHostCallSignature::Return => {
+                    unreachable!()
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Externals for ExecEnv {
@@ -174,7 +269,7 @@ impl Externals for ExecEnv {
             .clone()
         {
             Some(HostFunction {
-                desc: _desc,
+                desc,
                 execution_env: HostFunctionExecutionEnv { ctx, cb },
             }) => {
                 let mut ctx = (*ctx).borrow_mut();
@@ -183,7 +278,25 @@ impl Externals for ExecEnv {
                 #[cfg(feature = "profile")]
                 let start = Instant::now();
 
-                let r = cb(&self.observer.borrow(), ctx, args);
+                let r = match (desc, cb) {
+                    (HostFunctionDesc::External { sig, .. }, HostFunctionCallback::Traced(cb))
+                        if sig.is_buffer() =>
+                    {
+                        let observer = self.observer.borrow();
+
+                        ExecEnv::invoke_buffer(self.memory.as_ref(), *sig, args, |args| {
+                            cb(&observer, ctx, args)
+                        })?;
+
+                        None
+                    }
+                    (_, HostFunctionCallback::Traced(cb)) => {
+                        cb(&self.observer.borrow(), ctx, args)?
+                    }
+                    (_, HostFunctionCallback::Phantom(cb)) => {
+                        cb(&self.observer.borrow(), self.memory.as_ref(), ctx, args)?
+                    }
+                };
 
                 #[cfg(feature = "profile")]
                 let duration = start.elapsed();
@@ -191,7 +304,7 @@ impl Externals for ExecEnv {
                 #[cfg(feature = "profile")]
                 self.host_env
                     .time_profile
-                    .entry(_desc.name().to_string())
+                    .entry(desc.name().to_string())
                     .and_modify(|d| *d += duration.as_millis())
                     .or_insert(duration.as_millis());
 
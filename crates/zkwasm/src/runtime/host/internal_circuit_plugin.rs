@@ -12,13 +12,14 @@ use crate::runtime::monitor::observer::Observer;
 
 use super::ForeignContext;
 use super::ForeignPlugin;
+use super::HostFunctionResult;
 
 pub(super) struct ForeignOp {
     pub index: Option<usize>,
     pub index_within_plugin: usize,
     pub sig: Signature,
     pub plugin: HostPlugin,
-    pub cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> Option<RuntimeValue>>,
+    pub cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> HostFunctionResult>,
 }
 
 pub struct InternalCircuitEnv {
@@ -65,6 +66,25 @@ impl InternalCircuitEnv {
         plugin: HostPlugin,
         index_within_plugin: usize,
         cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> Option<RuntimeValue>>,
+    ) {
+        self.register_fallible_function(
+            function_name,
+            sig,
+            plugin,
+            index_within_plugin,
+            Rc::new(move |observer, context, args| Ok(cb(observer, context, args))),
+        )
+    }
+
+    /// Register a foreign function that traps on values it rejects, e.g. an index out of the
+    /// range given by the host.
+    pub fn register_fallible_function(
+        &mut self,
+        function_name: &str,
+        sig: Signature,
+        plugin: HostPlugin,
+        index_within_plugin: usize,
+        cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> HostFunctionResult>,
     ) {
         assert!(!*self.finalized.borrow());
 
@@ -1,4 +1,5 @@
 use crate::foreign::context::ContextOutput;
+use crate::foreign::log_helper::GuestLog;
 
 use self::default_env::ExecutionArg;
 use self::host_env::HostEnv;
@@ -7,10 +8,15 @@ use downcast_rs::Downcast;
 use specs::external_host_call_table::ExternalHostCallSignature;
 use specs::host_function::HostFunctionDesc;
 use std::cell::RefCell;
+use std::fmt;
 use std::rc::Rc;
+use wasmi::HostError;
+use wasmi::MemoryRef;
 use wasmi::RuntimeArgs;
 use wasmi::RuntimeValue;
 use wasmi::Signature;
+use wasmi::Trap;
+use wasmi::TrapKind;
 
 use super::monitor::observer::Observer;
 
@@ -19,6 +25,7 @@ pub mod external_circuit_plugin;
 
 pub mod host_env;
 mod internal_circuit_plugin;
+pub mod phantom_plugin;
 
 trait MatchForeignOpSignature {
     fn match_wasmi_signature(&self, signature: &Signature) -> bool;
@@ -28,6 +35,7 @@ impl MatchForeignOpSignature for ExternalHostCallSignature {
     /// Currently we only support
     /// * function with one argument and without return value
     /// * function with return value and without any arguments
+    /// * function with a pointer and a length of a buffer and without return value
     fn match_wasmi_signature(&self, signature: &Signature) -> bool {
         match self {
             ExternalHostCallSignature::Argument => {
@@ -39,10 +47,31 @@ impl MatchForeignOpSignature for ExternalHostCallSignature {
                 signature.params().len() == 0
                     && signature.return_type() == Some(wasmi::ValueType::I64)
             }
+            ExternalHostCallSignature::ArgumentBuffer | ExternalHostCallSignature::ReturnBuffer => {
+                signature.params() == &[wasmi::ValueType::I32, wasmi::ValueType::I32]
+                    && signature.return_type() == None
+            }
         }
     }
 }
 
+/// A host function rejects the values given by the guest, the execution traps instead of
+/// aborting the host.
+#[derive(Debug)]
+pub struct HostCallError(pub String);
+
+impl fmt::Display for HostCallError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl HostError for HostCallError {}
+
+pub fn host_trap(message: impl Into<String>) -> Trap {
+    Trap::new(TrapKind::Host(Box::new(HostCallError(message.into()))))
+}
+
 #[derive(Clone, Debug)]
 pub struct ForeignStatics {
     pub used_round: usize,
@@ -78,6 +107,10 @@ pub trait ForeignContext: Downcast {
     fn expose_context_outputs(&self) -> Vec<u64> {
         unreachable!()
     }
+
+    fn expose_guest_logs(&self) -> Vec<GuestLog> {
+        unreachable!()
+    }
 }
 impl_downcast!(ForeignContext);
 
@@ -86,10 +119,28 @@ pub struct ForeignPlugin {
     ctx: Rc<RefCell<Box<dyn ForeignContext>>>,
 }
 
+/// Result of a host function, `Err` traps the execution.
+pub type HostFunctionResult = Result<Option<RuntimeValue>, Trap>;
+
+#[derive(Clone)]
+enum HostFunctionCallback {
+    Traced(Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> HostFunctionResult>),
+    Phantom(
+        Rc<
+            dyn Fn(
+                &Observer,
+                Option<&MemoryRef>,
+                &mut dyn ForeignContext,
+                RuntimeArgs,
+            ) -> HostFunctionResult,
+        >,
+    ),
+}
+
 #[derive(Clone)]
 struct HostFunctionExecutionEnv {
     ctx: Rc<RefCell<Box<dyn ForeignContext>>>,
-    cb: Rc<dyn Fn(&Observer, &mut dyn ForeignContext, RuntimeArgs) -> Option<RuntimeValue>>,
+    cb: HostFunctionCallback,
 }
 
 #[derive(Clone)]
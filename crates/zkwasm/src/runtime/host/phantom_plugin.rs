@@ -0,0 +1,125 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasmi::FuncInstance;
+use wasmi::MemoryRef;
+use wasmi::ModuleImportResolver;
+use wasmi::RuntimeArgs;
+use wasmi::Signature;
+
+use crate::runtime::monitor::observer::Observer;
+
+use super::ForeignContext;
+use super::ForeignPlugin;
+use super::HostFunctionResult;
+
+pub(super) struct PhantomOp {
+    pub index: Option<usize>,
+    pub sig: Signature,
+    pub plugin: Rc<ForeignPlugin>,
+    pub cb: Rc<
+        dyn Fn(
+            &Observer,
+            Option<&MemoryRef>,
+            &mut dyn ForeignContext,
+            RuntimeArgs,
+        ) -> HostFunctionResult,
+    >,
+}
+
+/// Host functions without circuit.
+///
+/// They are never traced, so the image must only call them within phantom functions.
+/// In exchange, they can take any signature and read the linear memory. A callback returning an
+/// error traps the execution.
+pub struct PhantomEnv {
+    plugins: HashMap<String, Rc<ForeignPlugin>>,
+    pub(super) functions: HashMap<String, PhantomOp>,
+    finalized: Rc<RefCell<bool>>,
+}
+
+impl PhantomEnv {
+    pub(super) fn new(finalized: Rc<RefCell<bool>>) -> Self {
+        Self {
+            plugins: HashMap::new(),
+            functions: HashMap::new(),
+            finalized,
+        }
+    }
+
+    pub fn register_plugin(
+        &mut self,
+        name: &str,
+        ctx: Box<dyn ForeignContext>,
+    ) -> Rc<ForeignPlugin> {
+        let plugin = Rc::new(ForeignPlugin {
+            name: name.to_string(),
+            ctx: Rc::new(RefCell::new(ctx)),
+        });
+
+        self.plugins.insert(name.to_string(), plugin.clone());
+
+        plugin
+    }
+
+    pub fn register_function(
+        &mut self,
+        name: &str,
+        sig: Signature,
+        plugin: Rc<ForeignPlugin>,
+        cb: Rc<
+            dyn Fn(
+                &Observer,
+                Option<&MemoryRef>,
+                &mut dyn ForeignContext,
+                RuntimeArgs,
+            ) -> HostFunctionResult,
+        >,
+    ) {
+        assert!(!*self.finalized.borrow());
+
+        self.functions.insert(
+            name.to_owned(),
+            PhantomOp {
+                index: None,
+                sig,
+                plugin,
+                cb,
+            },
+        );
+    }
+
+    pub(crate) fn get_context_of_plugin(
+        &self,
+        name: &str,
+    ) -> Option<Rc<RefCell<Box<dyn ForeignContext>>>> {
+        self.plugins.get(name).map(|plugin| plugin.ctx.clone())
+    }
+}
+
+impl ModuleImportResolver for PhantomEnv {
+    fn resolve_func(
+        &self,
+        function_name: &str,
+        signature: &wasmi::Signature,
+    ) -> Result<wasmi::FuncRef, wasmi::Error> {
+        if let Some(PhantomOp { index, sig, .. }) = self.functions.get(function_name) {
+            if sig == signature {
+                Ok(FuncInstance::alloc_host(
+                    signature.clone(),
+                    index.expect("Unsolved host function index."),
+                ))
+            } else {
+                Err(wasmi::Error::Instantiation(format!(
+                    "Export `{}` doesn't match expected type {:?}",
+                    function_name, signature
+                )))
+            }
+        } else {
+            Err(wasmi::Error::Instantiation(format!(
+                "Export {} not found",
+                function_name
+            )))
+        }
+    }
+}
@@ -10,6 +10,7 @@ use specs::mtable::VarType;
 use specs::step::StepInfo;
 
 use crate::foreign::context::ContextOutput;
+use crate::foreign::log_helper::GuestLog;
 
 use self::host::ForeignStatics;
 use self::wasmi_interpreter::WasmiRuntime;
@@ -17,6 +18,9 @@ use self::wasmi_interpreter::WasmiRuntime;
 pub mod host;
 pub mod monitor;
 pub mod state;
+pub mod trace_checker;
+pub mod trace_diff;
+pub mod trace_explorer;
 pub mod wasmi_interpreter;
 
 pub struct CompiledImage<I> {
@@ -34,6 +38,8 @@ pub struct ExecutionResult<R> {
     pub guest_statics: usize, // total instructions used in guest circuits
     pub outputs: Vec<u64>,
     pub context_outputs: ContextOutput,
+    /// Messages emitted by the guest through `wasm_log`
+    pub guest_logs: Vec<GuestLog>,
 }
 
 impl<R> ExecutionResult<R> {
@@ -476,7 +482,56 @@ pub fn memory_event_of_step(event: &EventTableEntry) -> Vec<MemoryTableEntry> {
 
                 vec![stack_write]
             }
+            ExternalHostCallSignature::ArgumentBuffer | ExternalHostCallSignature::ReturnBuffer => {
+                unreachable!()
+            }
         },
+        StepInfo::ExternalHostCallBuffer {
+            address,
+            len,
+            values,
+            sig,
+            ..
+        } => {
+            let mut mops = vec![
+                MemoryTableEntry {
+                    eid,
+                    offset: sp_before_execution + 1,
+                    ltype: LocationType::Stack,
+                    atype: AccessType::Read,
+                    vtype: VarType::I32,
+                    is_mutable: true,
+                    value: *len as u64,
+                },
+                MemoryTableEntry {
+                    eid,
+                    offset: sp_before_execution + 2,
+                    ltype: LocationType::Stack,
+                    atype: AccessType::Read,
+                    vtype: VarType::I32,
+                    is_mutable: true,
+                    value: *address as u64,
+                },
+            ];
+
+            for (i, value) in values.iter().enumerate() {
+                mops.push(MemoryTableEntry {
+                    eid,
+                    offset: address / 8 + i as u32,
+                    ltype: LocationType::Heap,
+                    atype: if sig.is_ret() {
+                        AccessType::Write
+                    } else {
+                        AccessType::Read
+                    },
+                    vtype: VarType::I64,
+                    is_mutable: true,
+                    value: *value,
+                });
+            }
+
+            mops
+        }
 
         StepInfo::GetLocal {
             vtype,
@@ -1,5 +1,24 @@
+use std::collections::HashMap;
+
 #[derive(Debug, Default)]
 pub struct Observer {
     pub counter: usize,
     pub is_in_phantom: bool,
+    /// Index of the guest function being executed
+    pub current_function: Option<u32>,
+    /// Names of guest functions, taken from the name section or the export section
+    pub function_names: HashMap<u32, String>,
+    /// Maximal memory pages allocated so far
+    pub maximal_memory_pages: u32,
+}
+
+impl Observer {
+    pub fn current_function_name(&self) -> Option<String> {
+        self.current_function.map(|fid| {
+            self.function_names
+                .get(&fid)
+                .cloned()
+                .unwrap_or_else(|| format!("func[{}]", fid))
+        })
+    }
 }
@@ -1,14 +1,21 @@
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 
+use parity_wasm::elements::FuncBody;
+use parity_wasm::elements::ImportCountType;
+use parity_wasm::elements::Instruction;
 use parity_wasm::elements::Module;
 use regex::Regex;
+use specs::phantom::PhantomFunctionEntry;
+use specs::phantom::PhantomFunctionTable;
 use wasmi::monitor::Monitor;
 use wasmi::FuncRef;
 use wasmi::ModuleRef;
 
 pub struct PhantomHelper {
     phantom_regex: Vec<Regex>,
-    phantom_functions: HashSet<u32>,
+    /// Phantom functions by function index, with the name of the export matching a pattern.
+    phantom_functions: BTreeMap<u32, String>,
 
     pub(in crate::runtime::monitor) wasm_input: FuncRef,
     frame: Vec<u32>,
@@ -21,7 +28,7 @@ impl PhantomHelper {
                 .iter()
                 .map(|s| Regex::new(s).unwrap())
                 .collect::<Vec<_>>(),
-            phantom_functions: HashSet::new(),
+            phantom_functions: BTreeMap::new(),
 
             wasm_input,
 
@@ -34,7 +41,7 @@ impl PhantomHelper {
     }
 
     pub(in crate::runtime::monitor) fn is_phantom_function(&self, func_index: u32) -> bool {
-        self.phantom_functions.contains(&func_index)
+        self.phantom_functions.contains_key(&func_index)
     }
 
     pub(in crate::runtime::monitor) fn wasm_input_func_idx(&self, module_ref: &ModuleRef) -> u32 {
@@ -48,6 +55,109 @@ impl PhantomHelper {
     pub(in crate::runtime::monitor) fn pop_frame(&mut self) -> Option<u32> {
         self.frame.pop()
     }
+
+    /// Checks that phantom functions, and the functions they call, can't change the state seen by
+    /// the traced execution after they return. Nothing they do is rolled back, so memory and global
+    /// writes, memory growth, host calls other than phantom host functions, and indirect calls
+    /// whose callee can't be checked are all rejected.
+    pub(in crate::runtime::monitor) fn check_side_effects(
+        &self,
+        module: &Module,
+        is_phantom_host_function: impl Fn(u32) -> bool,
+    ) -> Result<(), wasmi::Error> {
+        let imported_functions = module.import_count(ImportCountType::Function) as u32;
+
+        for (fid, name) in &self.phantom_functions {
+            let side_effect = |callee: u32, reason: String| {
+                Err(wasmi::Error::Instantiation(format!(
+                    "Phantom function {} is not allowed to have side effects, function {} {}",
+                    name, callee, reason
+                )))
+            };
+
+            let mut visited = HashSet::new();
+            let mut pending = vec![*fid];
+
+            while let Some(callee) = pending.pop() {
+                if !visited.insert(callee) {
+                    continue;
+                }
+
+                if callee < imported_functions {
+                    if !is_phantom_host_function(callee) {
+                        return side_effect(callee, "is a host function".to_string());
+                    }
+
+                    continue;
+                }
+
+                for instruction in body_of(module, imported_functions, callee)
+                    .code()
+                    .elements()
+                {
+                    match instruction {
+                        Instruction::Call(index) => pending.push(*index),
+                        Instruction::CallIndirect(..) => {
+                            return side_effect(callee, "makes an indirect call".to_string())
+                        }
+                        Instruction::SetGlobal(..)
+                        | Instruction::GrowMemory(..)
+                        | Instruction::I32Store(..)
+                        | Instruction::I64Store(..)
+                        | Instruction::I32Store8(..)
+                        | Instruction::I32Store16(..)
+                        | Instruction::I64Store8(..)
+                        | Instruction::I64Store16(..)
+                        | Instruction::I64Store32(..) => {
+                            return side_effect(callee, format!("executes {}", instruction))
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Records the phantom functions with the bodies replaced in the instruction table.
+    pub(in crate::runtime::monitor) fn phantom_function_table(
+        &self,
+        module: &Module,
+        module_ref: &ModuleRef,
+    ) -> PhantomFunctionTable {
+        let imported_functions = module.import_count(ImportCountType::Function) as u32;
+
+        PhantomFunctionTable::new(
+            self.phantom_functions
+                .iter()
+                .map(|(fid, name)| PhantomFunctionEntry {
+                    fid: *fid,
+                    name: name.clone(),
+                    signature: module_ref
+                        .func_by_index(*fid)
+                        .unwrap()
+                        .signature()
+                        .clone()
+                        .into(),
+                    body: if *fid < imported_functions {
+                        vec![]
+                    } else {
+                        body_of(module, imported_functions, *fid)
+                            .code()
+                            .elements()
+                            .iter()
+                            .map(|instruction| instruction.to_string())
+                            .collect()
+                    },
+                })
+                .collect(),
+        )
+    }
+}
+
+fn body_of(module: &Module, imported_functions: u32, fid: u32) -> &FuncBody {
+    &module.code_section().unwrap().bodies()[(fid - imported_functions) as usize]
 }
 
 impl Monitor for PhantomHelper {
@@ -65,8 +175,10 @@ impl Monitor for PhantomHelper {
                 if export.as_func().is_some()
                     && self.phantom_regex.iter().any(|re| re.is_match(name))
                 {
-                    self.phantom_functions
-                        .insert(module_ref.func_index_by_func_ref(export.as_func().unwrap()));
+                    self.phantom_functions.insert(
+                        module_ref.func_index_by_func_ref(export.as_func().unwrap()),
+                        name.clone(),
+                    );
                 }
             });
 
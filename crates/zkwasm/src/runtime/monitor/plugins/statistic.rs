@@ -14,6 +14,7 @@ use crate::runtime::monitor::Observer;
 pub struct StatisticPlugin {
     phantom_helper: PhantomHelper,
     observer: Rc<RefCell<Observer>>,
+    frames: Vec<u32>,
 }
 
 impl StatisticPlugin {
@@ -21,6 +22,7 @@ impl StatisticPlugin {
         Self {
             phantom_helper: PhantomHelper::new(phantom_regex, wasm_input),
             observer: Rc::new(RefCell::new(Observer::default())),
+            frames: Vec::new(),
         }
     }
 
@@ -39,6 +41,27 @@ impl Monitor for StatisticPlugin {
         self.phantom_helper
             .register_module(module, module_ref, entry)?;
 
+        let mut observer = self.observer.borrow_mut();
+
+        module_ref
+            .exports
+            .borrow()
+            .iter()
+            .for_each(|(name, export)| {
+                if let Some(func) = export.as_func() {
+                    observer
+                        .function_names
+                        .insert(module_ref.func_index_by_func_ref(func), name.clone());
+                }
+            });
+
+        // The name section takes precedence over export names.
+        if let Some(functions) = module.names_section().and_then(|names| names.functions()) {
+            for (fid, name) in functions.names().iter() {
+                observer.function_names.insert(fid, name.clone());
+            }
+        }
+
         Ok(())
     }
 
@@ -47,7 +70,7 @@ impl Monitor for StatisticPlugin {
         fid: u32,
         _iid: u32,
         _sp: u32,
-        _allocated_memory_pages: u32,
+        allocated_memory_pages: u32,
         value_stack: &wasmi::runner::ValueStack,
         _function_context: &wasmi::runner::FunctionContext,
         _instruction: &wasmi::isa::Instruction,
@@ -56,9 +79,21 @@ impl Monitor for StatisticPlugin {
         self.observer.borrow_mut().counter +=
             !self.phantom_helper.is_in_phantom_function() as usize;
 
+        {
+            let mut observer = self.observer.borrow_mut();
+            observer.maximal_memory_pages =
+                observer.maximal_memory_pages.max(allocated_memory_pages);
+        }
+
+        if self.frames.is_empty() {
+            self.frames.push(fid);
+        }
+
         match outcome {
             InstructionOutcome::ExecuteCall(func_ref) => {
                 if let FuncInstanceInternal::Internal { index, .. } = func_ref.as_internal() {
+                    self.frames.push(*index as u32);
+
                     if self.phantom_helper.is_phantom_function(*index as u32) {
                         self.observer.borrow_mut().is_in_phantom = true;
 
@@ -67,6 +102,8 @@ impl Monitor for StatisticPlugin {
                 }
             }
             InstructionOutcome::Return(drop_keep) => {
+                self.frames.pop();
+
                 if self.phantom_helper.is_phantom_function(fid) {
                     self.phantom_helper.pop_frame();
 
@@ -89,5 +126,7 @@ impl Monitor for StatisticPlugin {
             }
             _ => {}
         }
+
+        self.observer.borrow_mut().current_function = self.frames.last().cloned();
     }
 }
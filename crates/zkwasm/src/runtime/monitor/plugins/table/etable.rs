@@ -2,44 +2,106 @@ use std::rc::Rc;
 
 use specs::etable::EventTable;
 use specs::etable::EventTableEntry;
+use specs::etable::EventTableWriter;
+use specs::etable::EVENT_TABLE_CHUNK_ENTRIES;
+use specs::imtable::InitMemoryTableDiff;
 use specs::step::StepInfo;
 use specs::TableBackend;
 use specs::TraceBackend;
 use wasmi::DEFAULT_VALUE_STACK_LIMIT;
 
+use crate::runtime::state::extend_init_memory_table_diff;
+
+// The slice being streamed to a file, its side tables are written along with it.
+struct SliceWriter {
+    writer: EventTableWriter,
+    memory_writes: InitMemoryTableDiff,
+}
+
 pub(super) struct ETable {
     pub(crate) eid: u32,
     slices: Vec<TableBackend<EventTable>>,
+    // All entries of the current slice for the memory backend, the entries not yet written for
+    // the file backend.
     entries: Vec<EventTableEntry>,
+    // Number of entries in the current slice.
+    len: usize,
     capacity: u32,
     backend: Rc<TraceBackend>,
+    writer: Option<SliceWriter>,
 }
 
 impl ETable {
     pub(crate) fn new(capacity: u32, backend: Rc<TraceBackend>) -> Self {
+        let buffered = match backend.as_ref() {
+            TraceBackend::File { .. } => EVENT_TABLE_CHUNK_ENTRIES,
+            TraceBackend::Memory => capacity as usize,
+        };
+
         Self {
             eid: 0,
             slices: Vec::default(),
-            entries: Vec::with_capacity(capacity as usize),
+            entries: Vec::with_capacity(buffered),
+            len: 0,
             capacity,
             backend,
+            writer: None,
         }
     }
 
-    pub(crate) fn flush(&mut self) {
-        let empty = Vec::with_capacity(self.capacity as usize);
-        let entries = std::mem::replace(&mut self.entries, empty);
+    fn slice_writer(&mut self) -> &mut SliceWriter {
+        if self.writer.is_none() {
+            let event_table_path = match self.backend.as_ref() {
+                TraceBackend::File {
+                    event_table_path, ..
+                } => event_table_path,
+                TraceBackend::Memory => unreachable!(),
+            };
+
+            self.writer = Some(SliceWriter {
+                writer: EventTableWriter::create(&event_table_path(self.slices.len())).unwrap(),
+                memory_writes: InitMemoryTableDiff::default(),
+            });
+        }
+
+        self.writer.as_mut().unwrap()
+    }
+
+    // Write the buffered entries of the current slice and their side tables.
+    fn write_buffered(&mut self) {
+        let entries = std::mem::take(&mut self.entries);
+        let writer = self.slice_writer();
 
+        extend_init_memory_table_diff(&mut writer.memory_writes, &entries);
+        writer.writer.write_entries(&entries).unwrap();
+        writer.writer.write_external_host_calls(&entries).unwrap();
+
+        self.entries = entries;
+        self.entries.clear();
+    }
+
+    pub(crate) fn flush(&mut self) {
         let event_table = match self.backend.as_ref() {
-            TraceBackend::File {
-                event_table_writer, ..
-            } => TableBackend::Json(event_table_writer(
-                self.slices.len(),
-                &EventTable::new(entries),
-            )),
-            TraceBackend::Memory => TableBackend::Memory(EventTable::new(entries)),
+            TraceBackend::File { .. } => {
+                self.write_buffered();
+
+                let SliceWriter {
+                    mut writer,
+                    memory_writes,
+                } = self.writer.take().unwrap();
+                writer.write_memory_writes(&memory_writes).unwrap();
+
+                TableBackend::File(writer.finish().unwrap())
+            }
+            TraceBackend::Memory => {
+                let empty = Vec::with_capacity(self.capacity as usize);
+                let entries = std::mem::replace(&mut self.entries, empty);
+
+                TableBackend::Memory(EventTable::new(entries))
+            }
         };
 
+        self.len = 0;
         self.slices.push(event_table);
     }
 
@@ -52,6 +114,14 @@ impl ETable {
         last_jump_eid: u32,
         step_info: StepInfo,
     ) {
+        // Entries are only written when the next one arrives, the last entry is still updated by
+        // the post hook of host calls.
+        if self.entries.len() == EVENT_TABLE_CHUNK_ENTRIES {
+            if let TraceBackend::File { .. } = self.backend.as_ref() {
+                self.write_buffered();
+            }
+        }
+
         self.eid += 1;
 
         let sp = (DEFAULT_VALUE_STACK_LIMIT as u32)
@@ -71,14 +141,20 @@ impl ETable {
         };
 
         self.entries.push(eentry);
+        self.len += 1;
+    }
+
+    /// Number of entries in the current slice.
+    pub(super) fn len(&self) -> usize {
+        self.len
     }
 
-    pub(super) fn entries(&self) -> &[EventTableEntry] {
-        &self.entries
+    pub(super) fn last(&self) -> Option<&EventTableEntry> {
+        self.entries.last()
     }
 
-    pub(crate) fn entries_mut(&mut self) -> &mut Vec<EventTableEntry> {
-        &mut self.entries
+    pub(crate) fn last_mut(&mut self) -> Option<&mut EventTableEntry> {
+        self.entries.last_mut()
     }
 
     pub fn finalized(mut self) -> Vec<TableBackend<EventTable>> {
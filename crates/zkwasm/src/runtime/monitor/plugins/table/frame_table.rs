@@ -144,7 +144,7 @@ impl FrameTable {
                 TraceBackend::Memory => TableBackend::Memory(frame_table),
                 TraceBackend::File {
                     frame_table_writer, ..
-                } => TableBackend::Json(frame_table_writer(self.slices.len(), &frame_table)),
+                } => TableBackend::File(frame_table_writer(self.slices.len(), &frame_table)),
             }
         };
 
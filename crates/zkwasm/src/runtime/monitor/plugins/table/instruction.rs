@@ -26,6 +26,7 @@ use wasmi::runner::FromValueInternal;
 use wasmi::runner::FunctionContext;
 use wasmi::runner::ValueInternal;
 use wasmi::runner::ValueStack;
+use wasmi::MemoryRef;
 use wasmi::ModuleRef;
 use wasmi::Signature;
 
@@ -41,6 +42,8 @@ pub struct FuncDesc {
 pub struct PhantomFunction;
 
 impl PhantomFunction {
+    /// Instructions replacing the body of a phantom function: the result, if any, is read by
+    /// `wasm_input(0)` and the return drops the arguments.
     pub fn build_phantom_function_instructions(
         sig: &Signature,
         // Wasm Image Function Id
@@ -167,6 +170,7 @@ impl<'a> InstructionIntoOpcode for wasmi::isa::Instruction<'a> {
                     specs::types::FunctionType::HostFunctionExternal { op, sig, .. } => {
                         Opcode::ExternalHostCall { op: *op, sig: *sig }
                     }
+                    specs::types::FunctionType::HostFunctionPhantom { .. } => unreachable!(),
                 }
             }
             Instruction::CallIndirect(idx) => Opcode::CallIndirect { type_idx: idx },
@@ -708,6 +712,21 @@ pub(super) enum RunInstructionTracePre {
     },
 }
 
+/// Reads the u64 words of `[address, address + len)`, `None` if the range overflows or is out of
+/// the linear memory. The host env traps on such a buffer, the tracer records no values for it.
+pub(super) fn read_buffer(memory: &MemoryRef, address: u32, len: u32) -> Option<Vec<u64>> {
+    let end = address.checked_add(len)?;
+
+    (address..end)
+        .step_by(8)
+        .map(|offset| {
+            let mut buf = [0u8; 8];
+            memory.get_into(offset, &mut buf).ok()?;
+            Some(u64::from_le_bytes(buf))
+        })
+        .collect()
+}
+
 pub(super) fn run_instruction_pre(
     value_stack: &ValueStack,
     function_context: &FunctionContext,
@@ -1288,6 +1307,27 @@ impl TablePlugin {
                                 op_index_in_plugin: *op_index_in_plugin,
                             }
                         }
+                        specs::types::FunctionType::HostFunctionExternal { op, sig, .. }
+                            if sig.is_buffer() =>
+                        {
+                            let address: u32 = <_>::from_value_internal(*value_stack.pick(2));
+                            let len: u32 = <_>::from_value_internal(*value_stack.pick(1));
+
+                            StepInfo::ExternalHostCallBuffer {
+                                op: *op,
+                                address,
+                                len,
+                                values: match sig {
+                                    ExternalHostCallSignature::ArgumentBuffer => {
+                                        read_buffer(&context.memory.clone().unwrap(), address, len)
+                                            .unwrap_or_default()
+                                    }
+                                    // Filled after the host function returns.
+                                    _ => vec![],
+                                },
+                                sig: *sig,
+                            }
+                        }
                         specs::types::FunctionType::HostFunctionExternal { op, sig, .. } => {
                             StepInfo::ExternalHostCall {
                                 op: *op,
@@ -1299,10 +1339,14 @@ impl TablePlugin {
                                         ))
                                     }
                                     ExternalHostCallSignature::Return => None,
+                                    ExternalHostCallSignature::ArgumentBuffer
+                                    | ExternalHostCallSignature::ReturnBuffer => unreachable!(),
                                 },
                                 sig: *sig,
                             }
                         }
+                        // Rejected when registering the module.
+                        specs::types::FunctionType::HostFunctionPhantom { .. } => unreachable!(),
                     }
                 } else {
                     unreachable!()
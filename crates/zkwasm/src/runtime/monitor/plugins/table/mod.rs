@@ -14,6 +14,7 @@ use specs::itable::InstructionTable;
 use specs::itable::InstructionTableInternal;
 use specs::mtable::LocationType;
 use specs::mtable::VarType;
+use specs::phantom::PhantomFunctionTable;
 use specs::state::InitializationState;
 use specs::step::StepInfo;
 use specs::types::FunctionType;
@@ -43,6 +44,7 @@ use crate::circuits::compute_slice_capability;
 
 use self::etable::ETable;
 use self::frame_table::FrameTable;
+use self::instruction::read_buffer;
 use self::instruction::run_instruction_pre;
 use self::instruction::FuncDesc;
 use self::instruction::InstructionIntoOpcode;
@@ -70,6 +72,7 @@ pub struct TablePlugin {
     elements: Vec<ElemEntry>,
     configure_table: ConfigureTable,
     init_memory_table: Vec<InitMemoryTableEntry>,
+    phantom_functions: PhantomFunctionTable,
     start_fid: Option<u32>,
 
     etable: ETable,
@@ -102,6 +105,7 @@ impl TablePlugin {
             elements: vec![],
             configure_table: ConfigureTable::default(),
             init_memory_table: vec![],
+            phantom_functions: PhantomFunctionTable::default(),
             function_table: vec![],
             start_fid: None,
 
@@ -114,6 +118,10 @@ impl TablePlugin {
         }
     }
 
+    pub fn phantom_functions(&self) -> &PhantomFunctionTable {
+        &self.phantom_functions
+    }
+
     pub fn into_compilation_table(&self) -> CompilationTable {
         let itable: InstructionTable = self.itable.clone().into();
         let imtable = InitMemoryTable::new(self.init_memory_table.clone());
@@ -144,6 +152,7 @@ impl TablePlugin {
             configure_table,
             initial_frame_table: Arc::new(self.frame_table.build_initial_frame_table()),
             initialization_state,
+            phantom_functions: Arc::new(self.phantom_functions.clone()),
         }
     }
 
@@ -168,7 +177,7 @@ impl TablePlugin {
         last_jump_eid: u32,
         step_info: StepInfo,
     ) {
-        if self.etable.entries().len() == self.capacity as usize {
+        if self.etable.len() == self.capacity as usize {
             self.etable.flush();
             self.frame_table.flush();
         }
@@ -391,6 +400,9 @@ impl Monitor for TablePlugin {
                                 sig,
                             }
                         }
+                        HostFunctionDesc::Phantom { name } => FunctionType::HostFunctionPhantom {
+                            function_name: name,
+                        },
                     },
                 };
 
@@ -402,6 +414,16 @@ impl Monitor for TablePlugin {
                 fid += 1;
             }
 
+            self.phantom_helper.check_side_effects(module, |fid| {
+                matches!(
+                    self.function_table[fid as usize].ftype,
+                    FunctionType::HostFunctionPhantom { .. }
+                )
+            })?;
+            self.phantom_functions = self
+                .phantom_helper
+                .phantom_function_table(module, module_ref);
+
             let mut fid = 0;
             while let Some(ref func) = module_ref.func_by_index(fid) {
                 let function_mapping = |index| self.function_table.get(index as usize).unwrap();
@@ -428,6 +450,17 @@ impl Monitor for TablePlugin {
                         let mut iter = code.iterate_from(0);
                         let mut iid = iter.position();
                         while let Some(instr) = iter.next() {
+                            if let Instruction::Call(index) = instr {
+                                if let FunctionType::HostFunctionPhantom { function_name } =
+                                    &function_mapping(index).ftype
+                                {
+                                    return Err(Error::Instantiation(format!(
+                                        "Host function {} can only be called within phantom functions",
+                                        function_name
+                                    )));
+                                }
+                            }
+
                             self.itable
                                 .push(fid, iid, instr.into_opcode(&function_mapping));
 
@@ -597,7 +630,7 @@ impl Monitor for TablePlugin {
             InstructionOutcome::ExecuteCall(func_ref) => {
                 if let FuncInstanceInternal::Internal { index, .. } = func_ref.as_internal() {
                     if !self.phantom_helper.is_in_phantom_function() {
-                        let eid = self.etable.entries().last().unwrap().eid;
+                        let eid = self.etable.last().unwrap().eid;
 
                         self.push_frame(
                             eid,
@@ -674,8 +707,30 @@ impl Monitor for TablePlugin {
             return;
         }
 
+        if let StepInfo::ExternalHostCallBuffer {
+            address,
+            len,
+            ref mut values,
+            sig,
+            ..
+        } = self.etable.last_mut().unwrap().step_info
+        {
+            if sig.is_ret() {
+                let memory_ref = self
+                    .module_ref
+                    .as_ref()
+                    .unwrap()
+                    .memory_by_index(DEFAULT_MEMORY_INDEX)
+                    .unwrap();
+
+                *values = read_buffer(&memory_ref, address, len).unwrap_or_default();
+            }
+
+            return;
+        }
+
         if let Some(return_value) = return_value {
-            match self.etable.entries_mut().last_mut().unwrap().step_info {
+            match self.etable.last_mut().unwrap().step_info {
                 StepInfo::CallHost {
                     ref mut ret_val, ..
                 } => {
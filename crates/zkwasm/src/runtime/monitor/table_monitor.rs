@@ -2,6 +2,7 @@ use std::cell::RefCell;
 use std::rc::Rc;
 
 use parity_wasm::elements::Module;
+use specs::phantom::PhantomFunctionTable;
 use specs::CompilationTable;
 use specs::Tables;
 use specs::TraceBackend;
@@ -49,6 +50,11 @@ impl TableMonitor {
         }
     }
 
+    /// Phantom functions of the registered module.
+    pub fn phantom_functions(&self) -> &PhantomFunctionTable {
+        self.table_plugin.phantom_functions()
+    }
+
     pub fn into_compilation_table(self) -> CompilationTable {
         self.table_plugin.into_compilation_table()
     }
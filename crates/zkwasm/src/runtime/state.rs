@@ -3,6 +3,7 @@ use specs::etable::EventTable;
 use specs::etable::EventTableEntry;
 use specs::host_function::HostPlugin;
 use specs::imtable::InitMemoryTable;
+use specs::imtable::InitMemoryTableDiff;
 use specs::imtable::InitMemoryTableEntry;
 use specs::mtable::AccessType;
 use specs::state::InitializationState;
@@ -23,33 +24,41 @@ pub(crate) trait UpdateInitializationState {
     ) -> Self;
 }
 
-impl UpdateInitMemoryTable for InitMemoryTable {
-    fn update_init_memory_table(&self, execution_table: &EventTable) -> InitMemoryTable {
-        // First insert origin imtable entries which may be overwritten.
-        let mut map = self.0.clone();
-
-        let mut it = execution_table.entries().iter();
-        while let Some(etable_entry) = it.next() {
-            let memory_writing_entires = memory_event_of_step(etable_entry)
-                .into_iter()
-                .filter(|entry| entry.atype == AccessType::Write);
-
-            for mentry in memory_writing_entires {
-                map.insert(
-                    (mentry.ltype, mentry.offset),
-                    InitMemoryTableEntry {
-                        ltype: mentry.ltype,
-                        is_mutable: mentry.is_mutable,
-                        offset: mentry.offset,
-                        vtype: mentry.vtype,
-                        value: mentry.value,
-                        eid: etable_entry.eid,
-                    },
-                );
-            }
+/// Records the last write of `entries` to each memory location in `diff`.
+pub(crate) fn extend_init_memory_table_diff(
+    diff: &mut InitMemoryTableDiff,
+    entries: &[EventTableEntry],
+) {
+    for etable_entry in entries {
+        let memory_writing_entires = memory_event_of_step(etable_entry)
+            .into_iter()
+            .filter(|entry| entry.atype == AccessType::Write);
+
+        for mentry in memory_writing_entires {
+            diff.insert(InitMemoryTableEntry {
+                ltype: mentry.ltype,
+                is_mutable: mentry.is_mutable,
+                offset: mentry.offset,
+                vtype: mentry.vtype,
+                value: mentry.value,
+                eid: etable_entry.eid,
+            });
         }
+    }
+}
+
+/// Collects the last write of each memory location in the slice.
+pub(crate) fn init_memory_table_diff(execution_table: &EventTable) -> InitMemoryTableDiff {
+    let mut diff = InitMemoryTableDiff::default();
 
-        InitMemoryTable(map)
+    extend_init_memory_table_diff(&mut diff, execution_table.entries());
+
+    diff
+}
+
+impl UpdateInitMemoryTable for InitMemoryTable {
+    fn update_init_memory_table(&self, execution_table: &EventTable) -> InitMemoryTable {
+        self.update(&init_memory_table_diff(execution_table))
     }
 }
 
@@ -90,6 +99,9 @@ impl UpdateInitializationState for InitializationState<u32> {
                     }
                 }
                 StepInfo::ExternalHostCall { .. } => external_host_call_call_index += 1,
+                StepInfo::ExternalHostCallBuffer { values, .. } => {
+                    external_host_call_call_index += values.len() as u32
+                }
                 _ => (),
             }
         }
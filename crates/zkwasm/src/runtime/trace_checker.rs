@@ -0,0 +1,973 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use specs::etable::EventTable;
+use specs::etable::EventTableEntry;
+use specs::itable::BinOp;
+use specs::itable::InstructionTableEntry;
+use specs::itable::Opcode;
+use specs::itable::RelOp;
+use specs::itable::ShiftOp;
+use specs::itable::UnaryOp;
+use specs::jtable::FrameTable;
+use specs::jtable::FrameTableEntryInternal;
+use specs::jtable::InheritedFrameTable;
+use specs::mtable::AccessType;
+use specs::mtable::LocationType;
+use specs::mtable::VarType;
+use specs::state::InitializationState;
+use specs::step::StepInfo;
+use specs::trace_file::TraceFileError;
+use specs::TableBackend;
+use specs::Tables;
+
+use super::memory_event_of_step;
+use super::state::UpdateInitializationState;
+
+/// A place where the trace disagrees with the reference model.
+#[derive(Clone, Debug)]
+pub struct TraceInconsistency {
+    pub slice: usize,
+    /// None if the inconsistency is not about a single event, e.g. a frame table entry.
+    pub eid: Option<u32>,
+    pub message: String,
+}
+
+impl fmt::Display for TraceInconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.eid {
+            Some(eid) => write!(f, "slice {} eid {}: {}", self.slice, eid, self.message),
+            None => write!(f, "slice {}: {}", self.slice, self.message),
+        }
+    }
+}
+
+pub trait TraceConsistency {
+    /// Replay the trace against a reference model of control flow, stack, memory and globals
+    /// without building the circuit. Returns all inconsistencies found, or an error if a table
+    /// stored in a file can't be read.
+    fn check_consistency(&self) -> Result<Vec<TraceInconsistency>, TraceFileError>;
+}
+
+impl TraceConsistency for Tables {
+    fn check_consistency(&self) -> Result<Vec<TraceInconsistency>, TraceFileError> {
+        let mut checker = Checker::new(self);
+
+        let slices = self
+            .execution_tables
+            .etable
+            .iter()
+            .zip(self.execution_tables.frame_table.iter())
+            .map(|(etable, frame_table)| {
+                let etable = match etable {
+                    TableBackend::Memory(etable) => EventTable::new(etable.entries().clone()),
+                    TableBackend::File(path) => EventTable::read(path)?,
+                };
+                let frame_table = match frame_table {
+                    TableBackend::Memory(frame_table) => FrameTable {
+                        inherited: frame_table.inherited.clone(),
+                        called: frame_table.called.clone(),
+                    },
+                    TableBackend::File(path) => FrameTable::read(path)?,
+                };
+
+                Ok((etable, frame_table))
+            })
+            .collect::<Result<Vec<_>, TraceFileError>>()?;
+
+        if self.execution_tables.etable.len() != self.execution_tables.frame_table.len() {
+            checker.report(
+                0,
+                None,
+                format!(
+                    "{} event table slices but {} frame table slices",
+                    self.execution_tables.etable.len(),
+                    self.execution_tables.frame_table.len()
+                ),
+            );
+        }
+
+        let mut state = (*self.compilation_tables.initialization_state).clone();
+
+        for (slice, (etable, _)) in slices.iter().enumerate() {
+            let next_entry = slices
+                .get(slice + 1)
+                .and_then(|(etable, _)| etable.entries().first());
+
+            if !checker.check_slice(slice, etable, &state) {
+                continue;
+            }
+
+            state = state.update_initialization_state(
+                etable,
+                &self.compilation_tables.configure_table,
+                next_entry,
+            );
+        }
+
+        checker.finalize(slices.len(), &state);
+
+        for (slice, (_, frame_table)) in slices.iter().enumerate() {
+            checker.check_frame_table(slice, frame_table);
+        }
+
+        Ok(checker.inconsistencies)
+    }
+}
+
+struct Frame {
+    entry: FrameTableEntryInternal,
+    // None for the static frames of the entry and start functions.
+    called_in: Option<usize>,
+    returned_in: Option<usize>,
+}
+
+struct Checker<'a> {
+    tables: &'a Tables,
+    instructions: HashMap<(u32, u32), &'a InstructionTableEntry>,
+
+    memory: HashMap<(LocationType, u32), u64>,
+    frames: Vec<Frame>,
+    // Indexes of active frames in `frames`.
+    call_stack: Vec<usize>,
+
+    // Expected (eid, fid, iid, allocated memory pages) of the next event.
+    next: Option<(u32, u32, u32, u32)>,
+
+    inconsistencies: Vec<TraceInconsistency>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(tables: &'a Tables) -> Self {
+        let instructions = tables
+            .compilation_tables
+            .itable
+            .iter()
+            .map(|entry| ((entry.fid, entry.iid), entry))
+            .collect();
+
+        // The entry function is pushed first and the start function, if any, runs on top of it.
+        let frames = tables
+            .compilation_tables
+            .initial_frame_table
+            .iter()
+            .filter_map(|entry| entry.0)
+            .map(|entry| Frame {
+                entry,
+                called_in: None,
+                returned_in: None,
+            })
+            .collect::<Vec<_>>();
+        let call_stack = (0..frames.len()).collect();
+
+        Self {
+            tables,
+            instructions,
+            memory: HashMap::new(),
+            frames,
+            call_stack,
+            next: None,
+            inconsistencies: vec![],
+        }
+    }
+
+    fn report(&mut self, slice: usize, eid: Option<u32>, message: String) {
+        self.inconsistencies.push(TraceInconsistency {
+            slice,
+            eid,
+            message,
+        });
+    }
+
+    // Returns false if the slice is empty.
+    fn check_slice(
+        &mut self,
+        slice: usize,
+        etable: &EventTable,
+        state: &InitializationState<u32>,
+    ) -> bool {
+        if let Some(first) = etable.entries().first() {
+            let expected = (
+                state.eid,
+                state.fid,
+                state.iid,
+                state.frame_id,
+                state.sp,
+                state.initial_memory_pages,
+            );
+            let actual = (
+                first.eid,
+                first.fid,
+                first.iid,
+                first.last_jump_eid,
+                first.sp,
+                first.allocated_memory_pages,
+            );
+
+            if expected != actual {
+                self.report(
+                    slice,
+                    Some(first.eid),
+                    format!(
+                        "the first event (eid, fid, iid, frame_id, sp, pages) {:?} differs from the initialization state {:?}",
+                        actual, expected
+                    ),
+                );
+            }
+        } else {
+            self.report(slice, None, "the event table is empty".to_string());
+
+            return false;
+        }
+
+        for entry in etable.entries() {
+            self.check_entry(slice, entry);
+        }
+
+        true
+    }
+
+    fn check_entry(&mut self, slice: usize, entry: &EventTableEntry) {
+        let eid = Some(entry.eid);
+
+        if let Some(expected) = self.next {
+            let actual = (
+                entry.eid,
+                entry.fid,
+                entry.iid,
+                entry.allocated_memory_pages,
+            );
+
+            if expected != actual {
+                self.report(
+                    slice,
+                    eid,
+                    format!(
+                        "(eid, fid, iid, pages) is {:?} but {:?} is expected",
+                        actual, expected
+                    ),
+                );
+            }
+        }
+
+        match self.call_stack.last() {
+            Some(frame) => {
+                let frame_id = self.frames[*frame].entry.frame_id;
+
+                if entry.last_jump_eid != frame_id {
+                    self.report(
+                        slice,
+                        eid,
+                        format!(
+                            "last_jump_eid is {} but the current frame is {}",
+                            entry.last_jump_eid, frame_id
+                        ),
+                    );
+                }
+            }
+            None => self.report(
+                slice,
+                eid,
+                "the event executes after the entry function returned".to_string(),
+            ),
+        }
+
+        if entry.allocated_memory_pages
+            > self
+                .tables
+                .compilation_tables
+                .configure_table
+                .maximal_memory_pages
+        {
+            self.report(
+                slice,
+                eid,
+                format!(
+                    "allocated memory pages {} exceed the maximal memory pages {}",
+                    entry.allocated_memory_pages,
+                    self.tables
+                        .compilation_tables
+                        .configure_table
+                        .maximal_memory_pages
+                ),
+            );
+        }
+
+        match self.instructions.get(&(entry.fid, entry.iid)) {
+            Some(instruction) => {
+                if let Err(message) = check_step(&instruction.opcode, &entry.step_info) {
+                    self.report(slice, eid, message);
+                }
+            }
+            None => self.report(
+                slice,
+                eid,
+                format!(
+                    "no instruction at fid {} iid {} in the instruction table",
+                    entry.fid, entry.iid
+                ),
+            ),
+        }
+
+        self.check_memory(slice, entry);
+        self.step(slice, entry);
+    }
+
+    fn check_memory(&mut self, slice: usize, entry: &EventTableEntry) {
+        for event in memory_event_of_step(entry) {
+            let location = (event.ltype, event.offset);
+
+            match event.atype {
+                AccessType::Read => {
+                    let expected = self.memory.get(&location).cloned().or_else(|| {
+                        match self
+                            .tables
+                            .compilation_tables
+                            .imtable
+                            .try_find(event.ltype, event.offset)
+                        {
+                            Some(init) => Some(init.value),
+                            // Heap is zero initialized.
+                            None if event.ltype == LocationType::Heap => Some(0),
+                            None => None,
+                        }
+                    });
+
+                    match expected {
+                        Some(expected) if expected != event.value => self.report(
+                            slice,
+                            Some(entry.eid),
+                            format!(
+                                "reads {} from {:?}[{}] which holds {}",
+                                event.value, event.ltype, event.offset, expected
+                            ),
+                        ),
+                        None => self.report(
+                            slice,
+                            Some(entry.eid),
+                            format!(
+                                "reads {:?}[{}] before it is written",
+                                event.ltype, event.offset
+                            ),
+                        ),
+                        _ => (),
+                    }
+                }
+                AccessType::Write => {
+                    if event.ltype == LocationType::Global && !event.is_mutable {
+                        self.report(
+                            slice,
+                            Some(entry.eid),
+                            format!("writes the immutable global {}", event.offset),
+                        );
+                    }
+
+                    self.memory.insert(location, event.value);
+                }
+                AccessType::Init => (),
+            }
+        }
+    }
+
+    // Advance the control flow model past the entry.
+    fn step(&mut self, slice: usize, entry: &EventTableEntry) {
+        let sequential = (entry.fid, entry.iid + 1);
+
+        let (fid, iid) = match &entry.step_info {
+            StepInfo::Br { dst_pc, .. } | StepInfo::BrTable { dst_pc, .. } => (entry.fid, *dst_pc),
+            StepInfo::BrIfEqz {
+                condition, dst_pc, ..
+            } => {
+                if *condition == 0 {
+                    (entry.fid, *dst_pc)
+                } else {
+                    sequential
+                }
+            }
+            StepInfo::BrIfNez {
+                condition, dst_pc, ..
+            } => {
+                if *condition != 0 {
+                    (entry.fid, *dst_pc)
+                } else {
+                    sequential
+                }
+            }
+            StepInfo::Call { index: callee }
+            | StepInfo::CallIndirect {
+                func_index: callee, ..
+            } => {
+                self.frames.push(Frame {
+                    entry: FrameTableEntryInternal {
+                        frame_id: entry.eid,
+                        next_frame_id: entry.last_jump_eid,
+                        callee_fid: *callee,
+                        fid: entry.fid,
+                        iid: entry.iid + 1,
+                        returned: false,
+                    },
+                    called_in: Some(slice),
+                    returned_in: None,
+                });
+                self.call_stack.push(self.frames.len() - 1);
+
+                (*callee, 0)
+            }
+            StepInfo::Return { .. } => match self.call_stack.pop() {
+                Some(frame) => {
+                    let frame = &mut self.frames[frame];
+                    frame.returned_in = Some(slice);
+
+                    (frame.entry.fid, frame.entry.iid)
+                }
+                None => {
+                    self.report(
+                        slice,
+                        Some(entry.eid),
+                        "returns without an active frame".to_string(),
+                    );
+
+                    sequential
+                }
+            },
+            _ => sequential,
+        };
+
+        let allocated_memory_pages = match &entry.step_info {
+            StepInfo::MemoryGrow { grow_size, result } if *result != -1 => {
+                entry.allocated_memory_pages + *grow_size as u32
+            }
+            _ => entry.allocated_memory_pages,
+        };
+
+        self.next = Some((entry.eid + 1, fid, iid, allocated_memory_pages));
+    }
+
+    fn finalize(&mut self, slices: usize, post_state: &InitializationState<u32>) {
+        let slice = slices.saturating_sub(1);
+
+        if !self.call_stack.is_empty() {
+            self.report(
+                slice,
+                None,
+                format!(
+                    "{} frames are not returned at the end of the execution",
+                    self.call_stack.len()
+                ),
+            );
+        }
+
+        if let Some((eid, ..)) = self.next {
+            if post_state.eid != eid {
+                self.report(
+                    slice,
+                    None,
+                    format!(
+                        "the post initialization state starts at eid {} but the trace ends before {}",
+                        post_state.eid, eid
+                    ),
+                );
+            }
+        }
+    }
+
+    // The frame table of a slice holds the frames called in the slice and the frames inherited
+    // from previous slices, i.e. active when the slice starts. `returned` tells whether the
+    // frame returns within the slice.
+    fn check_frame_table(&mut self, slice: usize, frame_table: &FrameTable) {
+        let key = |entry: &FrameTableEntryInternal| {
+            (
+                entry.frame_id,
+                entry.next_frame_id,
+                entry.callee_fid,
+                entry.fid,
+                entry.iid,
+                entry.returned,
+            )
+        };
+
+        let expected = |inherited: bool| {
+            let mut entries = self
+                .frames
+                .iter()
+                .filter(|frame| {
+                    let active = frame.returned_in.map_or(true, |returned| returned >= slice);
+
+                    match frame.called_in {
+                        Some(called) if inherited => called < slice && active,
+                        Some(called) => called == slice,
+                        None => inherited && active,
+                    }
+                })
+                .map(|frame| {
+                    key(&FrameTableEntryInternal {
+                        returned: frame.returned_in == Some(slice),
+                        ..frame.entry
+                    })
+                })
+                .collect::<Vec<_>>();
+            entries.sort();
+            entries
+        };
+
+        let mut inherited = InheritedFrameTable::try_from((*frame_table.inherited).clone())
+            .map(|inherited| {
+                inherited
+                    .iter()
+                    .filter_map(|entry| entry.0.as_ref().map(key))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        inherited.sort();
+
+        let mut called = frame_table
+            .called
+            .iter()
+            .map(|entry| key(&entry.0))
+            .collect::<Vec<_>>();
+        called.sort();
+
+        for (kind, actual, expected) in [
+            ("inherited", inherited, expected(true)),
+            ("called", called, expected(false)),
+        ] {
+            if actual != expected {
+                self.report(
+                    slice,
+                    None,
+                    format!(
+                        "{} frame table entries (frame_id, next_frame_id, callee_fid, fid, iid, returned) are {:?} but {:?} are expected",
+                        kind, actual, expected
+                    ),
+                );
+            }
+        }
+    }
+}
+
+macro_rules! eval_bin {
+    ($class:expr, $left:expr, $right:expr, $signed:ty, $unsigned:ty) => {
+        match $class {
+            BinOp::Add => $left.wrapping_add($right),
+            BinOp::Sub => $left.wrapping_sub($right),
+            BinOp::Mul => $left.wrapping_mul($right),
+            BinOp::UnsignedDiv => ($left as $unsigned).checked_div($right as $unsigned)? as $signed,
+            BinOp::UnsignedRem => ($left as $unsigned).checked_rem($right as $unsigned)? as $signed,
+            BinOp::SignedDiv => $left.checked_div($right)?,
+            BinOp::SignedRem => $left.checked_rem($right).or(Some(0))?,
+        }
+    };
+}
+
+macro_rules! eval_shift {
+    ($class:expr, $left:expr, $right:expr, $signed:ty, $unsigned:ty) => {
+        match $class {
+            ShiftOp::Shl => $left.wrapping_shl($right as u32),
+            ShiftOp::UnsignedShr => ($left as $unsigned).wrapping_shr($right as u32) as $signed,
+            ShiftOp::SignedShr => $left.wrapping_shr($right as u32),
+            ShiftOp::Rotl => $left.rotate_left($right as u32 % <$signed>::BITS),
+            ShiftOp::Rotr => $left.rotate_right($right as u32 % <$signed>::BITS),
+        }
+    };
+}
+
+macro_rules! eval_rel {
+    ($class:expr, $left:expr, $right:expr, $unsigned:ty) => {
+        match $class {
+            RelOp::Eq => $left == $right,
+            RelOp::Ne => $left != $right,
+            RelOp::SignedGt => $left > $right,
+            RelOp::UnsignedGt => ($left as $unsigned) > ($right as $unsigned),
+            RelOp::SignedGe => $left >= $right,
+            RelOp::UnsignedGe => ($left as $unsigned) >= ($right as $unsigned),
+            RelOp::SignedLt => $left < $right,
+            RelOp::UnsignedLt => ($left as $unsigned) < ($right as $unsigned),
+            RelOp::SignedLe => $left <= $right,
+            RelOp::UnsignedLe => ($left as $unsigned) <= ($right as $unsigned),
+        }
+    };
+}
+
+fn eval_i32_bin(class: BinOp, left: i32, right: i32) -> Option<i32> {
+    Some(eval_bin!(class, left, right, i32, u32))
+}
+
+fn eval_i64_bin(class: BinOp, left: i64, right: i64) -> Option<i64> {
+    Some(eval_bin!(class, left, right, i64, u64))
+}
+
+fn eval_unary(class: UnaryOp, vtype: VarType, operand: u64) -> u64 {
+    match vtype {
+        VarType::I32 => {
+            let operand = operand as u32;
+
+            (match class {
+                UnaryOp::Ctz => operand.trailing_zeros(),
+                UnaryOp::Clz => operand.leading_zeros(),
+                UnaryOp::Popcnt => operand.count_ones(),
+            }) as u64
+        }
+        VarType::I64 => {
+            (match class {
+                UnaryOp::Ctz => operand.trailing_zeros(),
+                UnaryOp::Clz => operand.leading_zeros(),
+                UnaryOp::Popcnt => operand.count_ones(),
+            }) as u64
+        }
+    }
+}
+
+fn size_mask(bytes: u32) -> u64 {
+    if bytes >= 8 {
+        u64::MAX
+    } else {
+        (1u64 << (bytes * 8)) - 1
+    }
+}
+
+// Bytes of the two 8-byte blocks starting at `address`.
+fn read_bytes(address: u32, block1: u64, block2: u64, bytes: u32) -> u64 {
+    let blocks = (block1 as u128) | ((block2 as u128) << 64);
+
+    ((blocks >> ((address % 8) * 8)) as u64) & size_mask(bytes)
+}
+
+/// Re-execute the step against the semantics of its instruction.
+fn check_step(opcode: &Opcode, step: &StepInfo) -> Result<(), String> {
+    let mismatch = || {
+        Err(format!(
+            "the step {:?} is inconsistent with the instruction {:?}",
+            step, opcode
+        ))
+    };
+    let check = |consistent: bool| if consistent { Ok(()) } else { mismatch() };
+
+    match (opcode, step) {
+        (
+            Opcode::Const {
+                value: expected, ..
+            },
+            StepInfo::I32Const { value },
+        ) => check(*expected as u32 == *value as u32),
+        (
+            Opcode::Const {
+                value: expected, ..
+            },
+            StepInfo::I64Const { value },
+        ) => check(*expected as i64 == *value),
+        (
+            Opcode::Bin { class, .. },
+            StepInfo::I32BinOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_i32_bin(*class, *left, *right) == Some(*value)),
+        (
+            Opcode::Bin { class, .. },
+            StepInfo::I64BinOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_i64_bin(*class, *left, *right) == Some(*value)),
+        (
+            Opcode::BinShift { class, .. },
+            StepInfo::I32BinShiftOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_shift!(class, *left, *right, i32, u32) == *value),
+        (
+            Opcode::BinShift { class, .. },
+            StepInfo::I64BinShiftOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_shift!(class, *left, *right, i64, u64) == *value),
+        (
+            Opcode::BinBit { class, .. },
+            StepInfo::I32BinBitOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(
+            class == step_class
+                && class.eval(*left as u32 as u64, *right as u32 as u64) == *value as u32 as u64,
+        ),
+        (
+            Opcode::BinBit { class, .. },
+            StepInfo::I64BinBitOp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && class.eval(*left as u64, *right as u64) == *value as u64),
+        (
+            Opcode::Unary { class, vtype },
+            StepInfo::UnaryOp {
+                class: step_class,
+                vtype: step_vtype,
+                operand,
+                result,
+            },
+        ) => check(
+            class == step_class
+                && vtype == step_vtype
+                && eval_unary(*class, *vtype, *operand) == *result,
+        ),
+        (Opcode::Test { vtype, .. }, StepInfo::Test { value, result, .. }) => {
+            let is_zero = match vtype {
+                VarType::I32 => *value as u32 == 0,
+                VarType::I64 => *value == 0,
+            };
+
+            check(*result == is_zero as i32)
+        }
+        (
+            Opcode::Rel { class, .. },
+            StepInfo::I32Comp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_rel!(class, *left, *right, u32) == *value),
+        (
+            Opcode::Rel { class, .. },
+            StepInfo::I64Comp {
+                class: step_class,
+                left,
+                right,
+                value,
+            },
+        ) => check(class == step_class && eval_rel!(class, *left, *right, u64) == *value),
+        (Opcode::Conversion { .. }, StepInfo::I32WrapI64 { value, result }) => {
+            check(*value as i32 == *result)
+        }
+        (
+            Opcode::Conversion { .. },
+            StepInfo::I64ExtendI32 {
+                value,
+                result,
+                sign,
+            },
+        ) => check(
+            *result
+                == if *sign {
+                    *value as i64
+                } else {
+                    *value as u32 as i64
+                },
+        ),
+        (Opcode::Conversion { .. }, StepInfo::I32SignExtendI8 { value, result }) => {
+            check(*value as i8 as i32 == *result)
+        }
+        (Opcode::Conversion { .. }, StepInfo::I32SignExtendI16 { value, result }) => {
+            check(*value as i16 as i32 == *result)
+        }
+        (Opcode::Conversion { .. }, StepInfo::I64SignExtendI8 { value, result }) => {
+            check(*value as i8 as i64 == *result)
+        }
+        (Opcode::Conversion { .. }, StepInfo::I64SignExtendI16 { value, result }) => {
+            check(*value as i16 as i64 == *result)
+        }
+        (Opcode::Conversion { .. }, StepInfo::I64SignExtendI32 { value, result }) => {
+            check(*value as i32 as i64 == *result)
+        }
+        (
+            Opcode::Load { offset, size, .. },
+            StepInfo::Load {
+                load_size,
+                offset: step_offset,
+                raw_address,
+                effective_address,
+                value,
+                block_value1,
+                block_value2,
+                ..
+            },
+        ) => {
+            let bytes = load_size.byte_size();
+
+            check(
+                offset == step_offset
+                    && size == load_size
+                    && raw_address.checked_add(*offset) == Some(*effective_address)
+                    && read_bytes(*effective_address, *block_value1, *block_value2, bytes)
+                        == *value & size_mask(bytes),
+            )
+        }
+        (
+            Opcode::Store { offset, size, .. },
+            StepInfo::Store {
+                store_size,
+                offset: step_offset,
+                raw_address,
+                effective_address,
+                pre_block_value1,
+                updated_block_value1,
+                pre_block_value2,
+                updated_block_value2,
+                value,
+                ..
+            },
+        ) => {
+            let bytes = store_size.byte_size() as u32;
+            let shift = (effective_address % 8) * 8;
+            let mask = (size_mask(bytes) as u128) << shift;
+            let pre = (*pre_block_value1 as u128) | ((*pre_block_value2 as u128) << 64);
+            let updated = (pre & !mask) | ((((*value & size_mask(bytes)) as u128) << shift) & mask);
+            let updated_block_value2 = if effective_address % 8 + bytes > 8 {
+                *updated_block_value2
+            } else {
+                // The second block is untouched.
+                *pre_block_value2
+            };
+
+            check(
+                offset == step_offset
+                    && size == store_size
+                    && raw_address.checked_add(*offset) == Some(*effective_address)
+                    && updated as u64 == *updated_block_value1
+                    && (updated >> 64) as u64 == updated_block_value2,
+            )
+        }
+        (Opcode::Call { index }, StepInfo::Call { index: callee }) => check(index == callee),
+        (Opcode::CallIndirect { type_idx }, StepInfo::CallIndirect { type_index, .. }) => {
+            check(type_idx == type_index)
+        }
+        (
+            Opcode::InternalHostCall {
+                function_index,
+                op_index_in_plugin,
+                ..
+            },
+            StepInfo::CallHost {
+                host_function_idx,
+                op_index_in_plugin: step_op_index_in_plugin,
+                ..
+            },
+        ) => check(
+            function_index == host_function_idx && op_index_in_plugin == step_op_index_in_plugin,
+        ),
+        (
+            Opcode::ExternalHostCall { op, sig },
+            StepInfo::ExternalHostCall {
+                op: step_op,
+                sig: step_sig,
+                ..
+            }
+            | StepInfo::ExternalHostCallBuffer {
+                op: step_op,
+                sig: step_sig,
+                ..
+            },
+        ) => check(op == step_op && sig == step_sig),
+        (
+            Opcode::LocalGet { vtype, offset },
+            StepInfo::GetLocal {
+                vtype: step_vtype,
+                depth,
+                ..
+            },
+        )
+        | (
+            Opcode::LocalSet { vtype, offset },
+            StepInfo::SetLocal {
+                vtype: step_vtype,
+                depth,
+                ..
+            },
+        )
+        | (
+            Opcode::LocalTee { vtype, offset },
+            StepInfo::TeeLocal {
+                vtype: step_vtype,
+                depth,
+                ..
+            },
+        ) => check(vtype == step_vtype && *offset == *depth as u64),
+        (Opcode::GlobalGet { idx }, StepInfo::GetGlobal { idx: step_idx, .. })
+        | (Opcode::GlobalSet { idx }, StepInfo::SetGlobal { idx: step_idx, .. }) => {
+            check(*idx == *step_idx as u64)
+        }
+        (
+            Opcode::Select,
+            StepInfo::Select {
+                val1,
+                val2,
+                cond,
+                result,
+                ..
+            },
+        ) => check(*result == if *cond != 0 { *val1 } else { *val2 }),
+        (
+            Opcode::Br { drop, keep, dst_pc },
+            StepInfo::Br {
+                drop: step_drop,
+                keep: step_keep,
+                dst_pc: step_dst_pc,
+                ..
+            },
+        )
+        | (
+            Opcode::BrIf { drop, keep, dst_pc },
+            StepInfo::BrIfNez {
+                drop: step_drop,
+                keep: step_keep,
+                dst_pc: step_dst_pc,
+                ..
+            },
+        )
+        | (
+            Opcode::BrIfEqz { drop, keep, dst_pc },
+            StepInfo::BrIfEqz {
+                drop: step_drop,
+                keep: step_keep,
+                dst_pc: step_dst_pc,
+                ..
+            },
+        ) => check(drop == step_drop && keep == step_keep && dst_pc == step_dst_pc),
+        (
+            Opcode::BrTable { targets },
+            StepInfo::BrTable {
+                index,
+                dst_pc,
+                drop,
+                keep,
+                ..
+            },
+        ) => {
+            // An out of range index takes the default target, which is the last one.
+            let target = targets.get(*index as u32 as usize).or(targets.last());
+
+            check(target.map_or(false, |target| {
+                target.drop == *drop && &target.keep == keep && target.dst_pc == *dst_pc
+            }))
+        }
+        (
+            Opcode::Return { drop, keep },
+            StepInfo::Return {
+                drop: step_drop,
+                keep: step_keep,
+                ..
+            },
+        ) => check(drop == step_drop && keep == step_keep),
+        // The memory pages and the stack are replayed by the checker itself.
+        (Opcode::MemorySize, StepInfo::MemorySize)
+        | (Opcode::MemoryGrow, StepInfo::MemoryGrow { .. })
+        | (Opcode::Drop, StepInfo::Drop) => Ok(()),
+        // `unreachable` traps and is never traced, any other pair does not belong together.
+        _ => mismatch(),
+    }
+}
@@ -0,0 +1,444 @@
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::Value;
+use specs::etable::EventTableEntry;
+use specs::etable::EventTableReader;
+use specs::itable::InstructionTable;
+use specs::itable::OpcodeClass;
+use specs::mtable::AccessType;
+use specs::mtable::LocationType;
+use specs::step::StepInfo;
+use specs::trace_file::TraceFileError;
+use specs::TableBackend;
+use specs::Tables;
+
+use super::memory_event_of_step;
+
+/// How many entries of each trace are searched for a common location after a control flow
+/// divergence.
+const REALIGN_WINDOW: usize = 1 << 12;
+
+/// Where an entry executes: the instruction and the number of guest calls it is nested in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct StepLocation {
+    pub fid: u32,
+    pub iid: u32,
+    pub depth: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MemoryWrite {
+    pub ltype: LocationType,
+    pub offset: u32,
+    pub value: u64,
+}
+
+/// Values exchanged with the host by a host call step.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HostCallValues {
+    pub name: String,
+    pub args: Vec<u64>,
+    pub rets: Vec<u64>,
+}
+
+/// One side of the first divergent step.
+#[derive(Clone, Debug, Serialize)]
+pub struct DivergentStep {
+    pub eid: u32,
+    pub location: StepLocation,
+    pub opcode: String,
+    pub memory_writes: Vec<MemoryWrite>,
+    pub host_call: Option<HostCallValues>,
+}
+
+/// A field of the step whose value differs, `None` if the field is absent on that side.
+#[derive(Clone, Debug, Serialize)]
+pub struct FieldDiff {
+    pub field: String,
+    pub a: Option<Value>,
+    pub b: Option<Value>,
+}
+
+/// Where both traces reach the same location again after a control flow divergence.
+#[derive(Clone, Debug, Serialize)]
+pub struct Realignment {
+    /// Entries each trace executes from the divergent step until the common location.
+    pub skipped_a: usize,
+    pub skipped_b: usize,
+    pub location: StepLocation,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Divergence {
+    /// Index of the step in both traces, every step before it matches.
+    pub step: usize,
+    /// `None` if the trace ends before the step.
+    pub a: Option<DivergentStep>,
+    pub b: Option<DivergentStep>,
+    /// Whether the steps execute different instructions or at different call depths, rather than
+    /// the same instruction on different values.
+    pub control_flow: bool,
+    /// `None` for a divergence in values, or if the traces don't meet again within
+    /// `REALIGN_WINDOW` entries.
+    pub realigned: Option<Realignment>,
+    pub fields: Vec<FieldDiff>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct OpcodeClassDelta {
+    pub class: String,
+    pub a: usize,
+    pub b: usize,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct TraceDiff {
+    pub steps_a: usize,
+    pub steps_b: usize,
+    /// `None` if the traces are identical.
+    pub first_divergence: Option<Divergence>,
+    /// Opcode classes whose number of executed instructions differs.
+    pub opcode_class_deltas: Vec<OpcodeClassDelta>,
+}
+
+type Entries<'a> = Box<dyn Iterator<Item = Result<EventTableEntry, TraceFileError>> + 'a>;
+
+/// Replays the event table of an execution, tracking the call depth of each entry.
+struct AlignedEntries<'a> {
+    itable: &'a InstructionTable,
+    entries: Entries<'a>,
+    /// Entries read ahead to realign the traces.
+    lookahead: VecDeque<(EventTableEntry, StepLocation)>,
+    depth: usize,
+    opcode_classes: BTreeMap<OpcodeClass, usize>,
+}
+
+impl<'a> AlignedEntries<'a> {
+    fn new(tables: &'a Tables) -> Self {
+        let entries = tables
+            .execution_tables
+            .etable
+            .iter()
+            .flat_map(|etable| -> Entries<'a> {
+                match etable {
+                    TableBackend::Memory(etable) => {
+                        Box::new(etable.entries().iter().cloned().map(Ok))
+                    }
+                    TableBackend::File(path) => match EventTableReader::open(path) {
+                        Ok(reader) => Box::new(reader),
+                        Err(err) => Box::new(std::iter::once(Err(err))),
+                    },
+                }
+            });
+
+        Self {
+            itable: &tables.compilation_tables.itable,
+            entries: Box::new(entries),
+            lookahead: VecDeque::new(),
+            depth: 0,
+            opcode_classes: BTreeMap::new(),
+        }
+    }
+
+    fn next(&mut self) -> Result<Option<(EventTableEntry, StepLocation)>, TraceFileError> {
+        match self.lookahead.pop_front() {
+            Some(step) => Ok(Some(step)),
+            None => self.read(),
+        }
+    }
+
+    /// Returns the location of the `n`th entry after the next one, without consuming entries.
+    fn peek_location(&mut self, n: usize) -> Result<Option<StepLocation>, TraceFileError> {
+        while self.lookahead.len() <= n {
+            match self.read()? {
+                Some(step) => self.lookahead.push_back(step),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(self.lookahead[n].1))
+    }
+
+    fn read(&mut self) -> Result<Option<(EventTableEntry, StepLocation)>, TraceFileError> {
+        let entry = match self.entries.next().transpose()? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let location = StepLocation {
+            fid: entry.fid,
+            iid: entry.iid,
+            depth: self.depth,
+        };
+
+        match &entry.step_info {
+            StepInfo::Call { .. } | StepInfo::CallIndirect { .. } => self.depth += 1,
+            // The entry function returns to the host at depth 0.
+            StepInfo::Return { .. } => self.depth = self.depth.saturating_sub(1),
+            _ => (),
+        }
+
+        *self
+            .opcode_classes
+            .entry((&entry.get_instruction(self.itable).opcode).into())
+            .or_default() += 1;
+
+        Ok(Some((entry, location)))
+    }
+
+    /// Counts the remaining entries, returning the opcode class counts of the whole trace.
+    fn finish(mut self) -> Result<BTreeMap<OpcodeClass, usize>, TraceFileError> {
+        // Entries read ahead are counted already.
+        while self.read()?.is_some() {}
+
+        Ok(self.opcode_classes)
+    }
+}
+
+fn divergent_step(
+    itable: &InstructionTable,
+    entry: &EventTableEntry,
+    location: StepLocation,
+) -> DivergentStep {
+    DivergentStep {
+        eid: entry.eid,
+        location,
+        opcode: format!("{:?}", entry.get_instruction(itable).opcode),
+        memory_writes: memory_event_of_step(entry)
+            .into_iter()
+            .filter(|entry| entry.atype == AccessType::Write)
+            .map(|entry| MemoryWrite {
+                ltype: entry.ltype,
+                offset: entry.offset,
+                value: entry.value,
+            })
+            .collect(),
+        host_call: host_call_values(&entry.step_info),
+    }
+}
+
+fn host_call_values(step_info: &StepInfo) -> Option<HostCallValues> {
+    let split = |is_ret: bool, values: Vec<u64>| {
+        if is_ret {
+            (vec![], values)
+        } else {
+            (values, vec![])
+        }
+    };
+
+    let (name, (args, rets)) = match step_info {
+        StepInfo::CallHost {
+            function_name,
+            args,
+            ret_val,
+            ..
+        } => (
+            function_name.clone(),
+            (args.clone(), ret_val.iter().cloned().collect()),
+        ),
+        StepInfo::ExternalHostCall { op, value, sig } => (
+            format!("external op {}", op),
+            split(sig.is_ret(), value.iter().cloned().collect()),
+        ),
+        StepInfo::ExternalHostCallBuffer {
+            op, values, sig, ..
+        } => (
+            format!("external op {} buffer", op),
+            split(sig.is_ret(), values.clone()),
+        ),
+        _ => return None,
+    };
+
+    Some(HostCallValues { name, args, rets })
+}
+
+/// Flattens an entry into its `StepInfo` fields, plus the variant name and the stack pointer and
+/// memory size the step starts with.
+fn step_fields(entry: &EventTableEntry) -> BTreeMap<String, Value> {
+    let mut fields = BTreeMap::new();
+
+    fields.insert("sp".to_string(), Value::from(entry.sp));
+    fields.insert(
+        "allocated_memory_pages".to_string(),
+        Value::from(entry.allocated_memory_pages),
+    );
+
+    // `StepInfo` is externally tagged: a unit variant is its name, others map the name to fields.
+    match serde_json::to_value(&entry.step_info).unwrap() {
+        Value::Object(variant) => {
+            for (name, value) in variant {
+                fields.insert("variant".to_string(), Value::String(name));
+
+                match value {
+                    Value::Object(values) => fields.extend(values),
+                    value => {
+                        fields.insert("value".to_string(), value);
+                    }
+                }
+            }
+        }
+        variant => {
+            fields.insert("variant".to_string(), variant);
+        }
+    }
+
+    fields
+}
+
+fn field_diffs(a: &EventTableEntry, b: &EventTableEntry) -> Vec<FieldDiff> {
+    let a = step_fields(a);
+    let mut b = step_fields(b);
+
+    let mut diffs = vec![];
+
+    for (field, a) in a {
+        let b = b.remove(&field);
+
+        if Some(&a) != b.as_ref() {
+            diffs.push(FieldDiff {
+                field,
+                a: Some(a),
+                b,
+            });
+        }
+    }
+
+    diffs.extend(b.into_iter().map(|(field, b)| FieldDiff {
+        field,
+        a: None,
+        b: Some(b),
+    }));
+
+    diffs.sort_by(|a, b| a.field.cmp(&b.field));
+
+    diffs
+}
+
+/// Finds the closest pair of entries at the same location, searching from the divergent steps at
+/// `a` and `b` through the next `REALIGN_WINDOW` entries of each trace.
+fn realign(
+    a: StepLocation,
+    b: StepLocation,
+    entries_a: &mut AlignedEntries,
+    entries_b: &mut AlignedEntries,
+) -> Result<Option<Realignment>, TraceFileError> {
+    fn location(
+        divergent: StepLocation,
+        entries: &mut AlignedEntries,
+        skipped: usize,
+    ) -> Result<Option<StepLocation>, TraceFileError> {
+        match skipped {
+            0 => Ok(Some(divergent)),
+            skipped => entries.peek_location(skipped - 1),
+        }
+    }
+
+    // Pairs are visited by the total number of skipped entries, so the first match skips the
+    // fewest.
+    for total in 1..2 * REALIGN_WINDOW - 1 {
+        for skipped_a in total.saturating_sub(REALIGN_WINDOW - 1)..=total.min(REALIGN_WINDOW - 1) {
+            let skipped_b = total - skipped_a;
+
+            if let (Some(location_a), Some(location_b)) = (
+                location(a, entries_a, skipped_a)?,
+                location(b, entries_b, skipped_b)?,
+            ) {
+                if location_a == location_b {
+                    return Ok(Some(Realignment {
+                        skipped_a,
+                        skipped_b,
+                        location: location_a,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn opcode_class_deltas(
+    a: BTreeMap<OpcodeClass, usize>,
+    b: BTreeMap<OpcodeClass, usize>,
+) -> Vec<OpcodeClassDelta> {
+    let mut classes = a.keys().chain(b.keys()).cloned().collect::<Vec<_>>();
+    classes.sort();
+    classes.dedup();
+
+    classes
+        .into_iter()
+        .map(|class| OpcodeClassDelta {
+            class: format!("{:?}", class),
+            a: a.get(&class).cloned().unwrap_or_default(),
+            b: b.get(&class).cloned().unwrap_or_default(),
+        })
+        .filter(|delta| delta.a != delta.b)
+        .collect()
+}
+
+/// Compares two executions step by step. Entries match if they execute the same instruction at
+/// the same call depth with the same `StepInfo`, the first entries that don't match are reported.
+/// If they execute different instructions, the traces are realigned on the next entries at a
+/// common location, skipping the entries executed by one trace only. Opcode class counts cover
+/// the whole traces.
+pub fn diff_traces(a: &Tables, b: &Tables) -> Result<TraceDiff, TraceFileError> {
+    let mut entries_a = AlignedEntries::new(a);
+    let mut entries_b = AlignedEntries::new(b);
+
+    let mut step = 0;
+    let mut first_divergence = None;
+
+    while first_divergence.is_none() {
+        let (a_step, b_step) = match (entries_a.next()?, entries_b.next()?) {
+            (None, None) => break,
+            steps => steps,
+        };
+
+        let control_flow = a_step.as_ref().map(|(_, location)| location)
+            != b_step.as_ref().map(|(_, location)| location);
+        let fields = match (&a_step, &b_step) {
+            (Some((a, _)), Some((b, _))) => field_diffs(a, b),
+            _ => vec![],
+        };
+
+        if control_flow || !fields.is_empty() {
+            let realigned = match (&a_step, &b_step) {
+                (Some((_, a)), Some((_, b))) if control_flow => {
+                    realign(*a, *b, &mut entries_a, &mut entries_b)?
+                }
+                _ => None,
+            };
+
+            first_divergence = Some(Divergence {
+                step,
+                a: a_step
+                    .map(|(entry, location)| divergent_step(entries_a.itable, &entry, location)),
+                b: b_step
+                    .map(|(entry, location)| divergent_step(entries_b.itable, &entry, location)),
+                control_flow,
+                realigned,
+                fields,
+            });
+        }
+
+        step += 1;
+    }
+
+    let opcode_classes_a = entries_a.finish()?;
+    let opcode_classes_b = entries_b.finish()?;
+
+    Ok(TraceDiff {
+        steps_a: opcode_classes_a.values().sum(),
+        steps_b: opcode_classes_b.values().sum(),
+        first_divergence,
+        opcode_class_deltas: opcode_class_deltas(opcode_classes_a, opcode_classes_b),
+    })
+}
+
+/// Compares the executions saved by `Tables::write` in two directories.
+pub fn diff_trace_dirs(a: &PathBuf, b: &PathBuf) -> std::io::Result<TraceDiff> {
+    Ok(diff_traces(&Tables::read(a)?, &Tables::read(b)?)?)
+}
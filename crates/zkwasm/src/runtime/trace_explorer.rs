@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use specs::etable::EventTable;
+use specs::etable::EventTableEntry;
+use specs::imtable::InitMemoryTable;
+use specs::itable::InstructionTable;
+use specs::itable::InstructionTableEntry;
+use specs::mtable::AccessType;
+use specs::mtable::LocationType;
+use specs::mtable::MemoryTableEntry;
+use specs::step::StepInfo;
+use specs::TableBackend;
+use specs::Tables;
+
+use super::memory_event_of_step;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Break when the function is entered.
+    Function(u32),
+    /// Break before the instruction executes.
+    Instruction { fid: u32, iid: u32 },
+    /// Break on any access to the 8-byte heap block containing the byte address.
+    Memory(u32),
+}
+
+/// Steps forward and backward through an execution trace, reconstructing the stack, globals and
+/// heap from the memory events of each step.
+///
+/// The cursor points at the entry about to execute, so the state shown is the state before it.
+pub struct TraceExplorer {
+    itable: Arc<InstructionTable>,
+    imtable: Arc<InitMemoryTable>,
+    function_names: HashMap<u32, String>,
+    entries: Vec<EventTableEntry>,
+    cursor: usize,
+    breakpoints: Vec<Breakpoint>,
+}
+
+impl TraceExplorer {
+    /// `function_names` maps function index to its name, see `Observer::function_names`.
+    pub fn new(tables: &Tables, function_names: HashMap<u32, String>) -> std::io::Result<Self> {
+        let mut entries = vec![];
+
+        for etable in &tables.execution_tables.etable {
+            match etable {
+                TableBackend::Memory(etable) => entries.extend(etable.entries().iter().cloned()),
+                TableBackend::File(path) => entries.extend(EventTable::read(path)?.unwrap()),
+            }
+        }
+
+        Ok(Self {
+            itable: tables.compilation_tables.itable.clone(),
+            imtable: tables.compilation_tables.imtable.clone(),
+            function_names,
+            entries,
+            cursor: 0,
+            breakpoints: vec![],
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn current(&self) -> Option<&EventTableEntry> {
+        self.entries.get(self.cursor)
+    }
+
+    pub fn instruction(&self) -> Option<&InstructionTableEntry> {
+        self.current()
+            .map(|entry| entry.get_instruction(&self.itable))
+    }
+
+    pub fn function_name(&self, fid: u32) -> String {
+        self.function_names
+            .get(&fid)
+            .cloned()
+            .unwrap_or_else(|| format!("func[{}]", fid))
+    }
+
+    pub fn find_function(&self, name: &str) -> Option<u32> {
+        self.function_names
+            .iter()
+            .find(|(_, function_name)| *function_name == name)
+            .map(|(fid, _)| *fid)
+    }
+
+    /// Memory events of the current entry.
+    pub fn memory_events(&self) -> Vec<MemoryTableEntry> {
+        self.current().map(memory_event_of_step).unwrap_or_default()
+    }
+
+    /// Move the cursor by `steps`, stopping at either end of the trace. Returns the steps taken.
+    pub fn step(&mut self, steps: isize) -> usize {
+        let target = self
+            .cursor
+            .saturating_add_signed(steps)
+            .min(self.entries.len().saturating_sub(1));
+        let taken = target.abs_diff(self.cursor);
+
+        self.cursor = target;
+
+        taken
+    }
+
+    /// Move the cursor to the entry with `eid`.
+    pub fn goto(&mut self, eid: u32) -> bool {
+        match self.entries.binary_search_by_key(&eid, |entry| entry.eid) {
+            Ok(index) => {
+                self.cursor = index;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) {
+        if !self.breakpoints.contains(&breakpoint) {
+            self.breakpoints.push(breakpoint);
+        }
+    }
+
+    pub fn breakpoints(&self) -> &Vec<Breakpoint> {
+        &self.breakpoints
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Move the cursor to the next (or previous if `forward` is false) entry hitting a breakpoint,
+    /// or to the end of the trace. Returns the breakpoint hit.
+    pub fn resume(&mut self, forward: bool) -> Option<Breakpoint> {
+        loop {
+            if self.step(if forward { 1 } else { -1 }) == 0 {
+                return None;
+            }
+
+            if let Some(breakpoint) = self
+                .breakpoints
+                .iter()
+                .find(|breakpoint| self.hits(breakpoint, self.cursor))
+            {
+                return Some(*breakpoint);
+            }
+        }
+    }
+
+    fn hits(&self, breakpoint: &Breakpoint, index: usize) -> bool {
+        let entry = &self.entries[index];
+
+        match breakpoint {
+            Breakpoint::Function(fid) => entry.fid == *fid && self.is_frame_entry(index),
+            Breakpoint::Instruction { fid, iid } => entry.fid == *fid && entry.iid == *iid,
+            Breakpoint::Memory(address) => memory_event_of_step(entry)
+                .iter()
+                .any(|event| event.ltype == LocationType::Heap && event.offset == address / 8),
+        }
+    }
+
+    fn is_frame_entry(&self, index: usize) -> bool {
+        if index == 0 {
+            return true;
+        }
+
+        match &self.entries[index - 1].step_info {
+            StepInfo::Call { .. } | StepInfo::CallIndirect { .. } => true,
+            // Returned from a function called by the host, e.g. the start function.
+            StepInfo::Return { .. } => self.entries[index - 1].last_jump_eid == 0,
+            _ => false,
+        }
+    }
+
+    /// Value at the location before the current entry executes, `None` if it has never been
+    /// accessed or initialized.
+    pub fn value_at(&self, ltype: LocationType, offset: u32) -> Option<u64> {
+        self.last_access(ltype, offset, |_| true)
+            .map(|event| event.value)
+            .or_else(|| {
+                self.imtable
+                    .try_find(ltype, offset)
+                    .map(|init_entry| init_entry.value)
+            })
+    }
+
+    /// The eid of the last entry before the cursor writing the location.
+    pub fn last_write(&self, ltype: LocationType, offset: u32) -> Option<u32> {
+        self.last_access(ltype, offset, |event| event.atype == AccessType::Write)
+            .map(|event| event.eid)
+    }
+
+    fn last_access(
+        &self,
+        ltype: LocationType,
+        offset: u32,
+        filter: impl Fn(&MemoryTableEntry) -> bool,
+    ) -> Option<MemoryTableEntry> {
+        self.entries[..self.cursor.min(self.entries.len())]
+            .iter()
+            .rev()
+            .find_map(|entry| {
+                memory_event_of_step(entry)
+                    .into_iter()
+                    .rev()
+                    .find(|event| event.ltype == ltype && event.offset == offset && filter(event))
+            })
+    }
+
+    /// The top `depth` values of the stack, from the top down.
+    pub fn stack(&self, depth: u32) -> Vec<(u32, Option<u64>)> {
+        let sp = match self.current() {
+            Some(entry) => entry.sp,
+            None => return vec![],
+        };
+
+        (sp + 1..=sp + depth)
+            .map(|offset| (offset, self.value_at(LocationType::Stack, offset)))
+            .collect()
+    }
+
+    /// Stack values of the current frame from the top down to the stack pointer at its call
+    /// site, i.e. the declared locals and the operands. Parameters sit right below them.
+    pub fn frame(&self) -> Vec<(u32, Option<u64>)> {
+        let entry = match self.current() {
+            Some(entry) => entry,
+            None => return vec![],
+        };
+
+        let call_site_sp = if entry.last_jump_eid == 0 {
+            self.entries[0].sp
+        } else {
+            match self
+                .entries
+                .binary_search_by_key(&entry.last_jump_eid, |entry| entry.eid)
+            {
+                Ok(index) => self.entries[index].sp,
+                // The call happened in a slice that is not loaded.
+                Err(_) => entry.sp,
+            }
+        };
+
+        self.stack(call_site_sp.saturating_sub(entry.sp))
+    }
+
+    /// The 8-byte heap block containing `address`.
+    pub fn memory(&self, address: u32) -> u64 {
+        self.value_at(LocationType::Heap, address / 8).unwrap_or(0)
+    }
+
+    pub fn global(&self, index: u32) -> Option<u64> {
+        self.value_at(LocationType::Global, index)
+    }
+}
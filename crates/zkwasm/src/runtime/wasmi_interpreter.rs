@@ -6,6 +6,7 @@ use wasmi::ModuleInstance;
 use wasmi::RuntimeValue;
 
 use crate::foreign::context::ContextOutput;
+use crate::foreign::log_helper::GUEST_LOG_PLUGIN;
 
 use super::host::host_env::ExecEnv;
 use super::host::host_env::HostEnv;
@@ -26,6 +27,7 @@ impl Execution<RuntimeValue> for CompiledImage<wasmi::NotStartedModuleRef<'_>> {
         let mut exec_env = ExecEnv {
             host_env: externals,
             observer: monitor.expose_observer(),
+            memory: self.instance.not_started_instance().memory_by_index(0),
         };
         let instance = self
             .instance
@@ -55,6 +57,12 @@ impl Execution<RuntimeValue> for CompiledImage<wasmi::NotStartedModuleRef<'_>> {
                 .borrow()
                 .expose_context_outputs(),
         );
+        let guest_logs = exec_env
+            .host_env
+            .phantom_env
+            .get_context_of_plugin(GUEST_LOG_PLUGIN)
+            .map(|context| context.borrow().expose_guest_logs())
+            .unwrap_or_default();
 
         Ok(ExecutionResult {
             result,
@@ -63,6 +71,7 @@ impl Execution<RuntimeValue> for CompiledImage<wasmi::NotStartedModuleRef<'_>> {
             public_inputs_and_outputs,
             outputs,
             context_outputs,
+            guest_logs,
         })
     }
 }
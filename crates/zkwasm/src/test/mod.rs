@@ -1,23 +1,128 @@
+use crate::circuits::config::MIN_K;
 use crate::loader::slice::Slices;
 use crate::loader::ZkWasmLoader;
 use crate::runtime::host::default_env::DefaultHostEnvBuilder;
 use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::host::host_env::HostEnv;
 use crate::runtime::host::HostEnvBuilder;
 use crate::runtime::monitor::table_monitor::TableMonitor;
+use crate::runtime::ExecutionResult;
 
 use anyhow::Result;
 use halo2_proofs::pairing::bn256::Fr;
+use specs::Tables;
 use specs::TraceBackend;
 use wabt::wat2wasm_with_features;
 use wabt::Features;
+use wasmi::RuntimeValue;
 
 mod test_wasm_instructions;
 
 mod spec;
+mod test_circuit_size;
+mod test_context_file;
+mod test_diagnostics;
+mod test_estimate;
+mod test_guest_log;
+mod test_init_memory_table;
+mod test_io_schema;
+mod test_mixed_k;
+mod test_phantom;
+mod test_precheck;
+mod test_profile;
 mod test_rlp;
 #[cfg(feature = "continuation")]
 mod test_rlp_slice;
 mod test_start;
+mod test_tables_io;
+mod test_trace_checker;
+mod test_trace_diff;
+mod test_trace_explorer;
+mod test_trace_file;
+
+/// Run the function within the host environment, then return the execution result along with
+/// the monitor holding the trace.
+pub fn run_with_env(
+    k: u32,
+    env: HostEnv,
+    wasm: &[u8],
+    function_name: &str,
+    phantom_functions: &Vec<String>,
+    backend: TraceBackend,
+) -> Result<(ExecutionResult<RuntimeValue>, TableMonitor)> {
+    let module = ZkWasmLoader::parse_module(wasm)?;
+
+    let mut monitor = TableMonitor::new(k, phantom_functions, backend, &env);
+    let mut loader = ZkWasmLoader::new(k, env)?;
+    loader.set_entry(function_name.to_string());
+
+    let runner = loader.compile(&module, &mut monitor)?;
+    let execution_result = loader.run(runner, &mut monitor)?;
+
+    Ok((execution_result, monitor))
+}
+
+/// Run the function within the host environment, then return the execution result along with
+/// the tables traced by `backend`.
+pub fn execute_with_env(
+    k: u32,
+    env: HostEnv,
+    wasm: &[u8],
+    function_name: &str,
+    phantom_functions: &Vec<String>,
+    backend: TraceBackend,
+) -> Result<(ExecutionResult<RuntimeValue>, Tables)> {
+    let (execution_result, monitor) =
+        run_with_env(k, env, wasm, function_name, phantom_functions, backend)?;
+
+    Ok((execution_result, monitor.into_tables()))
+}
+
+/// Run `zkmain` of the textual module within the default host environment. Only tests should
+/// use this function.
+pub fn run_zkmain(
+    k: u32,
+    textual_repr: &str,
+    arg: ExecutionArg,
+    phantom_functions: &Vec<String>,
+    backend: TraceBackend,
+) -> Result<(ExecutionResult<RuntimeValue>, TableMonitor)> {
+    let wasm = wabt::wat2wasm(textual_repr).expect("failed to parse wat");
+    let env = DefaultHostEnvBuilder.create_env(k, arg);
+
+    run_with_env(k, env, &wasm, "zkmain", phantom_functions, backend)
+}
+
+/// Trace `zkmain` of the textual module in memory, without inputs. Only tests should use this
+/// function.
+pub fn trace_zkmain(textual_repr: &str) -> Result<Tables> {
+    let (_, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec![],
+        TraceBackend::Memory,
+    )?;
+
+    Ok(monitor.into_tables())
+}
+
+/// Run the function within the host environment and generate trace, then test circuit with mock
+/// prover.
+pub fn test_circuit_with_host_env(
+    k: u32,
+    env: HostEnv,
+    wasm: Vec<u8>,
+    function_name: String,
+) -> Result<()> {
+    let (execution_result, tables) =
+        execute_with_env(k, env, &wasm, &function_name, &vec![], TraceBackend::Memory)?;
+    let instances: Vec<Fr> = execution_result.public_inputs_and_outputs();
+
+    Slices::new(k, tables)?.mock_test_all(instances)?;
+
+    Ok(())
+}
 
 /// Run the function and generate trace, then test circuit with mock prover.
 pub fn test_circuit_with_env(
@@ -27,35 +132,22 @@ pub fn test_circuit_with_env(
     public_inputs: Vec<u64>,
     private_inputs: Vec<u64>,
 ) -> Result<()> {
-    let module = ZkWasmLoader::parse_module(&wasm)?;
-
     let env = DefaultHostEnvBuilder.create_env(
         k,
         ExecutionArg {
             public_inputs,
             private_inputs,
             context_inputs: vec![],
+            indexed_inputs: vec![],
         },
     );
-    let mut monitor = TableMonitor::new(k, &vec![], TraceBackend::Memory, &env);
-    let mut loader = ZkWasmLoader::new(k, env)?;
-    loader.set_entry(function_name);
-
-    let runner = loader.compile(&module, &mut monitor)?;
-
-    let execution_result = loader.run(runner, &mut monitor)?;
-    let instances: Vec<Fr> = execution_result.public_inputs_and_outputs();
-
-    Slices::new(k, monitor.into_tables())?.mock_test_all(instances)?;
 
-    Ok(())
+    test_circuit_with_host_env(k, env, wasm, function_name)
 }
 
 /// Run test function and generate trace, then test circuit with mock prover. Only tests should
 /// use this function.
 fn test_circuit_noexternal(textual_repr: &str) -> Result<()> {
-    use crate::circuits::config::MIN_K;
-
     let mut features = Features::new();
     features.enable_sign_extension();
 
@@ -1,94 +1,470 @@
-/*
-    use wast::Error;
-
-    #[cfg(test)]
-    fn run_spec_test(_file_name: &str) -> Result<(), Error> {
-        todo!();
-
-        /*
-        let path = format!("src/test/spec/{}.wast", file_name);
-        let file = fs::read_to_string(&path).unwrap();
-
-        let mut lexer = Lexer::new(&file);
-        lexer.allow_confusing_unicode(true);
-        let parse_buffer = ParseBuffer::new_with_lexer(lexer)?;
-
-        let wast: Wast = wast::parser::parse(&parse_buffer)?;
-        let imports = ImportsBuilder::default();
-        let mut externals = NopExternals;
-
-        let compiler = WasmInterpreter::new();
-        let mut compile_outcome = None;
-
-        for directive in wast.directives {
-            match directive {
-                wast::WastDirective::Wat(wat) => match wat {
-                    wast::QuoteWat::Wat(wat) => match wat {
-                        wast::Wat::Module(module) => {
-                            let compiled = compiler
-                                .compile_from_wast(module, &imports, &HashMap::default())
-                                .unwrap();
-                            compile_outcome = Some(compiled);
-                        }
-                        wast::Wat::Component(_) => todo!(),
-                    },
-                    wast::QuoteWat::QuoteModule(_, _) => todo!(),
-                    wast::QuoteWat::QuoteComponent(_, _) => todo!(),
-                },
-                wast::WastDirective::AssertMalformed { .. } => todo!(),
-                wast::WastDirective::AssertInvalid { .. } => todo!(),
-                wast::WastDirective::Register { .. } => todo!(),
-                wast::WastDirective::Invoke(..) => todo!(),
-                wast::WastDirective::AssertTrap { .. } => todo!(),
-                wast::WastDirective::AssertReturn {
-                    span: _span,
-                    exec,
-                    results: _results,
-                } => {
-                    let from_wastarg = |arg: &WastArg| match arg {
-                        WastArg::Core(core) => match core {
-                            wast::core::WastArgCore::I32(v) => Value::I32(*v),
-                            wast::core::WastArgCore::I64(v) => Value::I64(*v),
-                            wast::core::WastArgCore::F32(_) => todo!(),
-                            wast::core::WastArgCore::F64(_) => todo!(),
-                            wast::core::WastArgCore::V128(_) => todo!(),
-                            wast::core::WastArgCore::RefNull(_) => todo!(),
-                            wast::core::WastArgCore::RefExtern(_) => todo!(),
-                        },
-                        WastArg::Component(_) => todo!(),
-                    };
-
-                    let _actual_results = match exec {
-                        wast::WastExecute::Invoke(invoke) => compiler.run(
-                            &mut externals,
-                            &compile_outcome.unwrap(),
-                            invoke.name,
-                            invoke.args.iter().map(|arg| from_wastarg(arg)).collect(),
-                        ),
-                        wast::WastExecute::Wat(_) => todo!(),
-                        wast::WastExecute::Get { .. } => todo!(),
+//! Runs the official WebAssembly spec tests (`*.wast` in this directory) through the tracer and the
+//! mock prover.
+//!
+//! The tracer only invokes an entry without arguments, so the invocations of a module are compiled
+//! into an entry function appended to the module. An `assert_return` calls the export with constant
+//! arguments and traps if the result differs from the expected one, thus a single traced execution
+//! of the entry proven by the mock prover checks all the assertions of a module, in order. Each
+//! `assert_trap` runs in an entry of its own and must fail in the tracer.
+//!
+//! Directives about floating-point or vector values, malformed or invalid modules, exhaustion and
+//! linking are skipped, as are modules the tracer cannot instantiate, e.g. those importing from
+//! other modules.
+//!
+//! Only the integer spec files are vendored, see `SPEC_FILES`. Spec files left out on purpose are
+//! listed in `SKIPPED_SPEC_FILES` with the reason.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::anyhow;
+use anyhow::Result;
+use parity_wasm::elements::BlockType;
+use parity_wasm::elements::CodeSection;
+use parity_wasm::elements::ExportEntry;
+use parity_wasm::elements::ExportSection;
+use parity_wasm::elements::Func;
+use parity_wasm::elements::FuncBody;
+use parity_wasm::elements::FunctionSection;
+use parity_wasm::elements::FunctionType;
+use parity_wasm::elements::ImportCountType;
+use parity_wasm::elements::Instruction;
+use parity_wasm::elements::Instructions;
+use parity_wasm::elements::Internal;
+use parity_wasm::elements::Module;
+use parity_wasm::elements::Section;
+use parity_wasm::elements::Type;
+use parity_wasm::elements::TypeSection;
+use parity_wasm::elements::ValueType;
+use wabt::script::Action;
+use wabt::script::CommandKind;
+use wabt::script::ScriptParser;
+use wabt::script::Value;
+use wabt::Features;
+
+use crate::circuits::config::MIN_K;
+use crate::loader::ZkWasmLoader;
+use crate::runtime::host::default_env::DefaultHostEnvBuilder;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::host::HostEnvBuilder;
+use crate::runtime::monitor::table_monitor::TableMonitor;
+use crate::test::test_circuit_with_env;
+use specs::TraceBackend;
+
+const ENTRY: &str = "__spec_entry";
+
+/// The spec files run by `test_spec_suite`, every `*.wast` in this directory must be listed here or
+/// in `SKIPPED_SPEC_FILES`.
+const SPEC_FILES: &[&str] = &[
+    "i32.wast",
+    "i64.wast",
+    "int_exprs.wast",
+    "int_literals.wast",
+];
+
+/// Integer spec files which are not run, and why.
+const SKIPPED_SPEC_FILES: &[(&str, &str)] = &[(
+    "conversions.wast",
+    "its only module mixes the integer conversions with floating-point ones",
+)];
+
+#[derive(Default)]
+struct SpecReport {
+    passed: usize,
+    skipped: usize,
+    failed: usize,
+    failures: Vec<String>,
+}
+
+impl fmt::Display for SpecReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} skipped, {} failed",
+            self.passed, self.skipped, self.failed
+        )?;
+
+        for failure in &self.failures {
+            write!(f, "\n    {}", failure)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Invocation {
+    line: u64,
+    field: String,
+    args: Vec<Instruction>,
+    // None if the result is dropped.
+    expected: Option<Vec<Instruction>>,
+}
+
+struct SpecModule {
+    line: u64,
+    wasm: Vec<u8>,
+    // Invocations in the order of the script, they share the state of the module.
+    invocations: Vec<Invocation>,
+    traps: Vec<Invocation>,
+}
+
+fn to_instruction(value: &Value) -> Option<Instruction> {
+    match value {
+        Value::I32(value) => Some(Instruction::I32Const(*value)),
+        Value::I64(value) => Some(Instruction::I64Const(*value)),
+        _ => None,
+    }
+}
+
+fn to_instructions(values: &[Value]) -> Option<Vec<Instruction>> {
+    values.iter().map(to_instruction).collect()
+}
+
+// Append an exported entry function running the invocations to the module.
+fn build_entry(wasm: &[u8], invocations: &[Invocation]) -> Result<Vec<u8>> {
+    let mut module: Module = parity_wasm::deserialize_buffer(wasm)?;
+
+    for section in [
+        Section::Type(TypeSection::with_types(vec![])),
+        Section::Function(FunctionSection::with_entries(vec![])),
+        Section::Export(ExportSection::with_entries(vec![])),
+        Section::Code(CodeSection::with_bodies(vec![])),
+    ] {
+        // Fails if the section exists.
+        let _ = module.insert_section(section);
+    }
+
+    let imported_functions = module.import_count(ImportCountType::Function) as u32;
+    let exports = module.export_section().unwrap().entries().to_vec();
+    let types = module.type_section().unwrap().types().to_vec();
+    let functions = module.function_section().unwrap().entries().to_vec();
+
+    let mut code = vec![];
+    for invocation in invocations {
+        let fid = exports
+            .iter()
+            .find_map(|export| match export.internal() {
+                Internal::Function(fid) if export.field() == invocation.field => Some(*fid),
+                _ => None,
+            })
+            .ok_or(anyhow!("no exported function {}", invocation.field))?;
+        let Type::Function(signature) = fid
+            .checked_sub(imported_functions)
+            .and_then(|index| functions.get(index as usize))
+            .and_then(|function| types.get(function.type_ref() as usize))
+            .ok_or(anyhow!("{} is not defined in the module", invocation.field))?;
+
+        code.extend(invocation.args.iter().cloned());
+        code.push(Instruction::Call(fid));
+
+        match &invocation.expected {
+            Some(expected) => {
+                if expected.len() != signature.results().len() {
+                    return Err(anyhow!("{} returns a different arity", invocation.field));
+                }
+
+                for (expected, ty) in expected.iter().zip(signature.results()).rev() {
+                    code.push(expected.clone());
+                    code.push(match ty {
+                        ValueType::I64 => Instruction::I64Ne,
+                        _ => Instruction::I32Ne,
+                    });
+                    code.push(Instruction::If(BlockType::NoResult));
+                    code.push(Instruction::Unreachable);
+                    code.push(Instruction::End);
+                }
+            }
+            None => code.extend(signature.results().iter().map(|_| Instruction::Drop)),
+        }
+    }
+    code.push(Instruction::End);
+
+    let type_ref = types.len() as u32;
+    let fid = imported_functions + functions.len() as u32;
+
+    module
+        .type_section_mut()
+        .unwrap()
+        .types_mut()
+        .push(Type::Function(FunctionType::new(vec![], vec![])));
+    module
+        .function_section_mut()
+        .unwrap()
+        .entries_mut()
+        .push(Func::new(type_ref));
+    module
+        .code_section_mut()
+        .unwrap()
+        .bodies_mut()
+        .push(FuncBody::new(vec![], Instructions::new(code)));
+    module
+        .export_section_mut()
+        .unwrap()
+        .entries_mut()
+        .push(ExportEntry::new(ENTRY.to_string(), Internal::Function(fid)));
+
+    Ok(parity_wasm::serialize(module)?)
+}
+
+// Run the entry in the tracer only, for invocations expected to trap.
+fn run_trace(wasm: Vec<u8>) -> Result<()> {
+    let module = ZkWasmLoader::parse_module(&wasm)?;
+    let env = DefaultHostEnvBuilder.create_env(
+        MIN_K,
+        ExecutionArg {
+            public_inputs: vec![],
+            private_inputs: vec![],
+            context_inputs: vec![],
+            indexed_inputs: vec![],
+        },
+    );
+    let mut monitor = TableMonitor::new(MIN_K, &vec![], TraceBackend::Memory, &env);
+    let mut loader = ZkWasmLoader::new(MIN_K, env)?;
+    loader.set_entry(ENTRY.to_string());
+
+    let runner = loader.compile(&module, &mut monitor)?;
+    loader.run(runner, &mut monitor)?;
+
+    Ok(())
+}
+
+fn check_module(file_name: &str, module: SpecModule, report: &mut SpecReport) {
+    if !module.invocations.is_empty() {
+        let assertions = module
+            .invocations
+            .iter()
+            .filter(|invocation| invocation.expected.is_some())
+            .count();
+
+        match build_entry(&module.wasm, &module.invocations)
+            .and_then(|wasm| test_circuit_with_env(MIN_K, wasm, ENTRY.to_string(), vec![], vec![]))
+        {
+            Ok(()) => report.passed += assertions,
+            Err(err) => {
+                let lines = module
+                    .invocations
+                    .iter()
+                    .filter(|invocation| invocation.expected.is_some())
+                    .map(|invocation| invocation.line.to_string())
+                    .collect::<Vec<_>>();
+
+                report.failed += assertions;
+                report.failures.push(format!(
+                    "{}:{}: assertions at lines {} failed: {}",
+                    file_name,
+                    module.line,
+                    lines.join(", "),
+                    err
+                ));
+            }
+        }
+    }
+
+    for trap in module.traps {
+        let line = trap.line;
+
+        let failure = match build_entry(&module.wasm, &[trap]).map(run_trace) {
+            Ok(Err(_)) => {
+                report.passed += 1;
+                continue;
+            }
+            Ok(Ok(())) => "expected a trap".to_string(),
+            Err(err) => err.to_string(),
+        };
+
+        report.failed += 1;
+        report
+            .failures
+            .push(format!("{}:{}: {}", file_name, line, failure));
+    }
+}
+
+// The module an action refers to, None if it is skipped.
+fn target<'a>(
+    modules: &'a mut [Option<SpecModule>],
+    names: &HashMap<String, usize>,
+    module: &Option<String>,
+) -> Option<&'a mut SpecModule> {
+    let index = match module {
+        Some(name) => *names.get(name)?,
+        None => modules.len().checked_sub(1)?,
+    };
+
+    modules[index].as_mut()
+}
+
+fn run_spec_test(path: &Path) -> Result<SpecReport> {
+    let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+    let source = fs::read(path)?;
+
+    let mut features = Features::new();
+    features.enable_sign_extension();
+
+    let mut parser: ScriptParser =
+        ScriptParser::from_source_and_name_with_features(&source, &file_name, features)?;
+
+    let mut report = SpecReport::default();
+    let mut modules: Vec<Option<SpecModule>> = vec![];
+    let mut names: HashMap<String, usize> = HashMap::new();
+
+    while let Some(command) = parser.next()? {
+        let line = command.line;
+
+        match command.kind {
+            CommandKind::Module { module, name } => {
+                let wasm = module.into_vec();
+
+                // Modules importing from other modules or the spectest host can't be instantiated.
+                let module = match ZkWasmLoader::parse_module(&wasm) {
+                    Ok(module) if module.module().import_section().is_none() => Some(SpecModule {
+                        line,
+                        wasm,
+                        invocations: vec![],
+                        traps: vec![],
+                    }),
+                    _ => {
+                        report.skipped += 1;
+                        None
                     }
-                    .unwrap();
+                };
 
-                    todo!()
+                if let Some(name) = name {
+                    names.insert(name, modules.len());
+                }
+                modules.push(module);
+            }
+            CommandKind::Register { name, as_name } => {
+                // Registered modules are only used by imports, which are not supported.
+                let index = match name {
+                    Some(name) => names.get(&name).cloned(),
+                    None => modules.len().checked_sub(1),
+                };
+                if let Some(index) = index {
+                    names.insert(as_name, index);
                 }
-                wast::WastDirective::AssertExhaustion { .. } => todo!(),
-                wast::WastDirective::AssertUnlinkable { .. } => todo!(),
-                wast::WastDirective::AssertException { .. } => todo!(),
             }
+            CommandKind::AssertReturn {
+                action:
+                    Action::Invoke {
+                        module,
+                        field,
+                        args,
+                    },
+                expected,
+            } => match (
+                target(&mut modules, &names, &module),
+                to_instructions(&args),
+                to_instructions(&expected),
+            ) {
+                (Some(module), Some(args), Some(expected)) => module.invocations.push(Invocation {
+                    line,
+                    field,
+                    args,
+                    expected: Some(expected),
+                }),
+                _ => report.skipped += 1,
+            },
+            CommandKind::PerformAction(Action::Invoke {
+                module,
+                field,
+                args,
+            }) => match (
+                target(&mut modules, &names, &module),
+                to_instructions(&args),
+            ) {
+                (Some(module), Some(args)) => module.invocations.push(Invocation {
+                    line,
+                    field,
+                    args,
+                    expected: None,
+                }),
+                _ => report.skipped += 1,
+            },
+            CommandKind::AssertTrap {
+                action:
+                    Action::Invoke {
+                        module,
+                        field,
+                        args,
+                    },
+                ..
+            } => match (
+                target(&mut modules, &names, &module),
+                to_instructions(&args),
+            ) {
+                (Some(module), Some(args)) => module.traps.push(Invocation {
+                    line,
+                    field,
+                    args,
+                    expected: None,
+                }),
+                _ => report.skipped += 1,
+            },
+            _ => report.skipped += 1,
         }
-        Ok(())
-        */
     }
 
-    /*
-    mod tests {
-        use super::run_spec_test;
+    for module in modules.into_iter().flatten() {
+        check_module(&file_name, module, &mut report);
+    }
+
+    Ok(report)
+}
+
+mod tests {
+    use std::collections::BTreeSet;
+    use std::path::Path;
 
-        #[test]
-        fn test_spec_i32() {
-            run_spec_test("i32").unwrap()
+    use super::run_spec_test;
+    use super::SKIPPED_SPEC_FILES;
+    use super::SPEC_FILES;
+
+    #[test]
+    fn test_spec_suite() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/test/spec");
+
+        let vendored = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| {
+                path.extension()
+                    .map_or(false, |extension| extension == "wast")
+            })
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+            .collect::<BTreeSet<_>>();
+        let unlisted = vendored
+            .iter()
+            .filter(|file| {
+                !SPEC_FILES.contains(&file.as_str())
+                    && SKIPPED_SPEC_FILES
+                        .iter()
+                        .all(|(skipped, _)| skipped != file)
+            })
+            .collect::<Vec<_>>();
+        assert!(
+            unlisted.is_empty(),
+            "spec files neither run nor skipped: {:?}",
+            unlisted
+        );
+
+        for (file, reason) in SKIPPED_SPEC_FILES {
+            println!("{}: skipped, {}", file, reason);
         }
+
+        let mut failed = vec![];
+        for file in SPEC_FILES {
+            let report = run_spec_test(&dir.join(file)).unwrap();
+
+            println!("{}: {}", file, report);
+
+            // A file whose assertions are all skipped would pass silently.
+            if report.failed != 0 || report.passed == 0 {
+                failed.push(file);
+            }
+        }
+
+        assert!(failed.is_empty(), "spec tests failed: {:?}", failed);
     }
-    */
-*/
+}
@@ -0,0 +1,136 @@
+mod tests {
+    use anyhow::Result;
+    use halo2_proofs::pairing::bn256::Fr;
+    use specs::TraceBackend;
+
+    use crate::circuits::compute_slice_capability;
+    use crate::circuits::config::MAX_K;
+    use crate::circuits::config::MIN_K;
+    use crate::circuits::config::MIN_SMALL_K;
+    use crate::circuits::image_table::compute_maximal_pages;
+    use crate::circuits::image_table::PAGE_ENTRIES;
+    use crate::circuits::image_table::PAGE_SIZE;
+    use crate::circuits::utils::image_table::init_memory_entries_offset;
+    use crate::circuits::utils::image_table::GLOBAL_CAPABILITY;
+    use crate::circuits::utils::image_table::STACK_CAPABILITY;
+    use crate::circuits::zkwasm_circuit::reserve_rows;
+    use crate::circuits::ZkWasmCircuit;
+    use crate::loader::slice::Slices;
+    use crate::runtime::host::default_env::ExecutionArg;
+    use crate::test::run_zkmain;
+
+    /// Bit operations of both widths run through the bit table, and the memory declares every
+    /// page the circuit supports and writes to the last one.
+    fn guest(pages: u32) -> String {
+        format!(
+            r#"
+            (module
+                (memory {})
+                (func (export "zkmain")
+                  i32.const {}
+                  i64.const 0x0123456789abcdef
+                  i64.store
+
+                  i32.const {}
+                  i64.load
+                  i64.const 0xf0f0f0f0f0f0f0f0
+                  i64.and
+                  i64.const 0x0f0f0f0f0f0f0f0f
+                  i64.or
+                  i64.const -1
+                  i64.xor
+                  i64.popcnt
+                  drop
+
+                  i32.const 0x12345678
+                  i32.const 0x0ff00ff0
+                  i32.xor
+                  i32.popcnt
+                  drop
+                )
+               )
+            "#,
+            pages,
+            pages * PAGE_SIZE - 8,
+            pages * PAGE_SIZE - 8,
+        )
+    }
+
+    fn mock_test(k: u32) -> Result<()> {
+        let pages = compute_maximal_pages(k);
+        assert!(pages > 0, "no memory page for K = {}", k);
+
+        let (result, monitor) = run_zkmain(
+            k,
+            &guest(pages),
+            ExecutionArg::default(),
+            &vec![],
+            TraceBackend::Memory,
+        )?;
+        let instances: Vec<Fr> = result.public_inputs_and_outputs();
+
+        for circuit in Slices::new(k, monitor.into_tables())? {
+            let circuit = circuit?;
+
+            // Below MIN_K the op table only fits u4 limbs.
+            assert_eq!(
+                matches!(
+                    circuit,
+                    ZkWasmCircuit::SmallOngoing(_) | ZkWasmCircuit::SmallLastSliceCircuit(_)
+                ),
+                k < MIN_K
+            );
+
+            circuit.mock_test(instances.clone())?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_small_circuit_mock() {
+        for k in [MIN_SMALL_K, MIN_SMALL_K + 1] {
+            mock_test(k).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_default_circuit_mock() {
+        mock_test(MIN_K).unwrap();
+    }
+
+    #[test]
+    #[ignore = "the mock prover of K=23 takes tens of GB, run it with --ignored"]
+    fn test_large_circuit_mock() {
+        // Large circuits are for memory-heavy guests, which did not fit the previous limit K=22.
+        assert!(compute_maximal_pages(23) > compute_maximal_pages(22));
+
+        mock_test(23).unwrap();
+    }
+
+    /// Checks the layout of the large circuits without the mock prover, see
+    /// `test_large_circuit_mock`.
+    #[test]
+    fn test_large_circuit_layout() {
+        for k in 23..=MAX_K {
+            let pages = compute_maximal_pages(k);
+            assert!(pages > compute_maximal_pages(k - 1));
+
+            // The image table holds the init memory of every supported page.
+            let image_table_rows = init_memory_entries_offset(k)
+                + STACK_CAPABILITY
+                + GLOBAL_CAPABILITY
+                + (pages * PAGE_ENTRIES) as usize;
+            assert!(image_table_rows + reserve_rows(k) <= 1 << k, "K = {}", k);
+
+            assert!(compute_slice_capability(k) > compute_slice_capability(k - 1));
+        }
+    }
+
+    #[test]
+    fn test_maximal_pages_grow_with_k() {
+        for k in MIN_SMALL_K..MAX_K {
+            assert!(compute_maximal_pages(k) < compute_maximal_pages(k + 1));
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use specs::args::parse_args;
+use specs::context::check_context_chain;
+use specs::context::ContextFile;
+use specs::context::ContextFileError;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::runtime::host::default_env::ExecutionArg;
+
+#[test]
+fn test_context_file_chain() {
+    // Each run continues the Fibonacci sequence kept in the context.
+    let textual_repr = r#"
+    (module
+        (import "env" "wasm_read_context" (func $wasm_read_context (result i64)))
+        (import "env" "wasm_write_context" (func $wasm_write_context (param i64)))
+        (func (export "zkmain")
+          (local i64 i64)
+          (local.set 0 (call $wasm_read_context))
+          (local.set 1 (call $wasm_read_context))
+          (call $wasm_write_context (local.get 1))
+          (call $wasm_write_context (i64.add (local.get 0) (local.get 1)))
+        )
+       )
+    "#;
+
+    let image_checksum = ("1".to_string(), "2".to_string());
+
+    let dir = std::env::temp_dir().join("zkwasm-context-file-chain");
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut context_inputs = parse_args(&["1:i64", "1:i64"]).unwrap();
+    let mut chain = vec![];
+
+    for run in 0..3 {
+        let (result, _) = run_zkmain(
+            MIN_K,
+            textual_repr,
+            ExecutionArg {
+                context_inputs: context_inputs.clone(),
+                ..Default::default()
+            },
+            &vec![],
+            TraceBackend::Memory,
+        )
+        .unwrap();
+
+        let path = dir.join(format!("{}.context.json", run));
+        ContextFile::new(
+            image_checksum.clone(),
+            &context_inputs,
+            result.context_outputs.0,
+        )
+        .write(&path)
+        .unwrap();
+
+        // The next run loads the file as `--ctxin <path>:context` does.
+        context_inputs = parse_args(&[format!("{}:context", path.display())]).unwrap();
+        chain.push(ContextFile::read(&path).unwrap());
+    }
+
+    assert_eq!(context_inputs, vec![3, 5]);
+    assert!(check_context_chain(&chain).is_ok());
+
+    // A run is missing.
+    assert!(matches!(
+        check_context_chain(&[chain[0].clone(), chain[2].clone()]),
+        Err((1, ContextFileError::NotContinued { .. }))
+    ));
+
+    // The last run executed another image.
+    let mut tampered = chain.clone();
+    tampered[2].image_checksum.0 = "3".to_string();
+    assert!(matches!(
+        check_context_chain(&tampered),
+        Err((2, ContextFileError::ImageMismatch { .. }))
+    ));
+    assert!(chain[2].check_image(&image_checksum).is_ok());
+}
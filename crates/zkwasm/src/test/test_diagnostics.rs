@@ -0,0 +1,67 @@
+use halo2_proofs::pairing::bn256::Fr;
+use specs::itable::OpcodeClass;
+use specs::step::StepInfo;
+use specs::TableBackend;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::circuits::diagnostics::CircuitTable;
+use crate::circuits::diagnostics::MockTestError;
+use crate::loader::slice::Slices;
+use crate::runtime::host::default_env::ExecutionArg;
+
+#[test]
+fn test_diagnose_tampered_trace() {
+    let textual_repr = r#"
+    (module
+        (func (export "zkmain")
+          (drop (i32.const 1))
+        )
+       )
+    "#;
+
+    let (result, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+    let instances: Vec<Fr> = result.public_inputs_and_outputs();
+
+    let mut tables = monitor.into_tables();
+
+    // Push a value other than the one encoded in the instruction.
+    let tampered_eid = match &mut tables.execution_tables.etable[0] {
+        TableBackend::Memory(etable) => {
+            let entry = etable
+                .entries_mut()
+                .iter_mut()
+                .find(|entry| matches!(entry.step_info, StepInfo::I32Const { .. }))
+                .unwrap();
+            entry.step_info = StepInfo::I32Const { value: 2 };
+
+            entry.eid
+        }
+        TableBackend::File(_) => unreachable!(),
+    };
+
+    let err = Slices::new(MIN_K, tables)
+        .unwrap()
+        .mock_test_all(instances)
+        .unwrap_err()
+        .downcast::<MockTestError>()
+        .unwrap();
+
+    assert_eq!(err.slice, Some(0));
+    assert!(!err.failures.is_empty());
+    // Only the rows of the tampered event are inconsistent.
+    assert!(err
+        .failures
+        .iter()
+        .filter(|failure| failure.table == CircuitTable::EventTable)
+        .all(|failure| failure.eid == Some(tampered_eid)
+            && failure.opcode_class == Some(OpcodeClass::Const)));
+}
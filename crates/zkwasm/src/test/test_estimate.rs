@@ -0,0 +1,54 @@
+mod tests {
+    use crate::circuits::config::MAX_K;
+    use crate::circuits::config::MIN_SMALL_K;
+    use crate::loader::estimate::estimate_k;
+    use crate::loader::estimate::ProvingCostModel;
+    use crate::loader::ZkWasmLoader;
+    use crate::runtime::host::default_env::DefaultHostEnvBuilder;
+    use crate::runtime::host::default_env::ExecutionArg;
+
+    #[test]
+    fn test_estimate_k() {
+        let textual_repr = r#"
+        (module
+            (memory 2)
+            (func (export "zkmain")
+              (local i32)
+              (loop
+                (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+                (br_if 0 (i32.lt_u (local.get 0) (i32.const 100)))
+              )
+            )
+           )
+        "#;
+
+        let wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
+        let module = ZkWasmLoader::parse_module(&wasm).unwrap();
+
+        let estimation = estimate_k(
+            &DefaultHostEnvBuilder,
+            &module,
+            &vec![],
+            ExecutionArg {
+                public_inputs: vec![],
+                private_inputs: vec![],
+                context_inputs: vec![],
+                indexed_inputs: vec![],
+            },
+            MIN_SMALL_K..=MAX_K,
+            &ProvingCostModel::default(),
+        )
+        .unwrap();
+
+        assert!(estimation.summary.instructions > 100);
+        assert_eq!(estimation.summary.maximal_memory_pages, 2);
+        assert_eq!(estimation.candidates.len() as u32, MAX_K - MIN_SMALL_K + 1);
+        assert!(estimation
+            .candidates
+            .iter()
+            .all(|candidate| candidate.slices == 1));
+        // 2 pages do not fit the smallest circuit
+        assert!(!estimation.candidates[0].is_feasible());
+        assert_eq!(estimation.recommended, Some(MIN_SMALL_K + 1));
+    }
+}
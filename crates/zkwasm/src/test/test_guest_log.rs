@@ -0,0 +1,102 @@
+use anyhow::Result;
+use halo2_proofs::pairing::bn256::Fr;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::foreign::log_helper::GuestLog;
+use crate::loader::slice::Slices;
+use crate::runtime::host::default_env::ExecutionArg;
+
+fn run(textual_repr: &str) -> Result<Vec<GuestLog>> {
+    let (result, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec!["log_hello".to_string()],
+        TraceBackend::Memory,
+    )?;
+    let instances: Vec<Fr> = result.public_inputs_and_outputs();
+
+    Slices::new(MIN_K, monitor.into_tables())?.mock_test_all(instances)?;
+
+    Ok(result.guest_logs)
+}
+
+fn log_hello(address: u32, len: u32) -> String {
+    format!(
+        r#"
+        (module
+            (type (;0;) (func (param i32) (result i64)))
+            (type (;1;) (func (param i32 i32 i32)))
+
+            (import "env" "wasm_input" (func $wasm_input (type 0)))
+            (import "env" "wasm_log" (func $wasm_log (type 1)))
+
+            (func $log_hello
+              i32.const 3
+              i32.const {}
+              i32.const {}
+              call $wasm_log
+            )
+
+            (func $zkmain
+              call $log_hello
+            )
+
+            (memory (;0;) 1)
+            (data (i32.const 0) "hello")
+            (export "log_hello" (func $log_hello))
+            (export "zkmain" (func $zkmain))
+           )
+        "#,
+        address, len
+    )
+}
+
+#[test]
+fn test_guest_log_in_phantom() {
+    let logs = run(&log_hello(0, 5)).unwrap();
+
+    assert_eq!(
+        logs,
+        vec![GuestLog {
+            level: log::Level::Info,
+            function: Some("log_hello".to_string()),
+            message: "hello".to_string(),
+        }]
+    );
+}
+
+#[test]
+fn test_guest_log_out_of_bounds() {
+    // The message ends past the single page of the linear memory.
+    assert!(run(&log_hello(65536 - 2, 5)).is_err());
+    assert!(run(&log_hello(0xffff_fffe, 5)).is_err());
+}
+
+#[test]
+fn test_guest_log_outside_phantom() {
+    let textual_repr = r#"
+        (module
+            (type (;0;) (func (param i32) (result i64)))
+            (type (;1;) (func (param i32 i32 i32)))
+
+            (import "env" "wasm_input" (func $wasm_input (type 0)))
+            (import "env" "wasm_log" (func $wasm_log (type 1)))
+
+            (func $zkmain
+              i32.const 3
+              i32.const 0
+              i32.const 5
+              call $wasm_log
+            )
+
+            (memory (;0;) 1)
+            (data (i32.const 0) "hello")
+            (export "zkmain" (func $zkmain))
+           )
+        "#;
+
+    assert!(run(textual_repr).is_err());
+}
@@ -0,0 +1,109 @@
+use halo2_proofs::pairing::bn256::Fr;
+use specs::imtable::InitMemoryTable;
+use specs::imtable::InitMemoryTableDiff;
+use specs::imtable::InitMemoryTableEntry;
+use specs::imtable::INIT_MEMORY_CHUNK_SIZE;
+use specs::mtable::LocationType;
+use specs::mtable::VarType;
+
+use super::trace_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::circuits::utils::image_table::encode_compilation_table_values;
+use crate::circuits::utils::image_table::EncodeImageTable;
+use crate::circuits::utils::image_table::ImageTableLayouter;
+use crate::loader::slice::Slices;
+
+fn heap_entry(offset: u32, value: u64, eid: u32) -> InitMemoryTableEntry {
+    InitMemoryTableEntry {
+        ltype: LocationType::Heap,
+        is_mutable: true,
+        offset,
+        vtype: VarType::I64,
+        value,
+        eid,
+    }
+}
+
+#[test]
+fn test_update_shares_untouched_chunks() {
+    let base = InitMemoryTable::new(vec![
+        heap_entry(0, 1, 0),
+        heap_entry(INIT_MEMORY_CHUNK_SIZE, 2, 0),
+        heap_entry(INIT_MEMORY_CHUNK_SIZE * 3 + 7, 3, 0),
+    ]);
+
+    let mut diff = InitMemoryTableDiff::default();
+    diff.insert(heap_entry(1, 4, 10));
+    diff.insert(heap_entry(INIT_MEMORY_CHUNK_SIZE * 3 + 7, 5, 11));
+
+    let post = base.update(&diff);
+
+    assert_eq!(post.len(), 4);
+    assert_eq!(post.try_find(LocationType::Heap, 1).unwrap().value, 4);
+    assert_eq!(
+        post.try_find(LocationType::Heap, INIT_MEMORY_CHUNK_SIZE * 3 + 7)
+            .unwrap()
+            .eid,
+        11
+    );
+
+    // The table before the update is untouched.
+    assert_eq!(base.len(), 3);
+    assert!(base.try_find(LocationType::Heap, 1).is_none());
+
+    // Only the entries of the two written chunks are revisited.
+    let mut updated = post
+        .updated_entries(&base)
+        .map(|entry| entry.offset)
+        .collect::<Vec<_>>();
+    updated.sort();
+    assert_eq!(updated, vec![0, 1, INIT_MEMORY_CHUNK_SIZE * 3 + 7]);
+}
+
+#[test]
+fn test_incremental_post_image() {
+    let textual_repr = r#"
+    (module
+        (memory 1 2)
+        (global $counter (mut i32) (i32.const 0))
+        (data (i32.const 8) "\2a")
+        (data (i32.const 16384) "\2b")
+        (func (export "zkmain")
+          (local i32)
+          (block
+            (loop
+              (global.set $counter (i32.add (global.get $counter) (i32.const 1)))
+              (i64.store (i32.mul (global.get $counter) (i32.const 8)) (i64.const 7))
+              (br_if 0 (i32.lt_u (global.get $counter) (i32.const 4)))
+            )
+          )
+          (local.set 0 (i32.load (i32.const 16384)))
+          (drop (memory.grow (i32.const 1)))
+          (i32.store (i32.const 65536) (local.get 0))
+        )
+       )
+    "#;
+
+    // memory grows to 2 pages, which do not fit MIN_SMALL_K
+    let k = MIN_K;
+    let slices = Slices::<Fr>::new(k, trace_zkmain(textual_repr).unwrap()).unwrap();
+
+    for circuit in slices {
+        let circuit = circuit.unwrap();
+        let slice = circuit.slice();
+
+        let pre: ImageTableLayouter<Fr> = slice.encode_pre_compilation_table_values(k);
+        let post = slice.encode_post_compilation_table_values(k, &pre);
+        let expected: ImageTableLayouter<Fr> = encode_compilation_table_values(
+            k,
+            &slice.itable,
+            &slice.br_table,
+            &slice.elem_table,
+            &slice.post_inherited_frame_table,
+            &slice.post_initialization_state,
+            &slice.post_imtable,
+        );
+
+        assert_eq!(post.plain(), expected.plain());
+    }
+}
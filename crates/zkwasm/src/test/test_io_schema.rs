@@ -0,0 +1,181 @@
+use serde_json::json;
+use specs::io_schema::IoSchema;
+use specs::io_schema::IoSchemaError;
+use specs::io_schema::Stream;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::runtime::host::default_env::ExecutionArg;
+
+const SCHEMA: &str = r#"
+    {
+        "public": [
+            { "name": "a", "type": "u32" },
+            { "name": "b", "type": "u64" }
+        ],
+        "private": [
+            { "name": "secret", "type": "bytes32" }
+        ],
+        "outputs": [
+            { "name": "sum", "type": "u64" },
+            { "name": "secret", "type": "bytes32" }
+        ]
+    }
+"#;
+
+#[test]
+fn test_io_schema_round_trip_through_guest() {
+    let textual_repr = r#"
+    (module
+        (import "env" "wasm_input" (func $wasm_input (param i32) (result i64)))
+        (import "env" "wasm_output" (func $wasm_output (param i64)))
+        (func (export "zkmain")
+          (call $wasm_output
+            (i64.add (call $wasm_input (i32.const 1)) (call $wasm_input (i32.const 1))))
+          (call $wasm_output (call $wasm_input (i32.const 0)))
+          (call $wasm_output (call $wasm_input (i32.const 0)))
+          (call $wasm_output (call $wasm_input (i32.const 0)))
+          (call $wasm_output (call $wasm_input (i32.const 0)))
+        )
+       )
+    "#;
+
+    let schema: IoSchema = serde_json::from_str(SCHEMA).unwrap();
+    let secret = format!("0x{}", hex::encode((0..32).collect::<Vec<u8>>()));
+    let inputs = schema
+        .encode(&json!({
+            "public": { "b": "0x10", "a": 7 },
+            "private": { "secret": secret },
+        }))
+        .unwrap();
+
+    // Fields are encoded in the order of the schema.
+    assert_eq!(inputs.public_inputs, vec![7, 16]);
+    assert_eq!(inputs.private_inputs[0], 0x0706050403020100);
+
+    let (result, _) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg {
+            public_inputs: inputs.public_inputs,
+            private_inputs: inputs.private_inputs,
+            context_inputs: inputs.context_inputs,
+            indexed_inputs: vec![],
+        },
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+
+    let outputs = schema.decode_outputs(&result.outputs).unwrap();
+    assert_eq!(outputs["sum"], json!(23));
+    assert_eq!(outputs["secret"], json!(secret));
+
+    // The last word of the secret is missing.
+    assert!(matches!(
+        schema.decode_outputs(&result.outputs[..4]),
+        Err(IoSchemaError::UnexpectedEnd { .. })
+    ));
+}
+
+#[test]
+fn test_io_schema_encoding() {
+    let schema: IoSchema = serde_json::from_value(json!({
+        "context": [
+            { "name": "root", "type": "field" },
+            { "name": "label", "type": "string" },
+            { "name": "pair", "type": { "array": { "of": "u32", "len": 2 } } },
+            { "name": "list", "type": { "array": { "of": "u64" } } }
+        ]
+    }))
+    .unwrap();
+
+    let values = json!({
+        "root": "0x0100000000000000020000000000000003",
+        "label": "zkWasm!!x",
+        "pair": [1, 2],
+        "list": [3, 4, 5],
+    });
+    let words = schema.encode_stream(Stream::Context, &values).unwrap();
+
+    assert_eq!(
+        words,
+        vec![
+            3,
+            2,
+            1,
+            0,
+            9,
+            u64::from_le_bytes(*b"zkWasm!!"),
+            'x' as u64,
+            1,
+            2,
+            3,
+            3,
+            4,
+            5
+        ]
+    );
+    assert_eq!(
+        serde_json::Value::Object(schema.decode_stream(Stream::Context, &words).unwrap()),
+        json!({
+            "root": "0x100000000000000020000000000000003",
+            "label": "zkWasm!!x",
+            "pair": [1, 2],
+            "list": [3, 4, 5],
+        })
+    );
+
+    let encode = |field: &str, value: serde_json::Value| {
+        let mut values = values.clone();
+        values[field] = value;
+
+        schema.encode_stream(Stream::Context, &values)
+    };
+
+    // Not smaller than the modulus of the scalar field.
+    assert!(matches!(
+        encode(
+            "root",
+            json!("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001")
+        ),
+        Err(IoSchemaError::InvalidValue { .. })
+    ));
+    assert!(matches!(
+        encode("pair", json!([1, 0x100000000u64])),
+        Err(IoSchemaError::InvalidValue { field, .. }) if field == "pair[1]"
+    ));
+    assert!(matches!(
+        encode("pair", json!([1])),
+        Err(IoSchemaError::InvalidValue { .. })
+    ));
+
+    let mut values = values.clone();
+    values.as_object_mut().unwrap().remove("label");
+    assert!(matches!(
+        schema.encode_stream(Stream::Context, &values),
+        Err(IoSchemaError::MissingField { field, .. }) if field == "label"
+    ));
+    values["extra"] = json!(1);
+    assert!(matches!(
+        schema.encode_stream(Stream::Context, &values),
+        Err(IoSchemaError::UnknownField { field, .. }) if field == "extra"
+    ));
+
+    assert!(matches!(
+        schema.decode_stream(Stream::Context, &[words.clone(), vec![0]].concat()),
+        Err(IoSchemaError::TrailingWords { count: 1, .. })
+    ));
+
+    // A string longer than the stream, including lengths whose word count would overflow.
+    for len in [17, u64::MAX - 6, u64::MAX] {
+        let mut words = words.clone();
+        words[4] = len;
+
+        assert!(matches!(
+            schema.decode_stream(Stream::Context, &words),
+            Err(IoSchemaError::UnexpectedEnd { .. })
+        ));
+    }
+}
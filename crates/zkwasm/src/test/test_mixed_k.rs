@@ -0,0 +1,49 @@
+mod tests {
+    use std::thread;
+
+    use crate::circuits::config::MAX_K;
+    use crate::circuits::config::MIN_K;
+    use crate::circuits::config::MIN_SMALL_K;
+    use crate::loader::ZkWasmLoader;
+    use crate::runtime::host::default_env::DefaultHostEnvBuilder;
+    use crate::runtime::host::HostEnvBuilder;
+    use crate::test::test_circuit_with_env;
+
+    fn wasm() -> Vec<u8> {
+        let textual_repr = r#"
+        (module
+            (func (export "zkmain")
+              i32.const 1
+              drop
+            )
+           )
+        "#;
+
+        wabt::wat2wasm(&textual_repr).expect("failed to parse wat")
+    }
+
+    #[test]
+    fn test_mixed_k_mock() {
+        let handles = [MIN_K, MIN_K + 1]
+            .into_iter()
+            .map(|k| {
+                thread::spawn(move || {
+                    test_circuit_with_env(k, wasm(), "zkmain".to_string(), vec![], vec![])
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_unsupported_k() {
+        for k in [MIN_SMALL_K - 1, MAX_K + 1] {
+            let env = DefaultHostEnvBuilder.create_env_without_value(k);
+
+            assert!(ZkWasmLoader::new(k, env).is_err());
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use anyhow::Result;
+use halo2_proofs::pairing::bn256::Fr;
+use specs::phantom::PhantomFunctionTable;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::loader::slice::Slices;
+use crate::loader::ZkWasmLoader;
+use crate::runtime::host::default_env::DefaultHostEnvBuilder;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::host::HostEnvBuilder;
+use crate::runtime::monitor::table_monitor::TableMonitor;
+
+fn run(textual_repr: &str) -> Result<(PhantomFunctionTable, Vec<Fr>)> {
+    let (result, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec!["^phantom_".to_string()],
+        TraceBackend::Memory,
+    )?;
+    let instances: Vec<Fr> = result.public_inputs_and_outputs();
+
+    let tables = monitor.into_tables();
+    let phantom_functions = (*tables.compilation_tables.phantom_functions).clone();
+    Slices::new(MIN_K, tables)?.mock_test_all(instances.clone())?;
+
+    Ok((phantom_functions, instances))
+}
+
+#[test]
+fn test_phantom_replaced_bodies() {
+    let textual_repr = r#"
+    (module
+        (import "env" "wasm_output" (func $wasm_output (param i64)))
+        (memory 1 1)
+        (data (i32.const 0) "\01\02\03\04\05\06\07\08")
+
+        (func $sum (param i32 i32) (result i64)
+          (local i64)
+          (block
+            (loop
+              (br_if 1 (i32.eqz (local.get 1)))
+              (local.set 2 (i64.add (local.get 2) (i64.load8_u (local.get 0))))
+              (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+              (local.set 1 (i32.sub (local.get 1) (i32.const 1)))
+              (br 0)
+            )
+          )
+          (local.get 2)
+        )
+
+        (func $phantom_sum_i64 (param i32 i32) (result i64)
+          (call $sum (local.get 0) (local.get 1))
+        )
+
+        (func $phantom_sum_i32 (param i32 i32) (result i32)
+          (i32.wrap_i64 (call $sum (local.get 0) (local.get 1)))
+        )
+
+        (func $zkmain
+          (call $wasm_output (call $phantom_sum_i64 (i32.const 0) (i32.const 8)))
+          (call $wasm_output
+            (i64.extend_i32_u (call $phantom_sum_i32 (i32.const 2) (i32.const 3))))
+        )
+
+        (export "phantom_sum_i64" (func $phantom_sum_i64))
+        (export "phantom_sum_i32" (func $phantom_sum_i32))
+        (export "zkmain" (func $zkmain))
+       )
+    "#;
+
+    let (phantom_functions, instances) = run(textual_repr).unwrap();
+
+    // The results are proven as returned by the phantom functions.
+    assert_eq!(instances, vec![Fr::from(36), Fr::from(12)]);
+
+    assert_eq!(
+        phantom_functions
+            .entries()
+            .iter()
+            .map(|entry| (entry.name.as_str(), entry.signature.params.len()))
+            .collect::<Vec<_>>(),
+        vec![("phantom_sum_i64", 2), ("phantom_sum_i32", 2)]
+    );
+    // The replaced bodies are recorded, the i32 one wraps the result of the i64 one.
+    let bodies = phantom_functions
+        .entries()
+        .iter()
+        .map(|entry| entry.body.len())
+        .collect::<Vec<_>>();
+    assert!(bodies[0] > 0);
+    assert_eq!(bodies[1], bodies[0] + 1);
+}
+
+#[test]
+fn test_phantom_rejects_side_effects() {
+    let phantom = |body: &str| {
+        format!(
+            r#"
+            (module
+                (import "env" "wasm_input" (func $wasm_input (param i32) (result i64)))
+                (memory 1 1)
+                (global $g (mut i32) (i32.const 0))
+                (table 1 funcref)
+
+                (func $helper
+                  (i32.store (i32.const 0) (i32.const 1))
+                )
+
+                (func $phantom_f
+                  {}
+                )
+
+                (func $zkmain
+                  (call $phantom_f)
+                )
+
+                (export "phantom_f" (func $phantom_f))
+                (export "zkmain" (func $zkmain))
+               )
+            "#,
+            body
+        )
+    };
+
+    assert!(run(&phantom("(nop)")).is_ok());
+
+    for body in [
+        "(global.set $g (i32.const 1))",
+        "(i64.store (i32.const 0) (i64.const 1))",
+        "(drop (memory.grow (i32.const 0)))",
+        "(drop (call $wasm_input (i32.const 0)))",
+        "(call_indirect (i32.const 0))",
+        // Side effects of callees are rejected as well.
+        "(call $helper)",
+    ] {
+        assert!(run(&phantom(body)).is_err(), "{} is accepted", body);
+    }
+}
+
+#[test]
+fn test_phantom_resolution() {
+    let resolve = |phantom_body: &str, exported: &str| {
+        let textual_repr = format!(
+            r#"
+            (module
+                (func $a (result i32) {})
+                (func $b (result i32) (i32.const 2))
+                (func $zkmain (drop (call ${})))
+                (export "phantom" (func ${}))
+                (export "zkmain" (func $zkmain))
+               )
+            "#,
+            phantom_body, exported, exported
+        );
+
+        let wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
+        let module = ZkWasmLoader::parse_module(&wasm).unwrap();
+
+        let env = DefaultHostEnvBuilder.create_env_without_value(MIN_K);
+        let mut monitor = TableMonitor::new(
+            MIN_K,
+            &vec!["^phantom$".to_string()],
+            TraceBackend::Memory,
+            &env,
+        );
+        ZkWasmLoader::new(MIN_K, env)
+            .unwrap()
+            .compile(&module, &mut monitor)
+            .unwrap();
+
+        monitor.phantom_functions().resolve()
+    };
+
+    let resolved = resolve("(i32.const 1)", "a");
+    assert_eq!(resolved.len(), 1);
+    assert_eq!((resolved[0].name.as_str(), resolved[0].fid), ("phantom", 0));
+    assert_eq!(resolve("(i32.const 1)", "a"), resolved);
+
+    // The pattern is unchanged but the replaced body or function differs.
+    assert_ne!(resolve("(i32.const 3)", "a"), resolved);
+    assert_ne!(resolve("(i32.const 1)", "b"), resolved);
+}
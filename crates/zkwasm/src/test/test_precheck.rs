@@ -0,0 +1,26 @@
+mod tests {
+    use crate::loader::ZkWasmLoader;
+
+    #[test]
+    fn test_precheck_rejects_float() {
+        let textual_repr = r#"
+        (module
+            (func (export "zkmain")
+              (drop (i32.reinterpret_f32 (f32.convert_i32_s (i32.const 1))))
+            )
+           )
+        "#;
+
+        let wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
+        assert!(ZkWasmLoader::parse_module(&wasm).is_err());
+
+        let textual_repr = r#"
+        (module
+            (func (export "zkmain") (param f64))
+           )
+        "#;
+
+        let wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
+        assert!(ZkWasmLoader::parse_module(&wasm).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use specs::TableBackend;
+use specs::Tables;
+use specs::TraceBackend;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::circuits::etable::EVENT_TABLE_ENTRY_ROWS;
+use crate::profile::flamegraph::FlameGraph;
+use crate::profile::flamegraph::FrameWeight;
+use crate::profile::instruction_statistic::InstructionStatistic;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::monitor::WasmiMonitor;
+
+fn execute() -> (Tables, HashMap<u32, String>) {
+    let textual_repr = r#"
+    (module
+        (memory 1)
+        (func $store
+          (i32.store (i32.const 0) (i32.const 1))
+        )
+        (func (export "zkmain")
+          (call $store)
+          (drop (i32.and (i32.const 3) (i32.const 1)))
+        )
+       )
+    "#;
+
+    let (_, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+
+    let function_names = monitor.expose_observer().borrow().function_names.clone();
+
+    (monitor.into_tables(), function_names)
+}
+
+#[test]
+fn test_profile_instruction() {
+    let (tables, function_names) = execute();
+    let report = tables.profile_instruction(&function_names).unwrap();
+
+    assert_eq!(
+        report.total.etable_rows,
+        report.total.instructions * EVENT_TABLE_ENTRY_ROWS as usize
+    );
+    assert_eq!(report.by_opcode_class["Call"].frame_table_entries, 1);
+    assert!(report.by_opcode_class["Store"].mtable_writes >= 1);
+    assert_eq!(report.by_opcode_class["BinBit"].bit_table_ops, 1);
+    assert_eq!(
+        report
+            .by_function
+            .values()
+            .map(|cost| cost.instructions)
+            .sum::<usize>(),
+        report.total.instructions
+    );
+}
+
+#[test]
+fn test_flamegraph() {
+    let (tables, function_names) = execute();
+    let instructions = tables
+        .profile_instruction(&function_names)
+        .unwrap()
+        .total
+        .instructions;
+
+    let mut folded = vec![];
+    tables
+        .write_folded_stacks(&function_names, FrameWeight::EtableRows, &mut folded)
+        .unwrap();
+    let folded = String::from_utf8(folded).unwrap();
+
+    let weights = folded
+        .lines()
+        .map(|line| {
+            let (stack, weight) = line.rsplit_once(' ').unwrap();
+            (stack.to_string(), weight.parse::<usize>().unwrap())
+        })
+        .collect::<HashMap<_, _>>();
+
+    assert_eq!(
+        weights.values().sum::<usize>(),
+        instructions * EVENT_TABLE_ENTRY_ROWS as usize
+    );
+    assert!(weights.keys().any(|stack| stack.split(';').count() == 2));
+
+    let mut chrome = vec![];
+    tables
+        .write_chrome_trace(&function_names, FrameWeight::MemoryOps, &mut chrome)
+        .unwrap();
+    let events: Vec<serde_json::Value> = serde_json::from_slice(&chrome).unwrap();
+
+    assert_eq!(
+        events.iter().filter(|event| event["ph"] == "B").count(),
+        events.iter().filter(|event| event["ph"] == "E").count()
+    );
+}
+
+#[test]
+fn test_profile_unreadable_trace() {
+    let (mut tables, function_names) = execute();
+    tables.execution_tables.etable = vec![TableBackend::File(PathBuf::from(
+        "/nonexistent/etable.json",
+    ))];
+
+    assert!(tables.profile_instruction(&function_names).is_err());
+    assert!(tables
+        .write_folded_stacks(
+            &function_names,
+            FrameWeight::EtableRows,
+            &mut Vec::<u8>::new()
+        )
+        .is_err());
+    assert!(tables
+        .write_chrome_trace(
+            &function_names,
+            FrameWeight::MemoryOps,
+            &mut Vec::<u8>::new()
+        )
+        .is_err());
+}
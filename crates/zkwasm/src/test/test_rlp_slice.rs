@@ -155,6 +155,7 @@ fn test_slices() -> Result<()> {
             public_inputs,
             private_inputs,
             context_inputs: vec![],
+            indexed_inputs: vec![],
             context_outputs: ContextOutput::default(),
         },
         (),
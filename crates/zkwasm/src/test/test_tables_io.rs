@@ -0,0 +1,110 @@
+use std::fs;
+
+use halo2_proofs::pairing::bn256::Fr;
+use specs::etable::EventTable;
+use specs::TableBackend;
+use specs::Tables;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::loader::slice::Slices;
+use crate::runtime::host::default_env::ExecutionArg;
+
+#[test]
+fn test_tables_roundtrip() {
+    let textual_repr = r#"
+    (module
+        (import "env" "wasm_input" (func $wasm_input (param i32) (result i64)))
+        (memory 1 2)
+        (global $g (mut i64) (i64.const 5))
+        (data (i32.const 8) "\2a")
+        (table 1 funcref)
+        (elem (i32.const 0) $double)
+        (func $double (param i64) (result i64)
+          (i64.add (local.get 0) (local.get 0))
+        )
+        (func (export "zkmain")
+          (global.set $g
+            (call_indirect (param i64) (result i64)
+              (i64.add (call $wasm_input (i32.const 1)) (i64.load (i32.const 8)))
+              (i32.const 0)))
+          (i64.store (i32.const 16) (global.get $g))
+        )
+       )
+    "#;
+
+    let (execution_result, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg {
+            public_inputs: vec![3],
+            ..Default::default()
+        },
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+    let instances: Vec<Fr> = execution_result.public_inputs_and_outputs();
+
+    let tables = monitor.into_tables();
+
+    let dir = std::env::temp_dir().join("zkwasm-tables-roundtrip");
+    fs::create_dir_all(&dir).unwrap();
+    tables
+        .write(
+            &dir,
+            |slice| format!("etable.{}.data", slice),
+            |slice| format!("frame_table.{}.data", slice),
+        )
+        .unwrap();
+
+    let read = Tables::read(&dir).unwrap();
+
+    let compilation_tables = |tables: &Tables| {
+        let tables = &tables.compilation_tables;
+
+        let mut imtable = tables
+            .imtable
+            .entries()
+            .map(|entry| serde_json::to_string(entry).unwrap())
+            .collect::<Vec<_>>();
+        imtable.sort();
+
+        vec![
+            serde_json::to_string(&tables.itable).unwrap(),
+            imtable.join(","),
+            serde_json::to_string(&tables.br_table).unwrap(),
+            serde_json::to_string(&tables.elem_table).unwrap(),
+            serde_json::to_string(&tables.configure_table).unwrap(),
+            serde_json::to_string(&tables.initial_frame_table).unwrap(),
+            serde_json::to_string(&tables.initialization_state).unwrap(),
+        ]
+    };
+    assert_eq!(compilation_tables(&read), compilation_tables(&tables));
+
+    assert_eq!(
+        read.execution_tables.etable.len(),
+        tables.execution_tables.etable.len()
+    );
+    for (read, etable) in read
+        .execution_tables
+        .etable
+        .iter()
+        .zip(tables.execution_tables.etable.iter())
+    {
+        match (read, etable) {
+            (TableBackend::File(path), TableBackend::Memory(etable)) => assert_eq!(
+                serde_json::to_string(&EventTable::read(path).unwrap()).unwrap(),
+                serde_json::to_string(etable).unwrap()
+            ),
+            _ => unreachable!(),
+        }
+    }
+
+    // The circuit is built from the directory alone.
+    Slices::new(MIN_K, read)
+        .unwrap()
+        .mock_test_all(instances)
+        .unwrap();
+}
@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+
+use specs::etable::EventTableEntry;
+use specs::step::StepInfo;
+use specs::TableBackend;
+use specs::Tables;
+
+use super::trace_zkmain;
+use crate::runtime::trace_checker::TraceConsistency;
+
+fn trace() -> Tables {
+    let textual_repr = r#"
+    (module
+        (memory 1 2)
+        (global $counter (mut i32) (i32.const 0))
+        (data (i32.const 8) "\2a")
+        (func $add (param i32 i32) (result i32)
+          (i32.add (local.get 0) (local.get 1))
+        )
+        (func (export "zkmain")
+          (local i32)
+          (block
+            (loop
+              (global.set $counter (call $add (global.get $counter) (i32.const 1)))
+              (br_if 0 (i32.lt_u (global.get $counter) (i32.const 3)))
+            )
+          )
+          (i32.store (i32.const 13) (i32.load (i32.const 8)))
+          (local.set 0 (i32.load8_u (i32.const 13)))
+          (drop (memory.grow (i32.const 1)))
+          (drop (memory.grow (i32.const 1)))
+        )
+       )
+    "#;
+
+    trace_zkmain(textual_repr).unwrap()
+}
+
+// Tamper the first entry accepted by `f`, returns its eid.
+fn tamper(tables: &mut Tables, f: impl Fn(&mut EventTableEntry) -> bool) -> u32 {
+    match &mut tables.execution_tables.etable[0] {
+        TableBackend::Memory(etable) => etable
+            .entries_mut()
+            .iter_mut()
+            .find_map(|entry| f(entry).then_some(entry.eid))
+            .unwrap(),
+        TableBackend::File(_) => unreachable!(),
+    }
+}
+
+// Select, locals, globals, br_table and call_indirect on top of `trace`.
+fn trace_control_flow() -> Tables {
+    let textual_repr = r#"
+    (module
+        (memory 1)
+        (global $g (mut i64) (i64.const 1))
+        (type $t (func (param i32) (result i32)))
+        (table 1 funcref)
+        (elem (i32.const 0) $id)
+        (func $id (type $t)
+          (local.get 0)
+        )
+        (func (export "zkmain")
+          (local i32)
+          (local.set 0 (select (i32.const 1) (i32.const 2) (i32.const 0)))
+          (block
+            (block
+              (block
+                (br_table 0 1 2 (local.tee 0 (local.get 0)))
+              )
+              (global.set $g (i64.const 2))
+            )
+          )
+          (drop (call_indirect (type $t) (local.get 0) (i32.const 0)))
+          (drop (memory.size))
+          (drop (global.get $g))
+        )
+       )
+    "#;
+
+    trace_zkmain(textual_repr).unwrap()
+}
+
+fn assert_consistent(tables: &Tables) {
+    let inconsistencies = tables.check_consistency().unwrap();
+
+    assert!(
+        inconsistencies.is_empty(),
+        "{}",
+        inconsistencies
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+}
+
+#[test]
+fn test_trace_checker_consistent() {
+    assert_consistent(&trace());
+    assert_consistent(&trace_control_flow());
+}
+
+#[test]
+fn test_trace_checker_tampered_step() {
+    let mut tables = trace();
+
+    let eid = tamper(&mut tables, |entry| match &mut entry.step_info {
+        StepInfo::I32BinOp { value, .. } => {
+            *value += 1;
+            true
+        }
+        _ => false,
+    });
+
+    let inconsistencies = tables.check_consistency().unwrap();
+    assert!(!inconsistencies.is_empty());
+    assert!(inconsistencies
+        .iter()
+        .any(|inconsistency| inconsistency.eid == Some(eid)));
+}
+
+#[test]
+fn test_trace_checker_tampered_memory() {
+    let mut tables = trace();
+
+    // The load reads a value the heap does not hold.
+    let eid = tamper(&mut tables, |entry| match &mut entry.step_info {
+        StepInfo::Load {
+            value,
+            block_value1,
+            ..
+        } => {
+            *value += 1;
+            *block_value1 += 1;
+            true
+        }
+        _ => false,
+    });
+
+    let inconsistencies = tables.check_consistency().unwrap();
+    assert!(inconsistencies
+        .iter()
+        .any(|inconsistency| inconsistency.eid == Some(eid)));
+}
+
+#[test]
+fn test_trace_checker_tampered_control_flow() {
+    let mut tables = trace();
+
+    // The callee runs in a frame other than the one created by the call.
+    let eid = tamper(&mut tables, |entry| {
+        let is_callee_entry = entry.iid == 0 && entry.last_jump_eid != 0;
+        if is_callee_entry {
+            entry.last_jump_eid += 1;
+        }
+
+        is_callee_entry
+    });
+
+    let inconsistencies = tables.check_consistency().unwrap();
+    assert!(inconsistencies
+        .iter()
+        .any(|inconsistency| inconsistency.eid == Some(eid)));
+}
+
+#[test]
+fn test_trace_checker_tampered_local() {
+    let mut tables = trace_control_flow();
+
+    let eid = tamper(&mut tables, |entry| match &mut entry.step_info {
+        StepInfo::GetLocal { depth, .. } => {
+            *depth += 1;
+            true
+        }
+        _ => false,
+    });
+
+    let inconsistencies = tables.check_consistency().unwrap();
+    assert!(inconsistencies
+        .iter()
+        .any(|inconsistency| inconsistency.eid == Some(eid)));
+}
+
+#[test]
+fn test_trace_checker_unexpected_step() {
+    let mut tables = trace_control_flow();
+
+    // A step of another kind than the instruction is never accepted.
+    let eid = tamper(&mut tables, |entry| {
+        let is_select = matches!(entry.step_info, StepInfo::Select { .. });
+        if is_select {
+            entry.step_info = StepInfo::Drop;
+        }
+
+        is_select
+    });
+
+    let inconsistencies = tables.check_consistency().unwrap();
+    assert!(inconsistencies
+        .iter()
+        .any(|inconsistency| inconsistency.eid == Some(eid)));
+}
+
+#[test]
+fn test_trace_checker_unreadable_trace() {
+    let mut tables = trace();
+    tables.execution_tables.etable = vec![TableBackend::File(PathBuf::from(
+        "/nonexistent/etable.json",
+    ))];
+
+    assert!(tables.check_consistency().is_err());
+}
@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::PathBuf;
+
+use specs::mtable::LocationType;
+use specs::step::StepInfo;
+use specs::TableBackend;
+use specs::Tables;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::trace_diff::diff_trace_dirs;
+use crate::runtime::trace_diff::diff_traces;
+
+// Doubles the public input in a callee and stores it, then loops as many times as the input.
+const TEXTUAL_REPR: &str = r#"
+    (module
+        (import "env" "wasm_input" (func $wasm_input (param i32) (result i64)))
+        (memory 1 1)
+        (func $double (param i64) (result i64)
+          (i64.add (local.get 0) (local.get 0))
+        )
+        (func (export "zkmain")
+          (local i64)
+          (local.set 0 (call $wasm_input (i32.const 1)))
+          (i64.store (i32.const 8) (call $double (local.get 0)))
+          (loop
+            (local.set 0 (i64.sub (local.get 0) (i64.const 1)))
+            (br_if 0 (i64.gt_s (local.get 0) (i64.const 0)))
+          )
+        )
+       )
+    "#;
+
+fn trace(input: u64) -> Tables {
+    let (_, monitor) = run_zkmain(
+        MIN_K,
+        TEXTUAL_REPR,
+        ExecutionArg {
+            public_inputs: vec![input],
+            ..Default::default()
+        },
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+
+    monitor.into_tables()
+}
+
+fn write(tables: &Tables, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("zkwasm-trace-diff-{}", name));
+    fs::create_dir_all(&dir).unwrap();
+    tables
+        .write(
+            &dir,
+            |slice| format!("etable.{}.data", slice),
+            |slice| format!("frame_table.{}.data", slice),
+        )
+        .unwrap();
+
+    dir
+}
+
+#[test]
+fn test_identical_traces() {
+    let diff = diff_traces(&trace(3), &trace(3)).unwrap();
+
+    assert!(diff.first_divergence.is_none());
+    assert!(diff.opcode_class_deltas.is_empty());
+    assert_eq!(diff.steps_a, diff.steps_b);
+}
+
+#[test]
+fn test_first_divergence() {
+    let a = write(&trace(3), "a");
+    let b = write(&trace(5), "b");
+
+    let diff = diff_trace_dirs(&a, &b).unwrap();
+
+    // The host call reading the input is the first step that differs.
+    let divergence = diff.first_divergence.unwrap();
+    assert!(!divergence.control_flow);
+    assert!(divergence.realigned.is_none());
+
+    let (a, b) = (divergence.a.unwrap(), divergence.b.unwrap());
+    assert_eq!(a.location, b.location);
+    assert_eq!(a.location.depth, 0);
+    assert_eq!(a.host_call.unwrap().rets, vec![3]);
+    assert_eq!(b.host_call.unwrap().rets, vec![5]);
+    // The returned value is pushed onto the stack.
+    assert!(a
+        .memory_writes
+        .iter()
+        .any(|write| write.ltype == LocationType::Stack && write.value == 3));
+    assert!(b
+        .memory_writes
+        .iter()
+        .any(|write| write.ltype == LocationType::Stack && write.value == 5));
+    assert_eq!(
+        divergence
+            .fields
+            .iter()
+            .map(|field| field.field.as_str())
+            .collect::<Vec<_>>(),
+        vec!["ret_val"]
+    );
+
+    // Two more iterations of the loop.
+    assert!(diff.steps_b > diff.steps_a);
+    assert!(!diff.opcode_class_deltas.is_empty());
+    assert!(diff
+        .opcode_class_deltas
+        .iter()
+        .all(|delta| delta.b > delta.a));
+}
+
+#[test]
+fn test_realigned_divergence() {
+    let a = trace(3);
+    let mut b = trace(3);
+
+    // b skips the addition of the callee.
+    let skipped = match &mut b.execution_tables.etable[0] {
+        TableBackend::Memory(etable) => {
+            let index = etable
+                .entries()
+                .iter()
+                .position(|entry| matches!(entry.step_info, StepInfo::I64BinOp { .. }))
+                .unwrap();
+
+            etable.entries_mut().remove(index)
+        }
+        TableBackend::File(_) => unreachable!(),
+    };
+
+    let divergence = diff_traces(&a, &b).unwrap().first_divergence.unwrap();
+    assert!(divergence.control_flow);
+    assert_eq!(divergence.a.unwrap().eid, skipped.eid);
+
+    // a executes the addition, then both traces return from the callee.
+    let realigned = divergence.realigned.unwrap();
+    assert_eq!((realigned.skipped_a, realigned.skipped_b), (1, 0));
+    assert_eq!(realigned.location, divergence.b.unwrap().location);
+    assert_eq!(realigned.location.depth, 1);
+}
@@ -0,0 +1,84 @@
+use specs::mtable::LocationType;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::circuits::config::MIN_K;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::monitor::WasmiMonitor;
+use crate::runtime::trace_explorer::Breakpoint;
+use crate::runtime::trace_explorer::TraceExplorer;
+
+fn explore() -> TraceExplorer {
+    let textual_repr = r#"
+    (module
+        (memory 1)
+        (data (i32.const 8) "\2a")
+        (func $store (export "store") (param i32)
+          (i32.store (i32.const 16) (local.get 0))
+        )
+        (func (export "zkmain")
+          (call $store (i32.const 7))
+          (call $store (i32.load (i32.const 8)))
+        )
+       )
+    "#;
+
+    let (_, monitor) = run_zkmain(
+        MIN_K,
+        textual_repr,
+        ExecutionArg::default(),
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+
+    let function_names = monitor.expose_observer().borrow().function_names.clone();
+
+    TraceExplorer::new(&monitor.into_tables(), function_names).unwrap()
+}
+
+#[test]
+fn test_trace_explorer_step() {
+    let mut explorer = explore();
+    let len = explorer.len();
+
+    assert_eq!(explorer.step(-1), 0);
+    assert_eq!(explorer.step(len as isize + 1), len - 1);
+    assert_eq!(explorer.step(-(len as isize)), len - 1);
+
+    let eid = explorer.current().unwrap().eid;
+    explorer.step(2);
+    assert!(explorer.goto(eid));
+    assert_eq!(explorer.current().unwrap().eid, eid);
+}
+
+#[test]
+fn test_trace_explorer_breakpoints() {
+    let mut explorer = explore();
+    let store = explorer.find_function("store").unwrap();
+
+    explorer.add_breakpoint(Breakpoint::Function(store));
+    assert_eq!(explorer.resume(true), Some(Breakpoint::Function(store)));
+    assert_eq!(explorer.current().unwrap().fid, store);
+    // The argument is the top of the stack when the function is entered.
+    assert_eq!(explorer.stack(1)[0].1, Some(7));
+
+    explorer.clear_breakpoints();
+    explorer.add_breakpoint(Breakpoint::Memory(16));
+    assert_eq!(explorer.resume(true), Some(Breakpoint::Memory(16)));
+    explorer.step(1);
+    assert_eq!(explorer.memory(16), 7);
+
+    // The heap is initialized by the data segment.
+    assert_eq!(explorer.memory(8), 0x2a);
+
+    assert_eq!(explorer.resume(true), Some(Breakpoint::Memory(16)));
+    explorer.step(1);
+    assert_eq!(explorer.memory(16), 0x2a);
+
+    let eid = explorer.last_write(LocationType::Heap, 16 / 8).unwrap();
+    assert_eq!(explorer.current().unwrap().eid, eid + 1);
+
+    assert_eq!(explorer.resume(false), Some(Breakpoint::Memory(16)));
+    assert_eq!(explorer.current().unwrap().eid, eid);
+}
@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::PathBuf;
+
+use specs::etable::EventTable;
+use specs::etable::EVENT_TABLE_CHUNK_ENTRIES;
+use specs::jtable::FrameTable;
+use specs::trace_file::TraceFileError;
+use specs::TableBackend;
+use specs::Tables;
+use specs::TraceBackend;
+
+use super::run_zkmain;
+use crate::runtime::host::default_env::ExecutionArg;
+use crate::runtime::state::init_memory_table_diff;
+
+// Large enough for a slice to span several chunks.
+const K: u32 = 20;
+
+fn trace() -> Tables {
+    let textual_repr = r#"
+    (module
+        (memory 1 1)
+        (func (export "zkmain")
+          (i32.store (i32.const 0) (i32.add (i32.const 1) (i32.const 2)))
+        )
+       )
+    "#;
+
+    trace_with_backend(textual_repr, TraceBackend::Memory)
+}
+
+fn trace_with_backend(textual_repr: &str, backend: TraceBackend) -> Tables {
+    let (_, monitor) =
+        run_zkmain(K, textual_repr, ExecutionArg::default(), &vec![], backend).unwrap();
+
+    monitor.into_tables()
+}
+
+fn trace_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("zkwasm-trace-file-{}", name));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write_etable(dir: &PathBuf) -> (EventTable, PathBuf) {
+    let etable = match trace().execution_tables.etable.remove(0) {
+        TableBackend::Memory(etable) => etable,
+        TableBackend::File(_) => unreachable!(),
+    };
+
+    let path = dir.join("etable.bin");
+    etable.write(&path).unwrap();
+
+    (etable, path)
+}
+
+#[test]
+fn test_trace_file_roundtrip() {
+    let dir = trace_dir("roundtrip");
+    let (etable, path) = write_etable(&dir);
+
+    let read = EventTable::read(&path).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&etable).unwrap(),
+        serde_json::to_string(&read).unwrap()
+    );
+}
+
+#[test]
+fn test_trace_file_rejects_other_version() {
+    let dir = trace_dir("version");
+    let (_, path) = write_etable(&dir);
+
+    let mut buf = fs::read(&path).unwrap();
+    // The version follows the 8 bytes magic.
+    buf[8] = buf[8].wrapping_add(1);
+    fs::write(&path, buf).unwrap();
+
+    assert!(matches!(
+        EventTable::read(&path),
+        Err(TraceFileError::UnsupportedVersion { .. })
+    ));
+}
+
+#[test]
+fn test_trace_file_rejects_other_schema() {
+    let dir = trace_dir("schema");
+    let (_, path) = write_etable(&dir);
+
+    let mut buf = fs::read(&path).unwrap();
+    // The schema version follows the magic, the version and the kind.
+    buf[13] = buf[13].wrapping_add(1);
+    fs::write(&path, buf).unwrap();
+
+    assert!(matches!(
+        EventTable::read(&path),
+        Err(TraceFileError::SchemaMismatch(_))
+    ));
+}
+
+#[test]
+fn test_trace_file_rejects_corruption() {
+    let dir = trace_dir("corruption");
+    let (_, path) = write_etable(&dir);
+
+    let mut buf = fs::read(&path).unwrap();
+    // The first byte of the payload of the first chunk, after the 17 bytes header and the 41
+    // bytes chunk header.
+    buf[17 + 41] ^= 1;
+    fs::write(&path, buf).unwrap();
+
+    assert!(matches!(
+        EventTable::read(&path),
+        Err(TraceFileError::DigestMismatch(_))
+    ));
+}
+
+#[test]
+fn test_trace_file_rejects_oversized_chunk() {
+    let dir = trace_dir("oversized");
+    let (_, path) = write_etable(&dir);
+
+    let mut buf = fs::read(&path).unwrap();
+    // The length of the first chunk follows its tag, after the 17 bytes header.
+    buf[17 + 1..17 + 9].copy_from_slice(&u64::MAX.to_le_bytes());
+    fs::write(&path, buf).unwrap();
+
+    assert!(matches!(
+        EventTable::read(&path),
+        Err(TraceFileError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn test_trace_file_rejects_other_table() {
+    let dir = trace_dir("kind");
+    let (_, path) = write_etable(&dir);
+
+    assert!(matches!(
+        FrameTable::read(&path),
+        Err(TraceFileError::UnexpectedKind { .. })
+    ));
+
+    let legacy = dir.join("legacy.bin");
+    fs::write(&legacy, [0u8; 16]).unwrap();
+    assert!(matches!(
+        EventTable::read(&legacy),
+        Err(TraceFileError::NotATraceFile(_))
+    ));
+}
+
+#[test]
+fn test_streamed_trace() {
+    // Runs for more than a chunk of entries.
+    let textual_repr = format!(
+        r#"
+        (module
+            (memory 1 1)
+            (func (export "zkmain")
+              (local i32)
+              (loop
+                (i32.store (i32.and (local.get 0) (i32.const 0xff)) (local.get 0))
+                (local.set 0 (i32.add (local.get 0) (i32.const 1)))
+                (br_if 0 (i32.lt_u (local.get 0) (i32.const {})))
+              )
+            )
+           )
+        "#,
+        EVENT_TABLE_CHUNK_ENTRIES / 8
+    );
+
+    let dir = trace_dir("stream");
+    let backend = {
+        let etable_dir = dir.clone();
+        let frame_table_dir = dir.clone();
+
+        TraceBackend::File {
+            event_table_path: Box::new(move |slice| {
+                etable_dir.join(format!("etable.{}.bin", slice))
+            }),
+            frame_table_writer: Box::new(move |slice, frame_table: &FrameTable| {
+                let path = frame_table_dir.join(format!("frame_table.{}.bin", slice));
+                frame_table.write(&path).unwrap();
+                path
+            }),
+        }
+    };
+
+    let streamed = trace_with_backend(&textual_repr, backend);
+    let in_memory = trace_with_backend(&textual_repr, TraceBackend::Memory);
+
+    assert_eq!(
+        streamed.execution_tables.etable.len(),
+        in_memory.execution_tables.etable.len()
+    );
+
+    for (streamed, in_memory) in streamed
+        .execution_tables
+        .etable
+        .iter()
+        .zip(in_memory.execution_tables.etable.iter())
+    {
+        let (path, in_memory) = match (streamed, in_memory) {
+            (TableBackend::File(path), TableBackend::Memory(etable)) => (path, etable),
+            _ => unreachable!(),
+        };
+
+        let etable = EventTable::read(path).unwrap();
+        assert!(etable.entries().len() > EVENT_TABLE_CHUNK_ENTRIES);
+        assert_eq!(
+            serde_json::to_string(&etable).unwrap(),
+            serde_json::to_string(in_memory).unwrap()
+        );
+
+        assert_eq!(
+            EventTable::read_first_entry(path).unwrap().unwrap().eid,
+            in_memory.entries()[0].eid
+        );
+        assert_eq!(
+            EventTable::read_external_host_call_table(path)
+                .unwrap()
+                .unwrap()
+                .entries()
+                .len(),
+            in_memory.filter_external_host_call_table().entries().len()
+        );
+
+        let mut memory_writes = EventTable::read_memory_writes(path)
+            .unwrap()
+            .unwrap()
+            .entries()
+            .map(|entry| (entry.ltype, entry.offset, entry.value, entry.eid))
+            .collect::<Vec<_>>();
+        let mut expected = init_memory_table_diff(in_memory)
+            .entries()
+            .map(|entry| (entry.ltype, entry.offset, entry.value, entry.eid))
+            .collect::<Vec<_>>();
+        memory_writes.sort();
+        expected.sort();
+        assert_eq!(memory_writes, expected);
+    }
+}
@@ -2,9 +2,16 @@ use specs::external_host_call_table::ExternalHostCallSignature;
 use std::rc::Rc;
 
 use crate::circuits::config::MIN_K;
+use crate::loader::slice::Slices;
 use crate::runtime::host::host_env::HostEnv;
 use crate::runtime::host::ForeignContext;
 use crate::runtime::host::ForeignStatics;
+use crate::test::execute_with_env;
+use crate::test::test_circuit_with_host_env;
+use halo2_proofs::pairing::bn256::Fr;
+use specs::step::StepInfo;
+use specs::TableBackend;
+use specs::TraceBackend;
 
 #[derive(Default)]
 struct Context {
@@ -81,3 +88,148 @@ fn test_call_host_external() {
     let _wasm = wabt::wat2wasm(&textual_repr).expect("failed to parse wat");
     // test_circuit_with_env(env, wasm, "test".to_string()).unwrap();
 }
+
+fn buffer_env() -> HostEnv {
+    let mut env = HostEnv::new(MIN_K);
+
+    let foreign_playground_plugin = env
+        .external_env
+        .register_plugin("foreign_playground", Box::new(Context::default()));
+    env.external_env.register_function(
+        "foreign_push_buffer",
+        0,
+        ExternalHostCallSignature::ArgumentBuffer,
+        foreign_playground_plugin.clone(),
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Context>().unwrap();
+
+                let value: u64 = args.nth(0);
+                context.acc += value;
+
+                None
+            },
+        ),
+    );
+    env.external_env.register_function(
+        "foreign_pop_buffer",
+        1,
+        ExternalHostCallSignature::ReturnBuffer,
+        foreign_playground_plugin,
+        Rc::new(
+            |_obs, context: &mut dyn ForeignContext, _args: wasmi::RuntimeArgs| {
+                let context = context.downcast_mut::<Context>().unwrap();
+
+                Some(wasmi::RuntimeValue::I64(context.acc as i64))
+            },
+        ),
+    );
+
+    env.finalize();
+
+    env
+}
+
+fn buffer_wasm(push_address: u32, push_len: u32) -> Vec<u8> {
+    let textual_repr = format!(
+        r#"
+        (module
+            (type (;0;) (func (param i32 i32)))
+            (type (;1;) (func (result i64)))
+            (import "env" "foreign_push_buffer" (func (;0;) (type 0)))
+            (import "env" "foreign_pop_buffer" (func (;1;) (type 0)))
+            (func (;2;) (type 1) (result i64)
+              i32.const 0
+              i64.const 5
+              i64.store
+              i32.const 8
+              i64.const 10
+              i64.store
+              i32.const {}
+              i32.const {}
+              call 0
+              i32.const 16
+              i32.const 8
+              call 1
+              i32.const 16
+              i64.load)
+            (memory (;0;) 1)
+            (export "memory" (memory 0))
+            (export "test" (func 2)))
+        "#,
+        push_address, push_len
+    );
+
+    wabt::wat2wasm(&textual_repr).expect("failed to parse wat")
+}
+
+#[test]
+fn test_call_host_external_buffer() {
+    test_circuit_with_host_env(MIN_K, buffer_env(), buffer_wasm(0, 16), "test".to_string())
+        .unwrap();
+}
+
+#[test]
+fn test_call_host_external_buffer_tampered() {
+    let (execution_result, mut tables) = execute_with_env(
+        MIN_K,
+        buffer_env(),
+        &buffer_wasm(0, 16),
+        "test",
+        &vec![],
+        TraceBackend::Memory,
+    )
+    .unwrap();
+
+    // Pass a word to the host other than the one stored in the buffer.
+    match &mut tables.execution_tables.etable[0] {
+        TableBackend::Memory(etable) => {
+            let entry = etable
+                .entries_mut()
+                .iter_mut()
+                .find(|entry| {
+                    matches!(
+                        entry.step_info,
+                        StepInfo::ExternalHostCallBuffer {
+                            sig: ExternalHostCallSignature::ArgumentBuffer,
+                            ..
+                        }
+                    )
+                })
+                .unwrap();
+
+            if let StepInfo::ExternalHostCallBuffer { values, .. } = &mut entry.step_info {
+                values[0] += 1;
+            }
+        }
+        TableBackend::File(_) => unreachable!(),
+    }
+
+    assert!(Slices::<Fr>::new(MIN_K, tables)
+        .unwrap()
+        .mock_test_all(execution_result.public_inputs_and_outputs())
+        .is_err());
+}
+
+#[test]
+fn test_call_host_external_buffer_traps() {
+    let run = |address, len| {
+        execute_with_env(
+            MIN_K,
+            buffer_env(),
+            &buffer_wasm(address, len),
+            "test",
+            &vec![],
+            TraceBackend::Memory,
+        )
+    };
+
+    // Unaligned buffer.
+    assert!(run(4, 16).is_err());
+    // More words than a buffer host call transfers.
+    assert!(run(0, 64).is_err());
+    // The end of the buffer overflows the address space.
+    assert!(run(0xfffffff8, 16).is_err());
+    // The end of the buffer is out of the linear memory.
+    assert!(run(0xfff8, 16).is_err());
+}